@@ -1,11 +1,34 @@
 //! Headless Chrome browser management via chromiumoxide
 
+use crate::cookies::Cookie;
 use anyhow::{Context, Result};
+use base64::Engine;
+use chromiumoxide::cdp::browser_protocol::fetch::{
+    ContinueRequestParams, EnableParams as FetchEnableParams, EventRequestPaused,
+    FailRequestParams, HeaderEntry, RequestPattern, RequestStage,
+};
+use chromiumoxide::cdp::browser_protocol::network::{
+    CookieParam, ErrorReason, EventRequestWillBeSent, EventResponseReceived, GetResponseBodyParams,
+    Headers, Request, ResourceType, SetCookiesParams, TimeSinceEpoch,
+};
+use chromiumoxide::cdp::browser_protocol::page::{EventLifecycleEvent, FrameId};
+use chromiumoxide::error::CdpError;
+use chromiumoxide::listeners::EventStream;
 use chromiumoxide::{Browser, BrowserConfig, Page};
-use futures::StreamExt;
+use chrono::{DateTime, Utc};
+use clap::ValueEnum;
+use futures::{FutureExt, StreamExt};
+use regex::Regex;
+use scraper::{Html, Selector};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::future::Future;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::Arc;
-use tokio::sync::Semaphore;
+use std::time::{Duration, Instant};
+use tokio::sync::{RwLock, Semaphore};
+use tracing::debug;
 
 /// Auto-detect Chrome/Chromium executable path based on OS
 fn detect_chrome_path() -> Option<PathBuf> {
@@ -49,115 +72,760 @@ fn detect_chrome_path() -> Option<PathBuf> {
     None
 }
 
+/// Default user agent (desktop Chrome on macOS)
+pub const DEFAULT_USER_AGENT: &str = "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36";
+
+/// `--user-agent` value that uses the running Chrome's own version
+pub const CHROME_LATEST: &str = "chrome-latest";
+
+/// Resource types not needed for text extraction, blocked unless `--load-resources`
+const BLOCKED_RESOURCES: [ResourceType; 4] = [
+    ResourceType::Image,
+    ResourceType::Media,
+    ResourceType::Font,
+    ResourceType::Stylesheet,
+];
+
+/// Crash relaunches allowed over the lifetime of a pool
+const MAX_RELAUNCHES: u32 = 3;
+
+/// Longest Retry-After honored on a 429 before retrying
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(60);
+
+/// `PageResult::error` when navigation exceeds its timeout
+const NAVIGATION_TIMEOUT: &str = "Navigation timeout";
+
+/// How often to re-check a bot challenge page while waiting for it to clear
+const CHALLENGE_POLL: Duration = Duration::from_millis(500);
+
+/// Titles of Cloudflare-style "checking your browser" interstitials (lowercase)
+const CHALLENGE_TITLES: [&str; 2] = ["just a moment...", "just a moment…"];
+
+/// Markup only the interstitial itself has (matched lowercase). The
+/// challenge-platform script path and `_cf_chl` also appear in Cloudflare's
+/// bot-detection script on ordinary pages, so they don't count.
+const CHALLENGE_MARKERS: [&str; 4] = [
+    "id=\"challenge-form\"",
+    "id=\"challenge-stage\"",
+    "cf-browser-verification",
+    "window._cf_chl_opt",
+];
+
 /// Browser pool configuration
 pub struct BrowserPool {
-    browser: Browser,
+    /// Replaced with a fresh instance if Chrome crashes
+    browser: RwLock<Browser>,
+    /// Times Chrome was relaunched after a crash
+    relaunches: AtomicU32,
     semaphore: Arc<Semaphore>,
     user_agent: String,
+    /// Accept-Language header and navigator.language (None = Chrome's own)
+    accept_language: Option<String>,
+    cookies: Vec<CookieParam>,
+    /// Basic auth, added to each request for its exact host
+    auth: Arc<Vec<HostAuth>>,
+    /// Load images, media, fonts and stylesheets (blocked by default)
+    load_resources: bool,
+    /// How long to wait for a bot challenge page to solve itself
+    challenge_wait: Duration,
+    /// Lifecycle event that ends navigation
+    wait_until: WaitUntil,
+    /// Attached to an external Chrome (don't shut it down on close)
+    connected: bool,
+    /// Extra Chrome flags, reused when relaunching after a crash
+    launch_args: Vec<String>,
 }
 
 impl BrowserPool {
     /// Create a new browser pool with concurrency limit
     pub async fn new(concurrency: usize) -> Result<Self> {
-        let chrome_path = detect_chrome_path().ok_or_else(|| {
-            anyhow::anyhow!(
-                "Chrome/Chromium not found. Searched paths:\n  \
-                 Linux: /usr/bin/google-chrome-stable, /usr/bin/google-chrome, /usr/bin/chromium-browser, /usr/bin/chromium\n  \
-                 macOS: /Applications/Google Chrome.app/...\n  \
-                 Windows: C:\\Program Files\\Google\\Chrome\\Application\\chrome.exe\n\
-                 Please install Chrome or Chromium."
-            )
-        })?;
-
-        let config = BrowserConfig::builder()
-            .chrome_executable(chrome_path)
-            .no_sandbox()
-            .arg("--disable-gpu")
-            .arg("--disable-dev-shm-usage")
-            .arg("--disable-setuid-sandbox")
-            .arg("--no-first-run")
-            .arg("--headless=new")
-            .build()
-            .map_err(|e| anyhow::anyhow!("Browser config error: {}", e))?;
+        Self::launch_with(concurrency, Vec::new()).await
+    }
 
-        let (browser, mut handler) = Browser::launch(config)
+    /// Launch Chrome with extra command line flags
+    pub async fn launch_with(concurrency: usize, args: Vec<String>) -> Result<Self> {
+        Ok(Self {
+            launch_args: args.clone(),
+            ..Self::from_browser(launch(&args).await?, concurrency, false)
+        })
+    }
+
+    /// Attach to an already running Chrome via its DevTools URL
+    ///
+    /// Accepts a `ws://` debugger URL or an `http://host:port` endpoint. Pages are
+    /// opened in a private browser context that is disposed on close, leaving the
+    /// external browser running for the next invocation.
+    pub async fn connect(url: &str, concurrency: usize) -> Result<Self> {
+        let started = Instant::now();
+        let (mut browser, mut handler) = Browser::connect(url)
             .await
-            .context("Failed to launch Chrome")?;
+            .with_context(|| format!("Failed to connect to Chrome at {}", url))?;
 
-        // Spawn handler in background
         tokio::spawn(async move { while handler.next().await.is_some() {} });
 
-        Ok(Self {
-            browser,
+        browser
+            .start_incognito_context()
+            .await
+            .context("Failed to create browser context")?;
+        debug!(
+            url,
+            ms = started.elapsed().as_millis() as u64,
+            "chrome connected"
+        );
+
+        Ok(Self::from_browser(browser, concurrency, true))
+    }
+
+    /// Connect to a running Chrome if a URL is given, otherwise launch one
+    /// (behind `socks5` if given)
+    pub async fn launch_or_connect(
+        concurrency: usize,
+        connect: Option<&str>,
+        socks5: Option<&str>,
+    ) -> Result<Self> {
+        match connect {
+            Some(url) => Self::connect(url, concurrency).await,
+            None => match socks5 {
+                Some(addr) => Self::launch_with(concurrency, socks5_args(addr)).await,
+                None => Self::new(concurrency).await,
+            },
+        }
+    }
+
+    fn from_browser(browser: Browser, concurrency: usize, connected: bool) -> Self {
+        Self {
+            browser: RwLock::new(browser),
+            relaunches: AtomicU32::new(0),
             semaphore: Arc::new(Semaphore::new(concurrency)),
-            user_agent: "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36".to_string(),
-        })
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            accept_language: None,
+            cookies: Vec::new(),
+            auth: Arc::default(),
+            load_resources: false,
+            challenge_wait: Duration::ZERO,
+            wait_until: WaitUntil::default(),
+            connected,
+            launch_args: Vec::new(),
+        }
+    }
+
+    /// Install cookies in every new page before navigation
+    pub fn with_cookies(mut self, cookies: &[Cookie]) -> Self {
+        self.cookies = cookies.iter().map(cookie_param).collect();
+        self
     }
 
-    /// Get a new page with resource blocking
+    /// Send basic auth with requests to the listed hosts only
+    pub fn with_auth(mut self, auth: &[HostAuth]) -> Self {
+        self.auth = Arc::new(auth.to_vec());
+        self
+    }
+
+    /// Load images, media, fonts and stylesheets instead of blocking them
+    pub fn with_resources(mut self, load_resources: bool) -> Self {
+        self.load_resources = load_resources;
+        self
+    }
+
+    /// Wait up to `secs` for bot challenge pages to clear before reporting them
+    pub fn with_challenge_wait(mut self, secs: u64) -> Self {
+        self.challenge_wait = Duration::from_secs(secs);
+        self
+    }
+
+    /// Ask for pages in this language (BCP 47 tag, e.g. `de-DE`)
+    pub fn with_lang(mut self, lang: Option<&str>) -> Self {
+        self.accept_language = lang.map(accept_language);
+        self
+    }
+
+    /// Lifecycle event `goto` waits for
+    pub fn with_wait_until(mut self, wait_until: WaitUntil) -> Self {
+        self.wait_until = wait_until;
+        self
+    }
+
+    /// Override the user agent (`chrome-latest` = the browser's own, minus "Headless")
+    pub async fn with_user_agent(mut self, user_agent: Option<&str>) -> Result<Self> {
+        match user_agent {
+            None => {}
+            Some(CHROME_LATEST) => {
+                let native = self
+                    .browser
+                    .get_mut()
+                    .user_agent()
+                    .await
+                    .context("Failed to query Chrome version")?;
+                self.user_agent = chrome_latest_user_agent(&native);
+            }
+            Some(ua) => self.user_agent = ua.to_string(),
+        }
+        Ok(self)
+    }
+
+    /// Get a new page (images, media, fonts and stylesheets blocked unless `with_resources`)
     pub async fn new_page(&self) -> Result<BrowserPage> {
         let permit = self.semaphore.clone().acquire_owned().await?;
-        let page = self.browser.new_page("about:blank").await?;
+        let page = retry_after_crash(
+            &self.relaunches,
+            || async { self.browser.read().await.new_page("about:blank").await },
+            |seen| self.relaunch(seen),
+        )
+        .await?;
 
-        // Set user agent
+        // Set user agent, and the language for both the header and navigator.language
         page.execute(
-            chromiumoxide::cdp::browser_protocol::network::SetUserAgentOverrideParams::new(
-                &self.user_agent,
-            ),
+            chromiumoxide::cdp::browser_protocol::network::SetUserAgentOverrideParams {
+                accept_language: self.accept_language.clone(),
+                ..chromiumoxide::cdp::browser_protocol::network::SetUserAgentOverrideParams::new(
+                    &self.user_agent,
+                )
+            },
         )
         .await?;
 
+        // Set cookies for authenticated fetches
+        if !self.cookies.is_empty() {
+            page.execute(SetCookiesParams::new(self.cookies.clone()))
+                .await?;
+        }
+
+        if !self.load_resources || !self.auth.is_empty() {
+            intercept_requests(&page, !self.load_resources, Arc::clone(&self.auth)).await?;
+        }
+
         Ok(BrowserPage {
             page,
+            challenge_wait: self.challenge_wait,
+            wait_until: self.wait_until,
             _permit: permit,
         })
     }
 
-    /// Close the browser
-    pub async fn close(mut self) -> Result<()> {
-        self.browser.close().await?;
+    /// Replace a crashed Chrome, unless another page already did since `seen`
+    async fn relaunch(&self, seen: u32) -> Result<()> {
+        if self.connected {
+            anyhow::bail!("Lost connection to external Chrome");
+        }
+        let mut browser = self.browser.write().await;
+        if self.relaunches.load(Ordering::SeqCst) == seen {
+            *browser = launch(&self.launch_args)
+                .await
+                .context("Failed to relaunch Chrome")?;
+            self.relaunches.fetch_add(1, Ordering::SeqCst);
+        }
+        Ok(())
+    }
+
+    /// User agent string sent by browser pages
+    pub fn user_agent(&self) -> &str {
+        &self.user_agent
+    }
+
+    /// Close the browser (or just our context and tabs when attached to an external one)
+    pub async fn close(self) -> Result<()> {
+        let mut browser = self.browser.into_inner();
+        if self.connected {
+            browser.quit_incognito_context().await?;
+        } else {
+            browser.close().await?;
+        }
         Ok(())
     }
 }
 
+/// Run a browser call, relaunching Chrome once and retrying if it has crashed
+///
+/// `relaunch` gets the relaunch count seen before the failed attempt, so pages
+/// failing at the same time replace the browser only once.
+async fn retry_after_crash<T, A, AFut, R, RFut>(
+    relaunches: &AtomicU32,
+    mut attempt: A,
+    relaunch: R,
+) -> Result<T>
+where
+    A: FnMut() -> AFut,
+    AFut: Future<Output = Result<T, CdpError>>,
+    R: FnOnce(u32) -> RFut,
+    RFut: Future<Output = Result<()>>,
+{
+    let seen = relaunches.load(Ordering::SeqCst);
+    match attempt().await {
+        Err(e) if is_browser_gone(&e) => {
+            if seen >= MAX_RELAUNCHES {
+                return Err(e).context("Chrome crashed again, giving up after relaunch limit");
+            }
+            eprintln!("Warning: Chrome crashed ({}), relaunching", e);
+            relaunch(seen).await?;
+            Ok(attempt().await?)
+        }
+        result => Ok(result?),
+    }
+}
+
+/// Whether an error means the browser process or its connection is gone
+fn is_browser_gone(error: &CdpError) -> bool {
+    matches!(
+        error,
+        CdpError::Ws(_) | CdpError::Io(_) | CdpError::ChannelSendError(_) | CdpError::NoResponse
+    )
+}
+
+/// Validate a `--connect` value: a `ws://` debugger URL or `http://host:port`
+pub fn parse_connect(s: &str) -> Result<String, String> {
+    let url =
+        url::Url::parse(s).map_err(|_| "expected ws://... or http://host:port".to_string())?;
+    if !matches!(url.scheme(), "ws" | "wss" | "http" | "https") || url.host_str().is_none() {
+        return Err("expected ws://... or http://host:port".to_string());
+    }
+    Ok(s.to_string())
+}
+
+/// Chrome flags sending all traffic through a SOCKS5 proxy at `host:port`
+///
+/// Chrome resolves hostnames on the proxy for `socks5://`; the resolver rule
+/// makes any local lookup fail rather than leak, except for the proxy itself.
+pub fn socks5_args(addr: &str) -> Vec<String> {
+    let addr = addr.trim_start_matches("socks5://");
+    let host = addr.rsplit_once(':').map_or(addr, |(host, _)| host);
+    vec![
+        format!("--proxy-server=socks5://{}", addr),
+        format!("--host-resolver-rules=MAP * ~NOTFOUND , EXCLUDE {}", host),
+    ]
+}
+
+/// Client builder for requests made outside Chrome (PDFs, robots.txt,
+/// sitemaps, DOI lookups), sent through the same `--socks5` proxy if any;
+/// `socks5h` resolves hostnames on the proxy, as Chrome does
+pub fn http_client(socks5: Option<&str>) -> Result<reqwest::ClientBuilder> {
+    let builder = reqwest::Client::builder();
+    let Some(addr) = socks5 else {
+        return Ok(builder);
+    };
+    let addr = addr.trim_start_matches("socks5://");
+    let proxy = reqwest::Proxy::all(format!("socks5h://{}", addr))
+        .with_context(|| format!("Invalid SOCKS5 proxy: {}", addr))?;
+    Ok(builder.proxy(proxy))
+}
+
+/// Launch a local headless Chrome with `args` added to the defaults
+async fn launch(args: &[String]) -> Result<Browser> {
+    let chrome_path = detect_chrome_path().ok_or_else(|| {
+        anyhow::anyhow!(
+            "Chrome/Chromium not found. Searched paths:\n  \
+             Linux: /usr/bin/google-chrome-stable, /usr/bin/google-chrome, /usr/bin/chromium-browser, /usr/bin/chromium\n  \
+             macOS: /Applications/Google Chrome.app/...\n  \
+             Windows: C:\\Program Files\\Google\\Chrome\\Application\\chrome.exe\n\
+             Please install Chrome or Chromium."
+        )
+    })?;
+
+    let config = BrowserConfig::builder()
+        .chrome_executable(chrome_path)
+        .no_sandbox()
+        .arg("--disable-gpu")
+        .arg("--disable-dev-shm-usage")
+        .arg("--disable-setuid-sandbox")
+        .arg("--no-first-run")
+        .arg("--headless=new")
+        .args(args)
+        .build()
+        .map_err(|e| anyhow::anyhow!("Browser config error: {}", e))?;
+
+    let started = Instant::now();
+    let (browser, mut handler) = Browser::launch(config)
+        .await
+        .context("Failed to launch Chrome")?;
+    debug!(ms = started.elapsed().as_millis() as u64, "chrome launched");
+
+    // Spawn handler in background
+    tokio::spawn(async move { while handler.next().await.is_some() {} });
+
+    Ok(browser)
+}
+
+/// Fail requests for `BLOCKED_RESOURCES` (if `block`) and add `auth` headers,
+/// before requests are sent
+///
+/// The document itself is never blocked, so an image URL can still be fetched directly.
+/// Each redirect hop is paused as a request of its own, so credentials never
+/// follow a redirect to another host.
+async fn intercept_requests(page: &Page, block: bool, auth: Arc<Vec<HostAuth>>) -> Result<()> {
+    let mut paused = page.event_listener::<EventRequestPaused>().await?;
+    let patterns: Vec<_> = if auth.is_empty() {
+        BLOCKED_RESOURCES
+            .iter()
+            .map(|resource_type| {
+                RequestPattern::builder()
+                    .resource_type(resource_type.clone())
+                    .request_stage(RequestStage::Request)
+                    .build()
+            })
+            .collect()
+    } else {
+        vec![RequestPattern::builder()
+            .url_pattern("*")
+            .request_stage(RequestStage::Request)
+            .build()]
+    };
+    page.execute(FetchEnableParams::builder().patterns(patterns).build())
+        .await?;
+
+    // Ends when the page closes and its event stream does
+    let page = page.clone();
+    tokio::spawn(async move {
+        while let Some(event) = paused.next().await {
+            let id = event.request_id.clone();
+            if block && BLOCKED_RESOURCES.contains(&event.resource_type) {
+                let _ = page
+                    .execute(FailRequestParams::new(id, ErrorReason::BlockedByClient))
+                    .await;
+                continue;
+            }
+            let mut resume = ContinueRequestParams::new(id);
+            resume.headers = auth_headers(&event.request, &auth);
+            let _ = page.execute(resume).await;
+        }
+    });
+    Ok(())
+}
+
+/// The request's headers plus `Authorization`, when its host has --auth
+/// credentials (None = send the request unchanged)
+fn auth_headers(request: &Request, auth: &[HostAuth]) -> Option<Vec<HeaderEntry>> {
+    let value = auth_header(auth, &request.url)?;
+    let mut headers: Vec<HeaderEntry> = request
+        .headers
+        .inner()
+        .as_object()
+        .into_iter()
+        .flatten()
+        .filter(|(name, _)| !name.eq_ignore_ascii_case("authorization"))
+        .filter_map(|(name, value)| Some(HeaderEntry::new(name, value.as_str()?)))
+        .collect();
+    headers.push(HeaderEntry::new("Authorization", value));
+    Some(headers)
+}
+
+/// Basic auth credentials for one host (--auth)
+#[derive(Clone)]
+pub struct HostAuth {
+    host: String,
+    user: String,
+    /// `Authorization` header value
+    header: String,
+}
+
+/// Never prints the password
+impl std::fmt::Debug for HostAuth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}={}:***", self.host, self.user)
+    }
+}
+
+/// Parse `host=user:pass`; errors never echo the value back
+pub fn parse_host_auth(value: &str) -> Result<HostAuth, String> {
+    let invalid = || "expected HOST=USER:PASS".to_string();
+    let (host, credentials) = value.split_once('=').ok_or_else(invalid)?;
+    let (user, _) = credentials.split_once(':').ok_or_else(invalid)?;
+    if host.is_empty() || user.is_empty() {
+        return Err(invalid());
+    }
+    Ok(HostAuth {
+        host: host.to_lowercase(),
+        user: user.to_string(),
+        header: format!(
+            "Basic {}",
+            base64::engine::general_purpose::STANDARD.encode(credentials)
+        ),
+    })
+}
+
+/// `Authorization` header for a URL whose host exactly matches an --auth entry
+fn auth_header<'a>(auth: &'a [HostAuth], url: &str) -> Option<&'a str> {
+    let url = url::Url::parse(url).ok()?;
+    let host = url.host_str()?;
+    auth.iter()
+        .find(|a| a.host == host)
+        .map(|a| a.header.as_str())
+}
+
+/// Accept-Language value for a tag, falling back to its base language (`de-DE` -> `de-DE,de;q=0.9`)
+pub fn accept_language(tag: &str) -> String {
+    match tag.split_once('-') {
+        Some((base, _)) => format!("{},{};q=0.9", tag, base),
+        None => tag.to_string(),
+    }
+}
+
+/// Validate a BCP 47 language tag (`en`, `de-DE`, `zh-Hant-TW`) for --lang
+pub fn parse_lang(tag: &str) -> Result<String, String> {
+    let re = Regex::new(r"^[A-Za-z]{2,8}(?:-[A-Za-z0-9]{1,8})*$").unwrap();
+    if re.is_match(tag) {
+        Ok(tag.to_string())
+    } else {
+        Err("expected a language tag like en, de-DE or pt-BR".to_string())
+    }
+}
+
+/// Page lifecycle event that ends navigation (--wait-until)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum WaitUntil {
+    /// HTML parsed; images and late scripts may still be loading
+    #[value(name = "domcontentloaded")]
+    DomContentLoaded,
+    /// The load event (subresources finished)
+    #[default]
+    Load,
+    /// Load, then no network requests for 500ms
+    #[value(name = "networkidle")]
+    NetworkIdle,
+}
+
+impl WaitUntil {
+    /// Chrome `Page.lifecycleEvent` names that must all have fired
+    fn events(self) -> &'static [&'static str] {
+        match self {
+            Self::DomContentLoaded => &["DOMContentLoaded"],
+            Self::Load => &["load"],
+            Self::NetworkIdle => &["load", "networkIdle"],
+        }
+    }
+}
+
+/// Tracks the main frame's lifecycle events for one navigation
+#[derive(Debug)]
+struct LifecycleWatch {
+    wait_until: WaitUntil,
+    /// Seen `init`, so later events belong to the new document
+    started: bool,
+    seen: HashSet<String>,
+}
+
+impl LifecycleWatch {
+    fn new(wait_until: WaitUntil) -> Self {
+        Self {
+            wait_until,
+            started: false,
+            seen: HashSet::new(),
+        }
+    }
+
+    /// Record an event; true once everything `wait_until` needs has fired
+    fn on_event(&mut self, name: &str) -> bool {
+        if name == "init" {
+            // A new document (also after a redirect): start over
+            self.started = true;
+            self.seen.clear();
+        } else if self.started {
+            self.seen.insert(name.to_string());
+        }
+        self.started
+            && self
+                .wait_until
+                .events()
+                .iter()
+                .all(|event| self.seen.contains(*event))
+    }
+}
+
 /// A browser page with automatic permit release
 pub struct BrowserPage {
     page: Page,
+    challenge_wait: Duration,
+    wait_until: WaitUntil,
     _permit: tokio::sync::OwnedSemaphorePermit,
 }
 
 impl BrowserPage {
-    /// Navigate to URL and wait for DOM content loaded
+    /// Navigate to URL and wait for the `wait_until` lifecycle event
     pub async fn goto(&self, url: &str, timeout_ms: u64) -> Result<PageResult> {
+        // Capture responses to learn the document's content type, requests for redirect hops
+        let mut responses = self.page.event_listener::<EventResponseReceived>().await?;
+        let mut requests = self.page.event_listener::<EventRequestWillBeSent>().await?;
+
+        let started = Instant::now();
         let nav_result = tokio::time::timeout(
             std::time::Duration::from_millis(timeout_ms),
-            self.page.goto(url),
+            self.navigate(url),
         )
         .await;
+        let ms = started.elapsed().as_millis() as u64;
 
         match nav_result {
             Ok(Ok(_)) => {
-                let status = self.get_status().await;
+                debug!(url, ms, "navigated");
+                let document = document_response(&mut responses);
+                let raw = document
+                    .as_ref()
+                    .is_some_and(|d| is_raw_content_type(&d.response.mime_type));
+                // Cloudflare labels its interstitial responses
+                let mitigated = document.as_ref().is_some_and(|d| {
+                    header(&d.response.headers, "cf-mitigated")
+                        .is_some_and(|v| v.eq_ignore_ascii_case("challenge"))
+                });
+                let challenge = !raw && self.challenge_after_wait(url, mitigated).await;
+                let mut status = self.get_status().await;
                 let title = self.page.get_title().await.ok().flatten();
+                let main_frame = self.page.mainframe().await.ok().flatten();
+                let mut redirects = redirect_hops(&mut requests, main_frame.as_ref());
+                if let (false, Some(document)) = (redirects.is_empty(), &document) {
+                    redirects.push(RedirectHop {
+                        url: document.response.url.clone(),
+                        status: document.response.status as u16,
+                    });
+                }
+                // Chrome wraps JSON/text/XML in a viewer page; keep the body as sent
+                let body = match &document {
+                    Some(d) if raw => self
+                        .response_bytes(d)
+                        .await
+                        .map(|bytes| String::from_utf8_lossy(&bytes).into_owned()),
+                    _ => None,
+                };
+                // A PDF is already downloaded; no need to fetch it again outside Chrome
+                let pdf = match &document {
+                    Some(d) if d.response.mime_type == "application/pdf" => {
+                        self.response_bytes(d).await
+                    }
+                    _ => None,
+                };
+                // Rate limited: the real status, and how long the server wants us to wait
+                let mut retry_after = None;
+                if let Some(d) = document.as_ref().filter(|d| d.response.status == 429) {
+                    status = 429;
+                    retry_after = header(&d.response.headers, "retry-after")
+                        .and_then(|v| parse_retry_after(&v, Utc::now()));
+                    debug!(url, ?retry_after, "rate limited");
+                }
                 Ok(PageResult {
                     status,
                     title,
                     error: None,
+                    content_type: document.map(|d| d.response.mime_type.clone()),
+                    redirects,
+                    body,
+                    pdf,
+                    retry_after,
+                    challenge,
                 })
             }
             Ok(Err(e)) => {
+                debug!(url, ms, error = %e, "navigation failed");
                 let (status, _) = parse_error(&e.to_string());
                 Ok(PageResult {
                     status,
                     title: None,
                     error: Some(e.to_string()),
+                    content_type: None,
+                    redirects: Vec::new(),
+                    body: None,
+                    pdf: None,
+                    retry_after: None,
+                    challenge: false,
+                })
+            }
+            Err(_) => {
+                debug!(url, ms, timeout_ms, "navigation timed out");
+                Ok(PageResult {
+                    status: 0,
+                    title: None,
+                    error: Some(NAVIGATION_TIMEOUT.to_string()),
+                    content_type: None,
+                    redirects: Vec::new(),
+                    body: None,
+                    pdf: None,
+                    retry_after: None,
+                    challenge: false,
                 })
             }
-            Err(_) => Ok(PageResult {
-                status: 0,
-                title: None,
-                error: Some("Navigation timeout".to_string()),
-            }),
+        }
+    }
+
+    /// `Page.navigate`, returning once the main frame reaches `wait_until`
+    ///
+    /// chromiumoxide's own `goto` resolves on `load`; DOMContentLoaded can
+    /// finish before it and networkIdle waits on after it.
+    async fn navigate(&self, url: &str) -> Result<()> {
+        if self.wait_until == WaitUntil::Load {
+            self.page.goto(url).await?;
+            return Ok(());
+        }
+        let mut lifecycle = self.page.event_listener::<EventLifecycleEvent>().await?;
+        let main_frame = self.page.mainframe().await?;
+        let mut watch = LifecycleWatch::new(self.wait_until);
+        let reached = async {
+            while let Some(event) = lifecycle.next().await {
+                let on_main_frame = main_frame.as_ref().is_none_or(|f| *f == event.frame_id);
+                if on_main_frame && watch.on_event(&event.name) {
+                    return;
+                }
+            }
+            std::future::pending().await
+        };
+
+        let goto = self
+            .page
+            .goto(url)
+            .map(|r| r.map(|_| ()).map_err(anyhow::Error::from));
+        tokio::pin!(goto, reached);
+        if self.wait_until == WaitUntil::NetworkIdle {
+            goto.await?;
+            reached.await;
+            return Ok(());
+        }
+        tokio::select! {
+            result = &mut goto => result,
+            () = &mut reached => {
+                // Chrome's own error page fires lifecycle events too; let goto report the error
+                let url = self.page.url().await.ok().flatten();
+                if url.is_some_and(|u| u.starts_with("chrome-error://")) {
+                    goto.await
+                } else {
+                    Ok(())
+                }
+            }
+        }
+    }
+
+    /// Whether a bot challenge is still showing after waiting up to `challenge_wait`
+    async fn challenge_after_wait(&self, url: &str, mitigated: bool) -> bool {
+        let deadline = Instant::now() + self.challenge_wait;
+        loop {
+            if !self.shows_challenge(mitigated).await {
+                return false;
+            }
+            if Instant::now() >= deadline {
+                debug!(url, "bot challenge");
+                return true;
+            }
+            tokio::time::sleep(CHALLENGE_POLL).await;
+        }
+    }
+
+    /// The interstitial's title plus Cloudflare's `cf-mitigated` header or the
+    /// interstitial's markup; other pages cost only a title lookup
+    async fn shows_challenge(&self, mitigated: bool) -> bool {
+        let title = self.page.get_title().await.ok().flatten();
+        if !title.is_some_and(|t| is_challenge_title(&t)) {
+            return false;
+        }
+        mitigated || is_challenge_page(&self.page.content().await.unwrap_or_default())
+    }
+
+    /// Body of a response as received (None if Chrome no longer has it)
+    async fn response_bytes(&self, response: &EventResponseReceived) -> Option<Vec<u8>> {
+        let body = self
+            .page
+            .execute(GetResponseBodyParams::new(response.request_id.clone()))
+            .await
+            .ok()?;
+        if body.base64_encoded {
+            base64::engine::general_purpose::STANDARD
+                .decode(&body.body)
+                .ok()
+        } else {
+            Some(body.body.clone().into_bytes())
         }
     }
 
@@ -196,6 +864,120 @@ impl BrowserPage {
     pub async fn current_url(&self) -> Option<String> {
         self.page.url().await.ok().flatten()
     }
+
+    /// Wait up to `timeout_ms` for an element matching `selector`; false if it never appears
+    pub async fn wait_for_selector(&self, selector: &str, timeout_ms: u64) -> bool {
+        let deadline = Instant::now() + Duration::from_millis(timeout_ms);
+        loop {
+            if self.page.find_element(selector).await.is_ok() {
+                return true;
+            }
+            if Instant::now() >= deadline {
+                return false;
+            }
+            tokio::time::sleep(SELECTOR_POLL).await;
+        }
+    }
+
+    /// A redirect the page does itself: a `<meta http-equiv="refresh">`
+    /// target, or where a top-level `location` script lands within `JS_REDIRECT_SETTLE`
+    pub async fn client_redirect(&self) -> Option<ClientRedirect> {
+        let current = self.current_url().await?;
+        let probe: RedirectProbe = self
+            .page
+            .evaluate(REDIRECT_PROBE)
+            .await
+            .ok()?
+            .into_value()
+            .ok()?;
+        if let Some(url) = probe
+            .refresh
+            .and_then(|content| refresh_target(&content, &current))
+        {
+            return Some(ClientRedirect {
+                url,
+                kind: "meta refresh",
+            });
+        }
+        if !has_js_redirect(&probe.scripts) {
+            return None;
+        }
+        let deadline = Instant::now() + JS_REDIRECT_SETTLE;
+        loop {
+            match self.current_url().await {
+                Some(url) if url != current => {
+                    return Some(ClientRedirect {
+                        url,
+                        kind: "JavaScript",
+                    })
+                }
+                _ if Instant::now() >= deadline => return None,
+                _ => tokio::time::sleep(SELECTOR_POLL).await,
+            }
+        }
+    }
+}
+
+/// Refresh `<meta>` content and inline script bodies, without serializing the DOM
+const REDIRECT_PROBE: &str = r#"({
+    refresh: Array.from(document.querySelectorAll('meta[http-equiv][content]'))
+        .find(m => m.httpEquiv.trim().toLowerCase() === 'refresh')?.content ?? null,
+    scripts: Array.from(document.querySelectorAll('script:not([src])'), s => s.textContent),
+})"#;
+
+#[derive(Debug, Deserialize)]
+struct RedirectProbe {
+    refresh: Option<String>,
+    scripts: Vec<String>,
+}
+
+/// Interval between checks in `wait_for_selector`
+const SELECTOR_POLL: Duration = Duration::from_millis(100);
+
+/// Longest a page with a top-level `location` script gets to redirect itself
+const JS_REDIRECT_SETTLE: Duration = Duration::from_secs(1);
+
+/// Redirect done by the page rather than the server
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClientRedirect {
+    pub url: String,
+    /// "meta refresh" or "JavaScript"
+    pub kind: &'static str,
+}
+
+/// Absolute target of a refresh `<meta>`'s `content="0; url=..."`;
+/// `None` for a plain reload (`content="30"`)
+fn refresh_target(content: &str, base_url: &str) -> Option<String> {
+    let re = Regex::new(r#"(?i)^\s*[\d.]*\s*[;,]?\s*(?:url\s*=\s*)?['"]?([^'"]*)"#).ok()?;
+    let target = re.captures(content)?[1].trim().to_string();
+    if target.is_empty() {
+        return None;
+    }
+    let base = url::Url::parse(base_url).ok()?;
+    base.join(&target).ok().map(|u| u.to_string())
+}
+
+/// Whether an inline script assigns `location` or calls `location.assign/replace`
+/// at top level, where it runs as the page loads rather than in a handler
+fn has_js_redirect<S: AsRef<str>>(scripts: &[S]) -> bool {
+    let Ok(re) = Regex::new(r"\blocation(?:\.href)?\s*=[^=]|\blocation\.(?:assign|replace)\s*\(")
+    else {
+        return false;
+    };
+    scripts.iter().any(|script| {
+        let script = script.as_ref();
+        re.find_iter(script)
+            .any(|m| brace_depth(&script[..m.start()]) == 0)
+    })
+}
+
+/// `{` nesting left open at the end of `code`
+fn brace_depth(code: &str) -> i32 {
+    code.chars().fold(0, |depth, c| match c {
+        '{' => depth + 1,
+        '}' => depth - 1,
+        _ => depth,
+    })
 }
 
 /// Result of a page navigation
@@ -204,6 +986,165 @@ pub struct PageResult {
     pub status: u16,
     pub title: Option<String>,
     pub error: Option<String>,
+    /// MIME type of the main document response (e.g. "application/pdf")
+    pub content_type: Option<String>,
+    /// Every hop of a redirected navigation, ending with the final page (empty if none)
+    pub redirects: Vec<RedirectHop>,
+    /// Raw body of JSON, plain text and XML documents
+    pub body: Option<String>,
+    /// Bytes of a PDF document, as Chrome received them
+    pub pdf: Option<Vec<u8>>,
+    /// Wait requested by a 429 response's Retry-After header
+    pub retry_after: Option<Duration>,
+    /// Still a bot challenge interstitial (e.g. Cloudflare "Just a moment...")
+    pub challenge: bool,
+}
+
+impl PageResult {
+    /// Navigation gave up waiting: our own timeout or Chrome's connection timeout
+    pub fn timed_out(&self) -> bool {
+        self.error
+            .as_deref()
+            .is_some_and(|e| e == NAVIGATION_TIMEOUT || parse_error(e).1 == "TIMEOUT")
+    }
+}
+
+/// Whether a page title is a challenge interstitial's ("Just a moment...")
+fn is_challenge_title(title: &str) -> bool {
+    CHALLENGE_TITLES.contains(&title.trim().to_lowercase().as_str())
+}
+
+/// Whether the HTML is an anti-bot challenge page rather than the real content:
+/// the interstitial's title and its markup
+pub fn is_challenge_page(html: &str) -> bool {
+    let doc = Html::parse_document(html);
+    let title = Selector::parse("title").unwrap();
+    let titled = doc
+        .select(&title)
+        .next()
+        .is_some_and(|t| is_challenge_title(&t.text().collect::<String>()));
+    let html = html.to_lowercase();
+    titled && CHALLENGE_MARKERS.iter().any(|marker| html.contains(marker))
+}
+
+/// Documents returned as-is instead of parsed as HTML (JSON, plain text, XML)
+pub fn is_raw_content_type(mime: &str) -> bool {
+    let mime = mime.trim().to_ascii_lowercase();
+    match mime.as_str() {
+        "application/json" | "text/plain" | "application/xml" | "text/xml" => true,
+        "application/xhtml+xml" => false,
+        _ => mime.ends_with("+json") || mime.ends_with("+xml"),
+    }
+}
+
+/// One response in a redirect chain
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RedirectHop {
+    pub url: String,
+    pub status: u16,
+}
+
+/// Redirect chain as "301 https://a/ -> 200 https://b/"
+pub fn format_redirects(hops: &[RedirectHop]) -> String {
+    hops.iter()
+        .map(|hop| format!("{} {}", hop.status, hop.url))
+        .collect::<Vec<_>>()
+        .join(" -> ")
+}
+
+/// Run a navigation, retrying transient failures with exponential backoff
+///
+/// Only network-level failures (status 0: DNS, connection, timeout) are retried,
+/// never definitive HTTP errors like 404. Waits 500ms, 1s, 2s, ... between attempts.
+/// A 429 with Retry-After is retried once after the requested wait (capped at 60s).
+pub async fn retry_navigation<F, Fut>(retries: u8, mut navigate: F) -> Result<PageResult>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<PageResult>>,
+{
+    let mut result = navigate().await?;
+    if let (429, Some(wait)) = (result.status, result.retry_after) {
+        tokio::time::sleep(wait.min(MAX_RETRY_AFTER)).await;
+        result = navigate().await?;
+    }
+    let mut delay = Duration::from_millis(500);
+
+    for _ in 0..retries {
+        if result.status != 0 {
+            break;
+        }
+        tokio::time::sleep(delay).await;
+        delay *= 2;
+        result = navigate().await?;
+    }
+
+    Ok(result)
+}
+
+/// Header value by case-insensitive name
+fn header(headers: &Headers, name: &str) -> Option<String> {
+    headers
+        .inner()
+        .as_object()?
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .and_then(|(_, value)| value.as_str().map(String::from))
+}
+
+/// Retry-After as delay-seconds or an HTTP date (dates in the past mean no wait)
+fn parse_retry_after(value: &str, now: DateTime<Utc>) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    Some(
+        (date.with_timezone(&Utc) - now)
+            .to_std()
+            .unwrap_or_default(),
+    )
+}
+
+/// First document response received so far
+fn document_response(
+    responses: &mut EventStream<EventResponseReceived>,
+) -> Option<Arc<EventResponseReceived>> {
+    while let Some(Some(event)) = responses.next().now_or_never() {
+        if event.r#type == ResourceType::Document {
+            return Some(event);
+        }
+    }
+    None
+}
+
+/// Redirect responses of the main frame's document requests received so far
+fn redirect_hops(
+    requests: &mut EventStream<EventRequestWillBeSent>,
+    main_frame: Option<&FrameId>,
+) -> Vec<RedirectHop> {
+    let mut hops = Vec::new();
+    while let Some(Some(event)) = requests.next().now_or_never() {
+        let is_main_document = event.r#type == Some(ResourceType::Document)
+            && main_frame.is_none_or(|frame| event.frame_id.as_ref() == Some(frame));
+        if let (true, Some(response)) = (is_main_document, &event.redirect_response) {
+            hops.push(RedirectHop {
+                url: response.url.clone(),
+                status: response.status as u16,
+            });
+        }
+    }
+    hops
+}
+
+/// Convert a cookie file entry to a CDP cookie
+fn cookie_param(cookie: &Cookie) -> CookieParam {
+    let mut param = CookieParam::new(&cookie.name, &cookie.value);
+    param.domain = Some(cookie.domain.clone());
+    param.path = Some(cookie.path.clone());
+    param.secure = Some(cookie.secure);
+    param.http_only = Some(cookie.http_only);
+    param.expires = cookie.expires.map(TimeSinceEpoch::new);
+    param
 }
 
 fn parse_error(error: &str) -> (u16, String) {
@@ -220,6 +1161,11 @@ fn parse_error(error: &str) -> (u16, String) {
     }
 }
 
+/// Chrome's native UA without the "HeadlessChrome" marker sites block on
+fn chrome_latest_user_agent(native: &str) -> String {
+    native.replace("HeadlessChrome/", "Chrome/")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -234,6 +1180,804 @@ mod tests {
         assert_eq!(parse_error("random error").1, "NETWORK_ERROR");
     }
 
+    fn nav(status: u16, error: Option<&str>) -> PageResult {
+        PageResult {
+            status,
+            title: None,
+            error: error.map(String::from),
+            content_type: None,
+            redirects: Vec::new(),
+            body: None,
+            pdf: None,
+            retry_after: None,
+            challenge: false,
+        }
+    }
+
+    #[test]
+    fn test_refresh_target() {
+        let page = "https://old.example.com/docs/page";
+        let target = refresh_target("0; URL='https://new.example.org/docs/'", page).unwrap();
+        assert_eq!(target, "https://new.example.org/docs/");
+        assert!(crate::normalize::is_cross_domain(page, &target, false));
+
+        // Relative targets resolve against the page, delay and spacing vary
+        assert_eq!(
+            refresh_target("5;url=../moved.html", page).as_deref(),
+            Some("https://old.example.com/moved.html")
+        );
+        assert_eq!(
+            refresh_target("3, https://b.example.net/", page).as_deref(),
+            Some("https://b.example.net/")
+        );
+
+        // Periodic reloads aren't redirects
+        assert_eq!(refresh_target("30", page), None);
+    }
+
+    #[test]
+    fn test_has_js_redirect() {
+        assert!(has_js_redirect(&[
+            "window.location.href = 'https://b.example/';"
+        ]));
+        assert!(has_js_redirect(&["location.replace(\"/new\")"]));
+        assert!(has_js_redirect(&[
+            "var x = {a: 1};\nif (ok) document.location='/a'"
+        ]));
+        // Reads and comparisons don't count
+        assert!(!has_js_redirect(&[
+            "if (location.href == start) track(location.pathname)"
+        ]));
+        // Handlers and functions only run later, if at all
+        assert!(!has_js_redirect(&["if (x) { document.location='/a' }"]));
+        assert!(!has_js_redirect(&[
+            "btn.onclick = function () { location.assign('/next') };"
+        ]));
+        assert!(!has_js_redirect::<&str>(&[]));
+    }
+
+    #[test]
+    fn test_lifecycle_watch() {
+        // Chrome's order for a page; the leading `load` is the previous document
+        let events = [
+            "load",
+            "init",
+            "DOMContentLoaded",
+            "load",
+            "networkAlmostIdle",
+            "networkIdle",
+        ];
+        let completes_at = |wait_until| {
+            let mut watch = LifecycleWatch::new(wait_until);
+            events.iter().position(|name| watch.on_event(name))
+        };
+        assert_eq!(completes_at(WaitUntil::DomContentLoaded), Some(2));
+        assert_eq!(completes_at(WaitUntil::Load), Some(3));
+        assert_eq!(completes_at(WaitUntil::NetworkIdle), Some(5));
+
+        // networkIdle before load (a fast page) still waits for load
+        let mut watch = LifecycleWatch::new(WaitUntil::NetworkIdle);
+        assert!(!watch.on_event("init"));
+        assert!(!watch.on_event("networkIdle"));
+        assert!(watch.on_event("load"));
+
+        // A redirect starts a new document: earlier events no longer count
+        let mut watch = LifecycleWatch::new(WaitUntil::DomContentLoaded);
+        assert!(!watch.on_event("init"));
+        assert!(watch.on_event("DOMContentLoaded"));
+        assert!(!watch.on_event("init"));
+        assert!(!watch.on_event("load"));
+        assert!(watch.on_event("DOMContentLoaded"));
+    }
+
+    #[test]
+    fn test_parse_connect() {
+        assert!(parse_connect("http://127.0.0.1:9222").is_ok());
+        assert!(parse_connect("ws://127.0.0.1:9222/devtools/browser/abc").is_ok());
+        assert!(parse_connect("127.0.0.1:9222").is_err());
+        assert!(parse_connect("ftp://127.0.0.1:9222").is_err());
+    }
+
+    #[test]
+    fn test_socks5_args() {
+        assert_eq!(
+            socks5_args("127.0.0.1:9050"),
+            [
+                "--proxy-server=socks5://127.0.0.1:9050",
+                "--host-resolver-rules=MAP * ~NOTFOUND , EXCLUDE 127.0.0.1",
+            ]
+        );
+        // Scheme optional
+        assert_eq!(
+            socks5_args("socks5://proxy.lan:1080"),
+            socks5_args("proxy.lan:1080")
+        );
+    }
+
+    /// SOCKS5 server that records each requested host, then refuses the connection
+    async fn socks5_recorder() -> (String, Arc<std::sync::Mutex<Vec<String>>>) {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        let hosts = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let seen = Arc::clone(&hosts);
+        tokio::spawn(async move {
+            while let Ok((mut conn, _)) = listener.accept().await {
+                let mut buf = [0u8; 512];
+                // Greeting: no authentication
+                let Ok(n) = conn.read(&mut buf).await else {
+                    continue;
+                };
+                if n < 2 || buf[0] != 5 {
+                    continue;
+                }
+                let _ = conn.write_all(&[5, 0]).await;
+                // CONNECT with a domain name (socks5h): 5 1 0 3 len name port
+                let Ok(n) = conn.read(&mut buf).await else {
+                    continue;
+                };
+                if n > 5 && buf[3] == 3 {
+                    let len = buf[4] as usize;
+                    let host = String::from_utf8_lossy(&buf[5..5 + len]).into_owned();
+                    seen.lock().unwrap().push(host);
+                }
+                let _ = conn.write_all(&[5, 5, 0, 1, 0, 0, 0, 0, 0, 0]).await;
+            }
+        });
+        (addr, hosts)
+    }
+
+    #[tokio::test]
+    async fn test_http_client_uses_socks5() {
+        let (addr, hosts) = socks5_recorder().await;
+        let proxy = Some(addr.as_str());
+
+        // robots.txt: unreachable, so everything is allowed, but asked via the proxy
+        let robots = crate::robots::RobotsCache::new("ref-test", proxy).unwrap();
+        assert!(robots.is_allowed("http://robots.test/page").await);
+
+        assert!(
+            crate::sitemap::fetch_sitemap_urls("http://sitemap.test/sitemap.xml", proxy)
+                .await
+                .is_err()
+        );
+
+        let client = crate::doi::client(proxy).unwrap();
+        assert!(client.get("https://doi.test/api").send().await.is_err());
+
+        let client = http_client(Some(&format!("socks5://{}", addr)))
+            .unwrap()
+            .build()
+            .unwrap();
+        assert!(client.get("http://scheme.test/").send().await.is_err());
+
+        assert_eq!(
+            *hosts.lock().unwrap(),
+            ["robots.test", "sitemap.test", "doi.test", "scheme.test"]
+        );
+        assert!(http_client(Some("not a proxy:x:y")).is_err());
+    }
+
+    #[test]
+    fn test_parse_host_auth() {
+        let auth = parse_host_auth("Wiki.Corp.Example=alice:s3cr:et").unwrap();
+        assert_eq!(auth.host, "wiki.corp.example");
+        // base64("alice:s3cr:et"): passwords may contain ':'
+        assert_eq!(auth.header, "Basic YWxpY2U6czNjcjpldA==");
+
+        for bad in ["wiki.corp.example", "=alice:pw", "host=alice", "host=:pw"] {
+            assert!(parse_host_auth(bad).is_err());
+        }
+        let err = parse_host_auth("host=hunter2").unwrap_err();
+        assert!(!err.contains("hunter2"));
+        assert_eq!(format!("{:?}", auth), "wiki.corp.example=alice:***");
+    }
+
+    #[test]
+    fn test_auth_header_exact_host() {
+        let auth = vec![parse_host_auth("wiki.corp.example=alice:pw").unwrap()];
+        let header = Some("Basic YWxpY2U6cHc=");
+
+        assert_eq!(auth_header(&auth, "https://wiki.corp.example/page"), header);
+        assert_eq!(auth_header(&auth, "http://WIKI.corp.example:8080/"), header);
+        assert_eq!(auth_header(&auth, "https://corp.example/"), None);
+        assert_eq!(auth_header(&auth, "https://docs.wiki.corp.example/"), None);
+        assert_eq!(auth_header(&auth, "https://example.com/"), None);
+        assert_eq!(auth_header(&[], "https://wiki.corp.example/"), None);
+    }
+
+    #[test]
+    fn test_auth_headers_per_request() {
+        let auth = vec![parse_host_auth("wiki.corp.example=alice:pw").unwrap()];
+        let request = |url: &str| {
+            serde_json::from_value::<Request>(serde_json::json!({
+                "url": url,
+                "method": "GET",
+                "headers": {"Accept": "text/html"},
+                "initialPriority": "VeryHigh",
+                "referrerPolicy": "no-referrer",
+            }))
+            .unwrap()
+        };
+
+        let headers = auth_headers(&request("https://wiki.corp.example/page"), &auth).unwrap();
+        assert!(headers.contains(&HeaderEntry::new("Accept", "text/html")));
+        assert!(headers.contains(&HeaderEntry::new("Authorization", "Basic YWxpY2U6cHc=")));
+
+        // A redirect to another host, or a third-party script, is a new request: no header
+        assert_eq!(
+            auth_headers(&request("https://cdn.example.net/app.js"), &auth),
+            None
+        );
+        assert_eq!(
+            auth_headers(&request("https://docs.wiki.corp.example/"), &auth),
+            None
+        );
+    }
+
+    #[test]
+    fn test_timed_out() {
+        assert!(nav(0, Some(NAVIGATION_TIMEOUT)).timed_out());
+        assert!(nav(0, Some("net::ERR_CONNECTION_TIMED_OUT")).timed_out());
+        assert!(!nav(0, Some("net::ERR_NAME_NOT_RESOLVED")).timed_out());
+        assert!(!nav(200, None).timed_out());
+    }
+
+    #[tokio::test]
+    async fn test_retry_navigation_flaky() {
+        // Connection error first, then 200
+        let mut attempts = 0;
+        let result = retry_navigation(2, || {
+            attempts += 1;
+            let n = attempts;
+            async move {
+                Ok(if n == 1 {
+                    nav(0, Some("net::ERR_CONNECTION_RESET"))
+                } else {
+                    nav(200, None)
+                })
+            }
+        })
+        .await
+        .unwrap();
+        assert_eq!(result.status, 200);
+        assert!(result.error.is_none());
+        assert_eq!(attempts, 2);
+    }
+
+    #[tokio::test]
+    async fn test_retry_navigation_no_retry_on_404() {
+        let mut attempts = 0;
+        let result = retry_navigation(3, || {
+            attempts += 1;
+            async { Ok(nav(404, None)) }
+        })
+        .await
+        .unwrap();
+        assert_eq!(result.status, 404);
+        assert_eq!(attempts, 1);
+    }
+
+    #[tokio::test]
+    async fn test_retry_navigation_429_retry_after() {
+        let mut attempts = 0;
+        let started = std::time::Instant::now();
+        let result = retry_navigation(0, || {
+            attempts += 1;
+            let n = attempts;
+            async move {
+                Ok(if n == 1 {
+                    PageResult {
+                        retry_after: Some(Duration::from_secs(1)),
+                        ..nav(429, None)
+                    }
+                } else {
+                    nav(200, None)
+                })
+            }
+        })
+        .await
+        .unwrap();
+        assert_eq!(result.status, 200);
+        assert_eq!(attempts, 2);
+        assert!(started.elapsed() >= Duration::from_secs(1));
+
+        // No Retry-After: 429 is final, like any HTTP error
+        let mut attempts = 0;
+        let result = retry_navigation(3, || {
+            attempts += 1;
+            async { Ok(nav(429, None)) }
+        })
+        .await
+        .unwrap();
+        assert_eq!(result.status, 429);
+        assert_eq!(attempts, 1);
+    }
+
+    #[test]
+    fn test_parse_retry_after() {
+        let now: DateTime<Utc> = "2026-01-01T00:00:00Z".parse().unwrap();
+        assert_eq!(
+            parse_retry_after(" 120 ", now),
+            Some(Duration::from_secs(120))
+        );
+        assert_eq!(
+            parse_retry_after("Thu, 01 Jan 2026 00:00:30 GMT", now),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(
+            parse_retry_after("Wed, 31 Dec 2025 23:00:00 GMT", now),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(parse_retry_after("soon", now), None);
+    }
+
+    #[tokio::test]
+    async fn test_goto_429_then_ok() {
+        use wiremock::matchers::path;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(path("/busy"))
+            .respond_with(
+                ResponseTemplate::new(429)
+                    .insert_header("Retry-After", "1")
+                    .set_body_raw("<html><title>Slow down</title></html>", "text/html"),
+            )
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(path("/busy"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_raw("<html><title>Welcome</title></html>", "text/html"),
+            )
+            .mount(&server)
+            .await;
+
+        // Needs Chrome; skip on machines without one
+        let Ok(pool) = BrowserPool::new(1).await else {
+            return;
+        };
+        let page = pool.new_page().await.unwrap();
+        let url = format!("{}/busy", server.uri());
+        let result = retry_navigation(0, || page.goto(&url, 15000))
+            .await
+            .unwrap();
+        drop(page);
+        pool.close().await.unwrap();
+
+        assert_eq!(result.status, 200);
+        assert_eq!(result.title.as_deref(), Some("Welcome"));
+        assert_eq!(server.received_requests().await.unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_accept_language() {
+        assert_eq!(accept_language("de-DE"), "de-DE,de;q=0.9");
+        assert_eq!(accept_language("zh-Hant-TW"), "zh-Hant-TW,zh;q=0.9");
+        assert_eq!(accept_language("fr"), "fr");
+
+        assert!(parse_lang("pt-BR").is_ok());
+        assert!(parse_lang("es-419").is_ok());
+        assert!(parse_lang("de_DE").is_err());
+        assert!(parse_lang("en,fr").is_err());
+        assert!(parse_lang("").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_lang_sends_accept_language() {
+        use wiremock::matchers::{header, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(path("/"))
+            .and(header("accept-language", "de-DE,de;q=0.9"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_raw("<html><title>Willkommen</title></html>", "text/html"),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(path("/"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_raw("<html><title>Welcome</title></html>", "text/html"),
+            )
+            .mount(&server)
+            .await;
+
+        // Needs Chrome; skip on machines without one
+        let Ok(pool) = BrowserPool::new(1).await else {
+            return;
+        };
+        let pool = pool.with_lang(Some("de-DE"));
+        let page = pool.new_page().await.unwrap();
+        let result = page
+            .goto(&format!("{}/", server.uri()), 15000)
+            .await
+            .unwrap();
+        let language = page
+            .page
+            .evaluate("navigator.language")
+            .await
+            .ok()
+            .and_then(|v| v.into_value::<String>().ok());
+        drop(page);
+        pool.close().await.unwrap();
+
+        assert_eq!(result.title.as_deref(), Some("Willkommen"));
+        assert_eq!(language.as_deref(), Some("de-DE"));
+    }
+
+    #[tokio::test]
+    async fn test_auth_not_sent_across_redirect() {
+        use wiremock::matchers::{header, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        // 127.0.0.1 has credentials and redirects to localhost, another host
+        let server = MockServer::start().await;
+        let port = server.address().port();
+        Mock::given(path("/start"))
+            .and(header("authorization", "Basic YWxpY2U6cHc="))
+            .respond_with(
+                ResponseTemplate::new(302)
+                    .insert_header("location", format!("http://localhost:{}/landing", port)),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(path("/landing"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_raw("<html><title>Landing</title></html>", "text/html"),
+            )
+            .mount(&server)
+            .await;
+
+        // Needs Chrome; skip on machines without one
+        let Ok(pool) = BrowserPool::new(1).await else {
+            return;
+        };
+        let pool = pool.with_auth(&[parse_host_auth("127.0.0.1=alice:pw").unwrap()]);
+        let page = pool.new_page().await.unwrap();
+        let result = page
+            .goto(&format!("http://127.0.0.1:{}/start", port), 15000)
+            .await
+            .unwrap();
+        drop(page);
+        pool.close().await.unwrap();
+
+        assert_eq!(result.title.as_deref(), Some("Landing"));
+        let requests = server.received_requests().await.unwrap();
+        let landing: Vec<_> = requests
+            .iter()
+            .filter(|r| r.url.path() == "/landing")
+            .collect();
+        assert!(!landing.is_empty());
+        assert!(landing
+            .iter()
+            .all(|r| !r.headers.contains_key("authorization")));
+    }
+
+    #[tokio::test]
+    async fn test_goto_challenge_page() {
+        use wiremock::matchers::path;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        let challenge = std::fs::read_to_string("test-data/js-challenge.html").unwrap();
+        Mock::given(path("/guarded"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(challenge, "text/html"))
+            .mount(&server)
+            .await;
+        Mock::given(path("/open"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_raw("<html><title>Welcome</title></html>", "text/html"),
+            )
+            .mount(&server)
+            .await;
+
+        // Needs Chrome; skip on machines without one
+        let Ok(pool) = BrowserPool::new(1).await else {
+            return;
+        };
+        let pool = pool.with_challenge_wait(1);
+        let page = pool.new_page().await.unwrap();
+        let started = Instant::now();
+        let guarded = page
+            .goto(&format!("{}/guarded", server.uri()), 15000)
+            .await
+            .unwrap();
+        let waited = started.elapsed();
+        let open = page
+            .goto(&format!("{}/open", server.uri()), 15000)
+            .await
+            .unwrap();
+        drop(page);
+        pool.close().await.unwrap();
+
+        assert!(guarded.challenge);
+        assert!(waited >= Duration::from_secs(1));
+        assert!(!open.challenge);
+    }
+
+    fn browser_gone() -> CdpError {
+        futures::channel::oneshot::Canceled.into()
+    }
+
+    #[tokio::test]
+    async fn test_retry_after_crash_relaunches_once() {
+        let relaunches = AtomicU32::new(0);
+        let mut attempts = 0;
+        let mut relaunched = Vec::new();
+        let result = retry_after_crash(
+            &relaunches,
+            || {
+                attempts += 1;
+                let n = attempts;
+                async move {
+                    if n == 1 {
+                        Err(browser_gone())
+                    } else {
+                        Ok("page")
+                    }
+                }
+            },
+            |seen| {
+                relaunched.push(seen);
+                relaunches.fetch_add(1, Ordering::SeqCst);
+                async { Ok(()) }
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(result, "page");
+        assert_eq!(attempts, 2);
+        assert_eq!(relaunched, vec![0]);
+    }
+
+    #[tokio::test]
+    async fn test_retry_after_crash_no_loop() {
+        // Still dead after the relaunch: one relaunch, then the error surfaces
+        let relaunches = AtomicU32::new(0);
+        let mut attempts = 0;
+        let mut relaunched = 0;
+        let result: Result<()> = retry_after_crash(
+            &relaunches,
+            || {
+                attempts += 1;
+                async { Err(browser_gone()) }
+            },
+            |_| {
+                relaunched += 1;
+                async { Ok(()) }
+            },
+        )
+        .await;
+        assert!(result.is_err());
+        assert_eq!(attempts, 2);
+        assert_eq!(relaunched, 1);
+
+        // Limit reached: no further relaunches
+        let relaunches = AtomicU32::new(MAX_RELAUNCHES);
+        let result: Result<()> = retry_after_crash(
+            &relaunches,
+            || async { Err(browser_gone()) },
+            |_| async { panic!("relaunched past the limit") },
+        )
+        .await;
+        assert!(format!("{:#}", result.unwrap_err()).contains("relaunch limit"));
+    }
+
+    #[tokio::test]
+    async fn test_retry_after_crash_other_errors() {
+        let relaunches = AtomicU32::new(0);
+        let result: Result<()> = retry_after_crash(
+            &relaunches,
+            || async { Err(CdpError::Timeout) },
+            |_| async { panic!("relaunched on a timeout") },
+        )
+        .await;
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_chrome_latest_user_agent() {
+        let native = "Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36 (KHTML, like Gecko) HeadlessChrome/131.0.6778.85 Safari/537.36";
+        assert_eq!(
+            chrome_latest_user_agent(native),
+            "Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/131.0.6778.85 Safari/537.36"
+        );
+    }
+
+    /// Responds with the request's User-Agent header as the page body
+    struct EchoUserAgent;
+
+    impl wiremock::Respond for EchoUserAgent {
+        fn respond(&self, request: &wiremock::Request) -> wiremock::ResponseTemplate {
+            let ua = request
+                .headers
+                .get("user-agent")
+                .and_then(|v| v.to_str().ok())
+                .unwrap_or_default();
+            wiremock::ResponseTemplate::new(200).set_body_raw(
+                format!("<html><body><p>{}</p></body></html>", ua),
+                "text/html",
+            )
+        }
+    }
+
+    #[tokio::test]
+    async fn test_user_agent_override() {
+        let server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::any())
+            .respond_with(EchoUserAgent)
+            .mount(&server)
+            .await;
+
+        // Needs a local Chrome; skip on machines without one
+        let Ok(pool) = BrowserPool::new(1).await else {
+            return;
+        };
+        let pool = pool
+            .with_user_agent(Some("ref-test-agent/1.0"))
+            .await
+            .unwrap();
+        let page = pool.new_page().await.unwrap();
+        page.goto(&server.uri(), 15000).await.unwrap();
+        let html = page.content().await.unwrap();
+        drop(page);
+        pool.close().await.unwrap();
+
+        assert!(html.contains("ref-test-agent/1.0"));
+    }
+
+    #[tokio::test]
+    async fn test_resources_blocked() {
+        let server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::path("/"))
+            .respond_with(wiremock::ResponseTemplate::new(200).set_body_raw(
+                r#"<html><head><link rel="stylesheet" href="/style.css"></head>
+                <body><img src="/photo.png"><p>Text survives</p></body></html>"#,
+                "text/html",
+            ))
+            .mount(&server)
+            .await;
+        wiremock::Mock::given(wiremock::matchers::path("/photo.png"))
+            .respond_with(wiremock::ResponseTemplate::new(200).set_body_raw("png", "image/png"))
+            .mount(&server)
+            .await;
+
+        // Needs a local Chrome; skip on machines without one
+        let Ok(pool) = BrowserPool::new(1).await else {
+            return;
+        };
+        let page = pool.new_page().await.unwrap();
+        page.goto(&server.uri(), 15000).await.unwrap();
+        let html = page.content().await.unwrap();
+        drop(page);
+        pool.close().await.unwrap();
+
+        assert!(html.contains("Text survives"));
+        let requests = server.received_requests().await.unwrap();
+        let paths: Vec<&str> = requests.iter().map(|r| r.url.path()).collect();
+        assert!(paths.contains(&"/"));
+        assert!(!paths.contains(&"/photo.png"));
+        assert!(!paths.contains(&"/style.css"));
+    }
+
+    #[tokio::test]
+    async fn test_redirect_chain() {
+        use wiremock::matchers::path;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        let to = |status: u16, location: &str| {
+            ResponseTemplate::new(status)
+                .insert_header("location", format!("{}{}", server.uri(), location))
+        };
+        Mock::given(path("/old"))
+            .respond_with(to(301, "/moved"))
+            .mount(&server)
+            .await;
+        Mock::given(path("/moved"))
+            .respond_with(to(302, "/new"))
+            .mount(&server)
+            .await;
+        Mock::given(path("/new"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_raw("<html><body>New</body></html>", "text/html"),
+            )
+            .mount(&server)
+            .await;
+
+        // Needs a local Chrome; skip on machines without one
+        let Ok(pool) = BrowserPool::new(1).await else {
+            return;
+        };
+        let page = pool.new_page().await.unwrap();
+        let result = page
+            .goto(&format!("{}/old", server.uri()), 15000)
+            .await
+            .unwrap();
+        drop(page);
+        pool.close().await.unwrap();
+
+        let hop = |status: u16, p: &str| RedirectHop {
+            url: format!("{}{}", server.uri(), p),
+            status,
+        };
+        assert_eq!(
+            result.redirects,
+            vec![hop(301, "/old"), hop(302, "/moved"), hop(200, "/new")]
+        );
+    }
+
+    #[test]
+    fn test_is_challenge_page() {
+        let html = std::fs::read_to_string("test-data/js-challenge.html").unwrap();
+        assert!(is_challenge_page(&html));
+        assert!(is_challenge_page(
+            r#"<html><head><title>Just a moment…</title></head><body><form id="challenge-form"></form></body></html>"#
+        ));
+        assert!(!is_challenge_page(
+            r#"<html><body><form id="challenge-form" class="cf-browser-verification"></form></body></html>"#
+        ));
+
+        for fixture in ["paywall-de.html", "soft-404.html", "not-found-article.html"] {
+            let html = std::fs::read_to_string(format!("test-data/{}", fixture)).unwrap();
+            assert!(!is_challenge_page(&html), "{}", fixture);
+        }
+        // The phrase alone in a real page's text is not a challenge
+        assert!(!is_challenge_page(
+            "<title>Docs</title><p>Just a moment... while it builds.</p>"
+        ));
+        // Nor is an ordinary page carrying Cloudflare's bot-detection script
+        assert!(!is_challenge_page(
+            r#"<html><head><title>Pricing</title></head><body><p>Plans</p>
+            <script>(function(){window._cf_chl_done=1})();</script>
+            <script src="/cdn-cgi/challenge-platform/scripts/jsd/main.js"></script></body></html>"#
+        ));
+    }
+
+    #[test]
+    fn test_is_raw_content_type() {
+        for raw in [
+            "application/json",
+            "text/plain",
+            "text/xml",
+            "application/ld+json",
+            "application/rss+xml",
+        ] {
+            assert!(is_raw_content_type(raw), "{}", raw);
+        }
+        for parsed in ["text/html", "application/xhtml+xml", "application/pdf", ""] {
+            assert!(!is_raw_content_type(parsed), "{}", parsed);
+        }
+    }
+
+    #[test]
+    fn test_format_redirects() {
+        let hops = [
+            RedirectHop {
+                url: "http://a.com/".to_string(),
+                status: 301,
+            },
+            RedirectHop {
+                url: "https://b.com/".to_string(),
+                status: 200,
+            },
+        ];
+        assert_eq!(
+            format_redirects(&hops),
+            "301 http://a.com/ -> 200 https://b.com/"
+        );
+        assert_eq!(format_redirects(&[]), "");
+    }
+
     #[test]
     fn test_detect_chrome_path() {
         // This test verifies the function runs without panic
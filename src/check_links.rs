@@ -1,18 +1,38 @@
 //! check-links command: Check URL health
 //!
-//! LLM-optimized output - JSON compact only.
+//! LLM-optimized output - compact JSON (or YAML with --format yaml).
 
-use crate::browser::BrowserPool;
-use crate::extract::extract_urls;
+use crate::browser::{
+    parse_connect, parse_host_auth, retry_navigation, BrowserPool, HostAuth, RedirectHop, WaitUntil,
+};
+use crate::events;
+use crate::extract::{
+    extract_asciidoc_urls, extract_scheme_urls, extract_urls, is_valid_email, Scheme,
+};
+use crate::filter::UrlFilter;
+use crate::ftp;
+use crate::normalize::is_cross_domain;
+use crate::output::OutputFormat;
+use crate::progress;
+use crate::robots::RobotsCache;
+use crate::schema::ReferencesFile;
+use crate::sitemap::fetch_sitemap_urls;
+use crate::util::{bare_host, is_zero, truncate};
 use anyhow::{Context, Result};
+use base64::Engine;
 use clap::Args;
+use regex::Regex;
+use scraper::{Html, Selector};
 use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::io::{self, BufRead};
+use std::path::{Path, PathBuf};
 use tokio::fs;
 
 #[derive(Args)]
 pub struct CheckLinksArgs {
-    /// Markdown file to check URLs from
+    /// Markdown (or .rst/.adoc) file to check URLs from, or a references.yaml/.json
+    /// (failed links then list the files that cite them)
     #[arg(value_name = "FILE")]
     file: Option<String>,
 
@@ -24,17 +44,135 @@ pub struct CheckLinksArgs {
     #[arg(long)]
     stdin: bool,
 
+    /// Also check mailto:, ftp: and data: links, without the browser (comma-separated)
+    #[arg(long, value_enum, value_delimiter = ',', value_name = "SCHEMES")]
+    schemes: Vec<Scheme>,
+
+    /// Check every page listed in a sitemap.xml (indexes and .gz followed)
+    #[arg(long, value_name = "URL", conflicts_with_all = ["file", "url", "stdin"])]
+    sitemap: Option<String>,
+
+    /// Only check URLs matching this regex (repeatable)
+    #[arg(long, value_name = "REGEX")]
+    include: Vec<String>,
+
+    /// Skip URLs matching this regex (repeatable, wins over --include)
+    #[arg(long, value_name = "REGEX")]
+    exclude: Vec<String>,
+
     /// Number of parallel browser tabs (1-20)
     #[arg(short, long, default_value = "5", value_parser = clap::value_parser!(u8).range(1..=20))]
-    concurrency: u8,
+    pub(crate) concurrency: u8,
 
     /// Timeout per URL in milliseconds
     #[arg(long, default_value = "15000")]
-    timeout: u64,
+    pub(crate) timeout: u64,
 
-    /// Number of retries on failure
+    /// Retries on network failure, with exponential backoff (never on 404)
     #[arg(long, default_value = "1")]
-    retries: u8,
+    pub(crate) retries: u8,
+
+    /// Skip URLs disallowed by the host's robots.txt
+    #[arg(long)]
+    respect_robots: bool,
+
+    /// Attach to a running Chrome (ws:// or http://host:9222) instead of launching one
+    #[arg(
+        long,
+        visible_alias = "remote-debugging-url",
+        env = "REF_BROWSER_URL",
+        value_parser = parse_connect
+    )]
+    pub(crate) connect: Option<String>,
+
+    /// Route traffic and DNS through a SOCKS5 proxy (host:port), no local lookups
+    #[arg(long, value_name = "HOST:PORT", conflicts_with = "connect")]
+    pub(crate) socks5: Option<String>,
+
+    /// User agent string, or "chrome-latest" for the browser's own version
+    #[arg(long, value_name = "UA")]
+    pub(crate) user_agent: Option<String>,
+
+    /// Load images, media, fonts and stylesheets (blocked by default for speed)
+    #[arg(long)]
+    load_resources: bool,
+
+    /// Page lifecycle event that ends navigation
+    #[arg(long, value_enum, default_value = "load")]
+    wait_until: WaitUntil,
+
+    /// Wait up to this long for bot challenge pages ("Just a moment...") to clear
+    #[arg(long, value_name = "SECS", default_value = "0")]
+    wait_challenge: u64,
+
+    /// Exit with code 1 if any link fails (JSON report is still printed first)
+    #[arg(long)]
+    fail_on_error: bool,
+
+    /// Exit with code 1 only if more than N links fail
+    #[arg(long, value_name = "N")]
+    fail_threshold: Option<usize>,
+
+    /// Print each result as a JSON line as soon as it completes (completion order, not input order)
+    #[arg(long)]
+    jsonl: bool,
+
+    /// Report format
+    #[arg(long, value_enum, default_value = "json", conflicts_with = "jsonl")]
+    format: OutputFormat,
+
+    /// Verify #fragment anchors exist on the page (missing anchors count as failed)
+    #[arg(long)]
+    check_anchors: bool,
+
+    /// Treat redirects within the same registrable domain (docs.example.com -> example.com) as ok
+    #[arg(long)]
+    pub allow_subdomain_redirects: bool,
+
+    /// Add a per-domain rollup (total, ok, failed URLs) as `by_domain`
+    #[arg(long)]
+    summary: bool,
+
+    /// Print only the counts (and `by_domain` with --summary), no per-URL `results`
+    #[arg(long, conflicts_with = "jsonl")]
+    summary_only: bool,
+
+    /// Also check relative links: resolved against this URL (HTTP) or directory (must exist)
+    #[arg(long, value_name = "URL_OR_PATH", requires = "file")]
+    base: Option<String>,
+
+    /// HTTP basic auth for links on exactly this host (repeatable; or
+    /// space-separated in REF_AUTH, which keeps it out of the process list)
+    #[arg(
+        long,
+        value_name = "HOST=USER:PASS",
+        env = "REF_AUTH",
+        value_delimiter = ' ',
+        hide_env_values = true
+    )]
+    auth: Vec<String>,
+}
+
+/// Where relative links point (--base)
+enum Base {
+    Url(url::Url),
+    Dir(PathBuf),
+}
+
+impl Base {
+    /// http(s) URLs are treated as a directory ("https://x.com/docs" = ".../docs/")
+    fn parse(base: &str) -> Self {
+        match url::Url::parse(base) {
+            Ok(mut url) if matches!(url.scheme(), "http" | "https") => {
+                if !url.path().ends_with('/') {
+                    let path = format!("{}/", url.path());
+                    url.set_path(&path);
+                }
+                Self::Url(url)
+            }
+            _ => Self::Dir(PathBuf::from(base)),
+        }
+    }
 }
 
 /// Configuration for check-links
@@ -42,6 +180,26 @@ pub struct CheckLinksConfig {
     pub concurrency: usize,
     pub timeout_ms: u64,
     pub retries: u8,
+    /// Skip URLs disallowed by robots.txt (reported as failed)
+    pub respect_robots: bool,
+    /// DevTools URL of a running Chrome to attach to (None = launch one)
+    pub connect: Option<String>,
+    /// Route traffic and DNS through a SOCKS5 proxy (host:port); ftp:// links are skipped
+    pub socks5: Option<String>,
+    /// User agent override ("chrome-latest" = browser's own)
+    pub user_agent: Option<String>,
+    /// Load images, media, fonts and stylesheets (blocked by default)
+    pub load_resources: bool,
+    /// Lifecycle event that ends navigation
+    pub wait_until: WaitUntil,
+    /// Seconds to wait for bot challenge pages to clear
+    pub wait_challenge: u64,
+    /// Verify that #fragment anchors exist on the loaded page
+    pub check_anchors: bool,
+    /// Same registrable domain redirects are not reported as `redirect_to`
+    pub allow_subdomain_redirects: bool,
+    /// Basic auth credentials per host
+    pub auth: Vec<HostAuth>,
 }
 
 /// Result for a single link check (compact)
@@ -53,6 +211,38 @@ pub struct LinkResult {
     pub error: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub redirect_to: Option<String>,
+    /// Each hop when the link redirected, ending with the final page
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub redirects: Option<Vec<RedirectHop>>,
+    /// Excluded by --include/--exclude, or a scheme that can't be checked (ftps://)
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub skipped: bool,
+    /// Files citing a failed link (references file input)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub cited_in: Vec<String>,
+}
+
+impl LinkResult {
+    /// Checked, 2xx/3xx, same-host, no error (missing anchor, robots)
+    pub fn is_ok(&self) -> bool {
+        !self.skipped
+            && (200..400).contains(&self.status)
+            && self.redirect_to.is_none()
+            && self.error.is_none()
+    }
+
+    /// Result for a URL filtered out before checking
+    fn skipped(url: String) -> Self {
+        Self {
+            url,
+            status: 0,
+            error: None,
+            redirect_to: None,
+            redirects: None,
+            skipped: true,
+            cited_in: Vec::new(),
+        }
+    }
 }
 
 /// Full report (compact)
@@ -60,19 +250,104 @@ pub struct LinkResult {
 pub struct LinkReport {
     pub ok: usize,
     pub failed: usize,
+    #[serde(skip_serializing_if = "is_zero")]
+    pub skipped: usize,
     pub results: Vec<LinkResult>,
+    /// Per-host rollup (--summary)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub by_domain: Option<BTreeMap<String, DomainStats>>,
+}
+
+/// A report without its per-URL results (--summary-only)
+#[derive(Debug, Serialize)]
+pub struct LinkSummary<'a> {
+    pub ok: usize,
+    pub failed: usize,
+    #[serde(skip_serializing_if = "is_zero")]
+    pub skipped: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub by_domain: Option<&'a BTreeMap<String, DomainStats>>,
+}
+
+impl LinkReport {
+    pub fn summary(&self) -> LinkSummary<'_> {
+        LinkSummary {
+            ok: self.ok,
+            failed: self.failed,
+            skipped: self.skipped,
+            by_domain: self.by_domain.as_ref(),
+        }
+    }
+}
+
+/// Link health for one host
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct DomainStats {
+    pub total: usize,
+    pub ok: usize,
+    pub failed: usize,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub failed_urls: Vec<String>,
+}
+
+/// Group checked results by host ("www." stripped); skipped URLs are left out
+pub fn by_domain<'a>(
+    results: impl IntoIterator<Item = &'a LinkResult>,
+) -> BTreeMap<String, DomainStats> {
+    let mut domains = BTreeMap::new();
+    for result in results {
+        record_domain(&mut domains, result);
+    }
+    domains
+}
+
+fn record_domain(domains: &mut BTreeMap<String, DomainStats>, result: &LinkResult) {
+    if result.skipped {
+        return;
+    }
+    let host = bare_host(&result.url).unwrap_or_else(|| "local".to_string());
+    let stats = domains.entry(host).or_default();
+    stats.total += 1;
+    if result.is_ok() {
+        stats.ok += 1;
+    } else {
+        stats.failed += 1;
+        stats.failed_urls.push(result.url.clone());
+    }
 }
 
 /// Run the check-links command
 pub async fn run_check_links(args: CheckLinksArgs) -> Result<()> {
-    let urls = get_urls(&args).await?;
+    // Parsed here rather than by clap, whose errors quote the value
+    let auth = args
+        .auth
+        .iter()
+        .map(|value| parse_host_auth(value))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| anyhow::anyhow!("invalid --auth: {}", e))?;
+    let (urls, excluded, sources) = get_urls(&args).await?;
+    let mut local = match (&args.base, &args.file) {
+        (Some(base), Some(file)) => check_local_links(file, &Base::parse(base), &args).await?,
+        _ => Vec::new(),
+    };
+    // mailto/ftp/data links are checked here, never in the browser
+    let (other, urls): (Vec<String>, Vec<String>) = urls
+        .into_iter()
+        .partition(|url| Scheme::of(url).is_some_and(|s| args.schemes.contains(&s)));
+    for url in &other {
+        local.push(check_scheme_url(url, args.timeout, args.socks5.is_some()).await);
+    }
 
-    if urls.is_empty() {
+    if urls.is_empty() && excluded.is_empty() && local.is_empty() {
         eprintln!("No URLs found.");
         std::process::exit(1);
     }
+    if !excluded.is_empty() {
+        progress!("Skipping {} excluded URLs", excluded.len());
+    }
+    let skipped: Vec<LinkResult> = excluded.into_iter().map(LinkResult::skipped).collect();
 
-    eprintln!(
+    progress!(
         "Checking {} URLs ({} parallel)...",
         urls.len(),
         args.concurrency
@@ -82,24 +357,154 @@ pub async fn run_check_links(args: CheckLinksArgs) -> Result<()> {
         concurrency: args.concurrency as usize,
         timeout_ms: args.timeout,
         retries: args.retries,
+        respect_robots: args.respect_robots,
+        connect: args.connect.clone(),
+        socks5: args.socks5.clone(),
+        user_agent: args.user_agent.clone(),
+        load_resources: args.load_resources,
+        wait_until: args.wait_until,
+        wait_challenge: args.wait_challenge,
+        check_anchors: args.check_anchors,
+        allow_subdomain_redirects: args.allow_subdomain_redirects,
+        auth,
     };
 
-    let report = check_links(&urls, &config).await?;
+    events::start(urls.len());
+    let local_ok = local.iter().filter(|r| r.is_ok()).count();
+    let local_skipped = local.iter().filter(|r| r.skipped).count();
+    let local_failed = local.len() - local_ok - local_skipped;
+
+    let (ok, failed) = if args.jsonl {
+        // Stream one result per line instead of the wrapped report
+        let mut domains = BTreeMap::new();
+        for result in skipped.iter().chain(&local) {
+            if args.summary {
+                record_domain(&mut domains, result);
+            }
+            println!("{}", serde_json::to_string(result)?);
+        }
+        let counts = check_links_each(&urls, &config, |mut result| {
+            add_sources(&mut result, &sources);
+            if args.summary {
+                record_domain(&mut domains, &result);
+            }
+            if let Ok(line) = serde_json::to_string(&result) {
+                println!("{}", line);
+            }
+        })
+        .await?;
+        // Rollup goes last, once every host is counted
+        if args.summary {
+            println!("{}", serde_json::json!({ "by_domain": domains }));
+        }
+        (counts.0 + local_ok, counts.1 + local_failed)
+    } else {
+        let mut report = check_links(&urls, &config).await?;
+        for result in &mut report.results {
+            add_sources(result, &sources);
+        }
+        report.ok += local_ok;
+        report.failed += local_failed;
+        report.skipped = skipped.len() + local_skipped;
+        report.results.extend(local);
+        report.results.extend(skipped);
+        if args.summary {
+            report.by_domain = Some(by_domain(&report.results));
+        }
+
+        if args.summary_only {
+            print!("{}", args.format.render(&report.summary())?);
+        } else {
+            print!("{}", args.format.render(&report)?);
+        }
+        (report.ok, report.failed)
+    };
 
-    // Output compact JSON to stdout
-    println!("{}", serde_json::to_string(&report)?);
+    events::done();
+    progress!("Done: {}/{} OK", ok, ok + failed);
 
-    eprintln!("Done: {}/{} OK", report.ok, report.ok + report.failed);
+    // CI gate: exit non-zero after the report has been printed
+    if should_fail(failed, args.fail_on_error, args.fail_threshold) {
+        eprintln!("Failing: {} link(s) failed", failed);
+        std::process::exit(1);
+    }
 
     Ok(())
 }
 
-/// Get URLs from file, --url, or stdin
-async fn get_urls(args: &CheckLinksArgs) -> Result<Vec<String>> {
+/// Whether failures exceed the allowed count (--fail-threshold wins over --fail-on-error)
+fn should_fail(failed: usize, fail_on_error: bool, fail_threshold: Option<usize>) -> bool {
+    match fail_threshold {
+        Some(threshold) => failed > threshold,
+        None => fail_on_error && failed > 0,
+    }
+}
+
+/// Citing files per URL, from a references file input
+type Sources = HashMap<String, Vec<String>>;
+
+/// Get URLs from file, --url, or stdin, split into (to check, excluded), plus
+/// the citing files when FILE is a references file
+async fn get_urls(args: &CheckLinksArgs) -> Result<(Vec<String>, Vec<String>, Sources)> {
+    let filter = UrlFilter::new(&args.include, &args.exclude)?;
+    let (urls, sources) = match read_references(args).await {
+        Some(refs_file) => reference_urls(refs_file),
+        None => (read_urls(args).await?, Sources::new()),
+    };
+    let (urls, excluded) = filter.partition(urls);
+    Ok((urls, excluded, sources))
+}
+
+/// FILE as a references file, when it is a .yaml/.yml/.json that parses as one
+async fn read_references(args: &CheckLinksArgs) -> Option<ReferencesFile> {
+    if args.url.is_some() || args.stdin {
+        return None;
+    }
+    let path = Path::new(args.file.as_deref()?);
+    let ext = path.extension()?.to_str()?.to_lowercase();
+    if !matches!(ext.as_str(), "yaml" | "yml" | "json") {
+        return None;
+    }
+    ReferencesFile::load(path).await.ok()
+}
+
+/// Each reference's URL, in file order, with its `cited_in` files
+fn reference_urls(refs_file: ReferencesFile) -> (Vec<String>, Sources) {
+    let mut urls = Vec::with_capacity(refs_file.references.len());
+    let mut sources = Sources::new();
+    for reference in refs_file.references {
+        let cited_in = sources.entry(reference.url.clone()).or_insert_with(|| {
+            urls.push(reference.url);
+            Vec::new()
+        });
+        for file in reference.cited_in {
+            if !cited_in.contains(&file) {
+                cited_in.push(file);
+            }
+        }
+    }
+    (urls, sources)
+}
+
+/// Point a failed link at the files that cite it
+fn add_sources(result: &mut LinkResult, sources: &Sources) {
+    if !result.is_ok() {
+        if let Some(files) = sources.get(&result.url) {
+            result.cited_in = files.clone();
+        }
+    }
+}
+
+/// All URLs from file, --url, or stdin
+async fn read_urls(args: &CheckLinksArgs) -> Result<Vec<String>> {
     if let Some(url) = &args.url {
         return Ok(vec![url.clone()]);
     }
 
+    if let Some(sitemap) = &args.sitemap {
+        return fetch_sitemap_urls(sitemap, args.socks5.as_deref()).await;
+    }
+
     if args.stdin {
         let stdin = io::stdin();
         let urls: Vec<String> = stdin
@@ -115,94 +520,398 @@ async fn get_urls(args: &CheckLinksArgs) -> Result<Vec<String>> {
         let content = fs::read_to_string(file)
             .await
             .with_context(|| format!("Failed to read file: {}", file))?;
-        return Ok(extract_urls(&content));
+        let asciidoc = Path::new(file)
+            .extension()
+            .is_some_and(|e| e.eq_ignore_ascii_case("adoc") || e.eq_ignore_ascii_case("asciidoc"));
+        let mut urls = if asciidoc {
+            extract_asciidoc_urls(&content)
+        } else {
+            extract_urls(&content)
+        };
+        urls.extend(extract_scheme_urls(&content, &args.schemes));
+        // Relative links against a URL base are checked like any other URL
+        if let Some(Base::Url(base)) = args.base.as_deref().map(Base::parse) {
+            for link in relative_links(&content) {
+                if link.starts_with('#') {
+                    continue;
+                }
+                if let Ok(url) = base.join(&link) {
+                    if !urls.contains(&url.to_string()) {
+                        urls.push(url.to_string());
+                    }
+                }
+            }
+        }
+        return Ok(urls);
     }
 
     eprintln!("Usage:");
     eprintln!("  ref check-links <file.md>    Check URLs in markdown file");
     eprintln!("  ref check-links --url <URL>  Check single URL");
     eprintln!("  ref check-links --stdin      Read URLs from stdin");
+    eprintln!("  ref check-links --sitemap <URL>  Check pages listed in a sitemap");
     std::process::exit(1);
 }
 
+/// Check anchor-only links against the file's own headings, and relative paths
+/// against a directory base (URL bases are resolved in `read_urls` instead)
+async fn check_local_links(
+    file: &str,
+    base: &Base,
+    args: &CheckLinksArgs,
+) -> Result<Vec<LinkResult>> {
+    let content = fs::read_to_string(file)
+        .await
+        .with_context(|| format!("Failed to read file: {}", file))?;
+    let anchors = heading_anchors(&content);
+    let mut results = Vec::new();
+
+    for link in relative_links(&content) {
+        let result = if let Some(fragment) = link.strip_prefix('#') {
+            local_result(&link, anchors.contains(fragment), || {
+                format!("missing anchor #{}", fragment)
+            })
+        } else if let Base::Dir(dir) = base {
+            check_local_path(&link, dir, args.check_anchors).await
+        } else {
+            continue;
+        };
+        results.push(result);
+    }
+    Ok(results)
+}
+
+/// Check that a relative link's file exists under `dir` (and its #anchor, with --check-anchors)
+async fn check_local_path(link: &str, dir: &Path, check_anchors: bool) -> LinkResult {
+    let (path, fragment) = link.split_once('#').unwrap_or((link, ""));
+    let path = path.split('?').next().unwrap_or(path);
+    let target = dir.join(path.trim_start_matches('/'));
+
+    if !fs::try_exists(&target).await.unwrap_or(false) {
+        return local_result(link, false, || format!("not found: {}", target.display()));
+    }
+    let is_markdown = target.extension().is_some_and(|ext| ext == "md");
+    if check_anchors && is_markdown && !fragment.is_empty() {
+        let content = fs::read_to_string(&target).await.unwrap_or_default();
+        return local_result(link, heading_anchors(&content).contains(fragment), || {
+            format!("missing anchor #{}", fragment)
+        });
+    }
+    local_result(link, true, String::new)
+}
+
+/// 200 if found, 404 with `error` otherwise
+fn local_result(link: &str, found: bool, error: impl FnOnce() -> String) -> LinkResult {
+    LinkResult {
+        url: link.to_string(),
+        status: if found { 200 } else { 404 },
+        error: (!found).then(error),
+        redirect_to: None,
+        redirects: None,
+        skipped: false,
+        cited_in: Vec::new(),
+    }
+}
+
+/// Check a mailto: (address syntax), data: (well-formed, base64 decodes) or
+/// ftp: link (file exists on the server; not probed when `proxied`)
+async fn check_scheme_url(url: &str, timeout_ms: u64, proxied: bool) -> LinkResult {
+    if url
+        .get(..5)
+        .is_some_and(|s| s.eq_ignore_ascii_case("ftps:"))
+    {
+        // Needs TLS, which the FTP probe doesn't speak: left unchecked
+        return LinkResult {
+            error: Some("ftps:// is not checked".to_string()),
+            ..LinkResult::skipped(url.to_string())
+        };
+    }
+    if proxied && Scheme::of(url) == Some(Scheme::Ftp) {
+        // The FTP probe connects directly, which --socks5 must not do
+        return LinkResult {
+            error: Some("ftp:// is not checked with --socks5".to_string()),
+            ..LinkResult::skipped(url.to_string())
+        };
+    }
+    let checked = match Scheme::of(url) {
+        Some(Scheme::Mailto) => check_mailto(url).map_err(|e| (0, e)),
+        Some(Scheme::Data) => check_data_url(url).map_err(|e| (0, e)),
+        Some(Scheme::Ftp) => {
+            match ftp::exists(url, std::time::Duration::from_millis(timeout_ms)).await {
+                Ok(true) => Ok(()),
+                Ok(false) => Err((404, "not found on FTP server".to_string())),
+                Err(e) => Err((0, format!("{:#}", e))),
+            }
+        }
+        None => Err((0, "unsupported scheme".to_string())),
+    };
+    let (status, error) = match checked {
+        Ok(()) => (200, None),
+        Err((status, error)) => (status, Some(error)),
+    };
+    LinkResult {
+        url: url.to_string(),
+        status,
+        error,
+        redirect_to: None,
+        redirects: None,
+        skipped: false,
+        cited_in: Vec::new(),
+    }
+}
+
+/// Every address in `mailto:a@x.com,b@y.org?subject=...` must be valid
+fn check_mailto(url: &str) -> Result<(), String> {
+    let addresses = url[url.find(':').map_or(0, |i| i + 1)..]
+        .split('?')
+        .next()
+        .unwrap_or_default();
+    let addresses: Vec<&str> = addresses
+        .split(',')
+        .map(str::trim)
+        .filter(|a| !a.is_empty())
+        .collect();
+    if addresses.is_empty() {
+        return Err("no email address".to_string());
+    }
+    match addresses.iter().find(|a| !is_valid_email(a)) {
+        Some(address) => Err(format!("invalid email address: {}", address)),
+        None => Ok(()),
+    }
+}
+
+/// `data:[<mediatype>][;base64],<data>` with a decodable base64 payload
+fn check_data_url(url: &str) -> Result<(), String> {
+    let rest = &url[url.find(':').map_or(0, |i| i + 1)..];
+    let (header, payload) = rest
+        .split_once(',')
+        .ok_or_else(|| "malformed data URL: missing ','".to_string())?;
+    if header.to_ascii_lowercase().ends_with(";base64") {
+        base64::engine::general_purpose::STANDARD
+            .decode(payload)
+            .map_err(|e| format!("invalid base64 payload: {}", e))?;
+    }
+    Ok(())
+}
+
+/// Markdown link targets without a scheme (`./a.md`, `../b/`, `#section`), outside code fences
+fn relative_links(content: &str) -> Vec<String> {
+    let inline = Regex::new(r"\]\(\s*<?([^)\s>]+)").unwrap();
+    let reference = Regex::new(r"^\s{0,3}\[[^\]]+\]:\s*<?([^\s>]+)").unwrap();
+    let scheme = Regex::new(r"^[A-Za-z][A-Za-z0-9+.-]*:").unwrap();
+
+    let mut seen = HashSet::new();
+    let mut links = Vec::new();
+    for line in outside_code_fences(content) {
+        let targets = inline
+            .captures_iter(line)
+            .chain(reference.captures_iter(line));
+        for cap in targets {
+            let target = &cap[1];
+            if target == "#" || target.starts_with("//") || scheme.is_match(target) {
+                continue;
+            }
+            if seen.insert(target.to_string()) {
+                links.push(target.to_string());
+            }
+        }
+    }
+    links
+}
+
+/// Anchors a Markdown file defines: GitHub-style heading slugs plus explicit `id`/`name` attributes
+fn heading_anchors(content: &str) -> HashSet<String> {
+    let attr = Regex::new(r#"\b(?:id|name)=["']([^"']+)["']"#).unwrap();
+    let mut anchors = HashSet::new();
+    let mut counts: HashMap<String, usize> = HashMap::new();
+
+    for line in outside_code_fences(content) {
+        for cap in attr.captures_iter(line) {
+            anchors.insert(cap[1].to_string());
+        }
+        let trimmed = line.trim_start();
+        let level = trimmed.chars().take_while(|&c| c == '#').count();
+        if !(1..=6).contains(&level) || !trimmed[level..].starts_with(' ') {
+            continue;
+        }
+        let slug = slugify(trimmed[level..].trim().trim_end_matches('#').trim());
+        // Repeated headings get -1, -2, ... like GitHub
+        let count = counts.entry(slug.clone()).or_insert(0);
+        let anchor = match *count {
+            0 => slug,
+            n => format!("{}-{}", slug, n),
+        };
+        *count += 1;
+        anchors.insert(anchor);
+    }
+    anchors
+}
+
+/// GitHub heading slug: lowercase, punctuation dropped, spaces to hyphens
+fn slugify(heading: &str) -> String {
+    heading
+        .to_lowercase()
+        .chars()
+        .filter_map(|c| match c {
+            ' ' => Some('-'),
+            c if c.is_alphanumeric() || c == '-' || c == '_' => Some(c),
+            _ => None,
+        })
+        .collect()
+}
+
+fn outside_code_fences(content: &str) -> impl Iterator<Item = &str> {
+    let mut in_fence = false;
+    content.lines().filter(move |line| {
+        let fence = line.trim_start().starts_with("```") || line.trim_start().starts_with("~~~");
+        if fence {
+            in_fence = !in_fence;
+        }
+        !fence && !in_fence
+    })
+}
+
 /// Check multiple links and generate report
 pub async fn check_links(urls: &[String], config: &CheckLinksConfig) -> Result<LinkReport> {
-    let pool = BrowserPool::new(config.concurrency).await?;
     let mut results = Vec::with_capacity(urls.len());
+    let (ok, failed) = check_links_each(urls, config, |result| results.push(result)).await?;
+
+    Ok(LinkReport {
+        ok,
+        failed,
+        skipped: 0,
+        results,
+        by_domain: None,
+    })
+}
+
+/// Check multiple links, passing each result to `on_result` as soon as it completes
+///
+/// Returns the (ok, failed) counts.
+pub async fn check_links_each(
+    urls: &[String],
+    config: &CheckLinksConfig,
+    mut on_result: impl FnMut(LinkResult),
+) -> Result<(usize, usize)> {
+    if urls.is_empty() {
+        return Ok((0, 0));
+    }
+
+    let pool = BrowserPool::launch_or_connect(
+        config.concurrency,
+        config.connect.as_deref(),
+        config.socks5.as_deref(),
+    )
+    .await?
+    .with_auth(&config.auth)
+    .with_user_agent(config.user_agent.as_deref())
+    .await?
+    .with_resources(config.load_resources)
+    .with_wait_until(config.wait_until)
+    .with_challenge_wait(config.wait_challenge);
+    let robots = config
+        .respect_robots
+        .then(|| RobotsCache::new(pool.user_agent(), config.socks5.as_deref()))
+        .transpose()?;
     let mut ok_count = 0;
     let mut failed_count = 0;
 
     for url in urls {
-        eprintln!("  -> {}", truncate(url, 60));
-
-        let page = pool.new_page().await?;
-        let mut result = page.goto(url, config.timeout_ms).await?;
+        progress!("  -> {}", truncate(url, 60));
 
-        // Retry on failure
-        if result.status == 0 && config.retries > 0 {
-            tokio::time::sleep(std::time::Duration::from_secs(1)).await;
-            result = page.goto(url, config.timeout_ms).await?;
+        if let Some(robots) = &robots {
+            if !robots.is_allowed(url).await {
+                failed_count += 1;
+                events::item(url, false);
+                on_result(LinkResult {
+                    url: url.clone(),
+                    status: 0,
+                    error: Some("blocked by robots.txt".to_string()),
+                    redirect_to: None,
+                    redirects: None,
+                    skipped: false,
+                    cited_in: Vec::new(),
+                });
+                continue;
+            }
         }
 
-        // Determine if redirect (check final URL)
+        let page = pool.new_page().await?;
+        let result = retry_navigation(config.retries, || page.goto(url, config.timeout_ms)).await?;
+
+        // Determine if redirect (check final URL, including meta refresh and JS redirects)
         let redirect_to = if result.status >= 200 && result.status < 400 {
-            if let Some(final_url) = page.current_url().await {
-                // Check if different domain
-                let orig_host = url::Url::parse(url)
-                    .ok()
-                    .and_then(|u| u.host_str().map(String::from));
-                let final_host = url::Url::parse(&final_url)
-                    .ok()
-                    .and_then(|u| u.host_str().map(String::from));
-
-                if let (Some(orig), Some(fin)) = (orig_host, final_host) {
-                    let orig_norm = orig.trim_start_matches("www.");
-                    let fin_norm = fin.trim_start_matches("www.");
-                    if orig_norm != fin_norm {
-                        Some(final_url)
-                    } else {
-                        None
-                    }
-                } else {
-                    None
-                }
-            } else {
-                None
-            }
+            let final_url = match page.client_redirect().await {
+                Some(redirect) => Some(redirect.url),
+                None => page.current_url().await,
+            };
+            final_url.filter(|final_url| {
+                is_cross_domain(url, final_url, config.allow_subdomain_redirects)
+            })
         } else {
             None
         };
 
-        let is_ok = result.status >= 200 && result.status < 400 && redirect_to.is_none();
-        if is_ok {
+        let mut link = LinkResult {
+            url: url.clone(),
+            status: result.status,
+            error: result
+                .error
+                .or_else(|| result.challenge.then(|| "challenge page".to_string())),
+            redirect_to,
+            redirects: (!result.redirects.is_empty()).then_some(result.redirects),
+            skipped: false,
+            cited_in: Vec::new(),
+        };
+
+        // Docs rot: page loads fine but the #anchor is gone
+        if link.is_ok() && config.check_anchors {
+            if let Some(fragment) = anchor_fragment(url) {
+                if let Ok(html) = page.content().await {
+                    if !has_anchor(&html, &fragment) {
+                        link.error = Some(format!("missing anchor #{}", fragment));
+                    }
+                }
+            }
+        }
+
+        if link.is_ok() {
             ok_count += 1;
         } else {
             failed_count += 1;
         }
 
-        results.push(LinkResult {
-            url: url.clone(),
-            status: result.status,
-            error: result.error,
-            redirect_to,
-        });
+        events::item(url, link.is_ok());
+        on_result(link);
     }
 
     pool.close().await?;
 
-    Ok(LinkReport {
-        ok: ok_count,
-        failed: failed_count,
-        results,
-    })
+    Ok((ok_count, failed_count))
 }
 
-fn truncate(s: &str, max: usize) -> String {
-    if s.len() <= max {
-        s.to_string()
-    } else {
-        format!("{}...", &s[..max - 3])
+/// Fragment to verify, skipping `#top` and hash-bang/SPA routes (`#!/x`, `#/x`)
+fn anchor_fragment(url: &str) -> Option<String> {
+    let parsed = url::Url::parse(url).ok()?;
+    let fragment = parsed.fragment()?;
+    if fragment.is_empty()
+        || fragment.eq_ignore_ascii_case("top")
+        || fragment.starts_with('!')
+        || fragment.starts_with('/')
+    {
+        return None;
     }
+    Some(fragment.to_string())
+}
+
+/// Whether the page has an element with a matching `id` (or `<a name>`)
+fn has_anchor(html: &str, fragment: &str) -> bool {
+    let doc = Html::parse_document(html);
+    let Ok(sel) = Selector::parse("[id], a[name]") else {
+        return false;
+    };
+    doc.select(&sel)
+        .any(|el| el.value().id() == Some(fragment) || el.value().attr("name") == Some(fragment))
 }
 
 #[cfg(test)]
@@ -210,8 +919,254 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_truncate() {
-        assert_eq!(truncate("short", 10), "short");
-        assert_eq!(truncate("this is a very long string", 10), "this is...");
+    fn test_should_fail() {
+        assert!(!should_fail(2, false, None));
+        assert!(should_fail(1, true, None));
+        assert!(!should_fail(0, true, None));
+        assert!(!should_fail(2, false, Some(2)));
+        assert!(should_fail(3, false, Some(2)));
+        assert!(!should_fail(2, true, Some(5)));
+    }
+
+    #[test]
+    fn test_anchor_fragment() {
+        assert_eq!(
+            anchor_fragment("https://docs.rs/foo/struct.Foo.html#method.bar"),
+            Some("method.bar".to_string())
+        );
+        assert_eq!(anchor_fragment("https://example.com/page"), None);
+        assert_eq!(anchor_fragment("https://example.com/page#"), None);
+        assert_eq!(anchor_fragment("https://example.com/#top"), None);
+        assert_eq!(anchor_fragment("https://app.example.com/#/settings"), None);
+    }
+
+    #[test]
+    fn test_has_anchor() {
+        let html = std::fs::read_to_string("test-data/anchors.html").unwrap();
+        assert!(has_anchor(&html, "method.new"));
+        assert!(has_anchor(&html, "implementations"));
+        assert!(has_anchor(&html, "legacy-anchor"));
+        // Linked from docs but removed from the page
+        assert!(!has_anchor(&html, "method.bar"));
+    }
+
+    fn link(url: &str, status: u16) -> LinkResult {
+        LinkResult {
+            url: url.to_string(),
+            status,
+            error: None,
+            redirect_to: None,
+            redirects: None,
+            skipped: false,
+            cited_in: Vec::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_check_scheme_url() {
+        let result = check_scheme_url(
+            "mailto:team@example.com,ops@example.org?subject=Hi",
+            1000,
+            false,
+        )
+        .await;
+        assert!(result.is_ok());
+        let result = check_scheme_url("mailto:team@example", 1000, false).await;
+        assert_eq!(
+            result.error.as_deref(),
+            Some("invalid email address: team@example")
+        );
+        let result = check_scheme_url("mailto:?subject=Hi", 1000, false).await;
+        assert_eq!(result.error.as_deref(), Some("no email address"));
+
+        assert!(
+            check_scheme_url("data:image/png;base64,iVBORw0KGgo=", 1000, false)
+                .await
+                .is_ok()
+        );
+        assert!(check_scheme_url("data:,Hello%20world", 1000, false)
+            .await
+            .is_ok());
+        let result = check_scheme_url("data:image/png;base64,not base64!", 1000, false).await;
+        assert!(result.error.unwrap().starts_with("invalid base64 payload"));
+        let result = check_scheme_url("data:text/plain", 1000, false).await;
+        assert_eq!(
+            result.error.as_deref(),
+            Some("malformed data URL: missing ','")
+        );
+
+        // Nothing listening: reported, not fatal
+        let port = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let result =
+            check_scheme_url(&format!("ftp://127.0.0.1:{}/pub/x", port), 1000, false).await;
+        assert_eq!(result.status, 0);
+        assert!(result.error.unwrap().starts_with("connect to 127.0.0.1"));
+
+        // Not probed, so neither ok nor failed
+        let result = check_scheme_url("ftps://ftp.example.com/pub/x", 1000, false).await;
+        assert!(result.skipped);
+        assert_eq!(result.error.as_deref(), Some("ftps:// is not checked"));
+
+        // Never a direct connection behind --socks5
+        let result = check_scheme_url(&format!("ftp://127.0.0.1:{}/pub/x", port), 1000, true).await;
+        assert!(result.skipped);
+        assert_eq!(
+            result.error.as_deref(),
+            Some("ftp:// is not checked with --socks5")
+        );
+    }
+
+    #[test]
+    fn test_summary_omits_results() {
+        let mut report = LinkReport {
+            ok: 1,
+            failed: 1,
+            skipped: 0,
+            results: vec![link("https://a.com/", 200), link("https://b.com/", 404)],
+            by_domain: None,
+        };
+        let json = serde_json::to_value(report.summary()).unwrap();
+        assert_eq!(json, serde_json::json!({"ok": 1, "failed": 1}));
+
+        report.by_domain = Some(by_domain(&report.results));
+        let json = serde_json::to_value(report.summary()).unwrap();
+        assert!(json.get("results").is_none());
+        assert_eq!(
+            json["by_domain"]["b.com"]["failed_urls"][0],
+            "https://b.com/"
+        );
+    }
+
+    #[test]
+    fn test_dead_link_cited_in() {
+        let yaml = r#"
+meta:
+  created: "2024-01-01"
+  tool: ref
+  total_links: 3
+references:
+  - url: https://dead.example.com/paper
+    title: Gone
+    categories: [research]
+    cited_in: [docs/intro.md, README.md]
+    status: pending
+  - url: https://ok.example.com/
+    title: Fine
+    categories: [research]
+    cited_in: [README.md]
+    status: pending
+  - url: https://dead.example.com/paper
+    title: Gone again
+    categories: [research]
+    cited_in: [README.md, docs/design.md]
+    status: pending
+"#;
+        let refs_file = ReferencesFile::parse(yaml, Path::new("references.yaml")).unwrap();
+        let (urls, sources) = reference_urls(refs_file);
+        assert_eq!(
+            urls,
+            ["https://dead.example.com/paper", "https://ok.example.com/"]
+        );
+
+        let mut dead = link("https://dead.example.com/paper", 404);
+        add_sources(&mut dead, &sources);
+        assert_eq!(
+            dead.cited_in,
+            ["docs/intro.md", "README.md", "docs/design.md"]
+        );
+        let json = serde_json::to_value(&dead).unwrap();
+        assert_eq!(json["cited_in"][0], "docs/intro.md");
+
+        // Working links stay compact
+        let mut ok = link("https://ok.example.com/", 200);
+        add_sources(&mut ok, &sources);
+        assert!(ok.cited_in.is_empty());
+        assert!(serde_json::to_value(&ok).unwrap().get("cited_in").is_none());
+    }
+
+    #[test]
+    fn test_by_domain() {
+        let mut redirected = link("https://c.com/moved", 200);
+        redirected.redirect_to = Some("https://d.com/".to_string());
+        let results = vec![
+            link("https://www.a.com/1", 200),
+            link("https://a.com/2", 404),
+            link("https://b.com/x", 301),
+            redirected,
+            LinkResult::skipped("https://e.com/".to_string()),
+            link("./missing.md", 404),
+        ];
+
+        let domains = by_domain(&results);
+        assert_eq!(
+            domains.keys().collect::<Vec<_>>(),
+            ["a.com", "b.com", "c.com", "local"]
+        );
+        assert_eq!(
+            domains["a.com"],
+            DomainStats {
+                total: 2,
+                ok: 1,
+                failed: 1,
+                failed_urls: vec!["https://a.com/2".to_string()],
+            }
+        );
+        assert_eq!(domains["b.com"].ok, 1);
+        assert_eq!(domains["c.com"].failed_urls, ["https://c.com/moved"]);
+        assert_eq!(domains["local"].failed, 1);
+    }
+
+    const DOC: &str =
+        "# Guide\n\n## Set up (fast)\n\n## Set up (fast)\n\n<a id=\"legacy\"></a>\n\n\
+        See [other](./other.md), [api](../api/x.md#new), [top](#guide) and [gone](#old).\n\
+        [web](https://example.com) [mail](mailto:a@b.com)\n\n\
+        [ref]: sub/page.md\n\n```\n[code](./not-a-link.md)\n# not a heading\n```\n";
+
+    #[test]
+    fn test_relative_links() {
+        assert_eq!(
+            relative_links(DOC),
+            [
+                "./other.md",
+                "../api/x.md#new",
+                "#guide",
+                "#old",
+                "sub/page.md"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_heading_anchors() {
+        let anchors = heading_anchors(DOC);
+        assert!(anchors.contains("guide"));
+        assert!(anchors.contains("set-up-fast"));
+        assert!(anchors.contains("set-up-fast-1"));
+        assert!(anchors.contains("legacy"));
+        assert!(!anchors.contains("not-a-heading"));
+    }
+
+    #[tokio::test]
+    async fn test_check_local_path() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("other.md"), "# Intro\n").unwrap();
+
+        let found = check_local_path("./other.md", dir.path(), false).await;
+        assert!(found.is_ok());
+
+        let missing = check_local_path("./missing.md", dir.path(), false).await;
+        assert!(!missing.is_ok());
+        assert_eq!(missing.status, 404);
+        assert!(missing.error.unwrap().starts_with("not found:"));
+
+        let anchor = check_local_path("other.md#intro", dir.path(), true).await;
+        assert!(anchor.is_ok());
+        let anchor = check_local_path("other.md#outro", dir.path(), true).await;
+        assert_eq!(anchor.error.as_deref(), Some("missing anchor #outro"));
     }
 }
@@ -0,0 +1,358 @@
+//! Config file: per-subcommand defaults from `.ref-tools.toml`
+//!
+//! Looked up in the current directory, then `$HOME`. Values replace built-in
+//! defaults only; flags given on the command line (or via env) always win.
+//!
+//! ```toml
+//! [defaults]          # shared by all browser commands
+//! timeout = 45000
+//! user-agent = "chrome-latest"
+//! connect = "http://localhost:9222"
+//!
+//! [refresh-data]
+//! socks5 = "127.0.0.1:9050"
+//!
+//! [check-links]       # per-command sections override [defaults]
+//! parallel = 10
+//! ```
+
+use crate::check_links::CheckLinksArgs;
+use crate::fetch::FetchArgs;
+use crate::refresh_data::RefreshDataArgs;
+use crate::verify_refs::VerifyRefsArgs;
+use anyhow::{bail, Context, Result};
+use clap::parser::ValueSource;
+use clap::ArgMatches;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+/// Config file name
+pub const CONFIG_FILE: &str = ".ref-tools.toml";
+
+/// Parsed `.ref-tools.toml`
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Config {
+    #[serde(default)]
+    pub defaults: CommandDefaults,
+    #[serde(default)]
+    pub fetch: CommandDefaults,
+    #[serde(default)]
+    pub verify_refs: CommandDefaults,
+    #[serde(default)]
+    pub check_links: CommandDefaults,
+    #[serde(default)]
+    pub refresh_data: CommandDefaults,
+}
+
+/// Default flag values for a subcommand (unset = built-in default)
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct CommandDefaults {
+    /// Timeout per URL in milliseconds
+    pub timeout: Option<u64>,
+    /// Parallel browser tabs (`--concurrency` for check-links)
+    pub parallel: Option<usize>,
+    /// Retries on network failure
+    pub retries: Option<u8>,
+    /// DevTools URL of a running Chrome
+    pub connect: Option<String>,
+    /// SOCKS5 proxy (host:port); `proxy` is accepted as an alias
+    #[serde(alias = "proxy")]
+    pub socks5: Option<String>,
+    /// User agent string (or "chrome-latest")
+    pub user_agent: Option<String>,
+    /// Same registrable domain redirects count as ok (verify-refs, check-links)
+    pub allow_subdomain_redirects: Option<bool>,
+}
+
+impl CommandDefaults {
+    /// These values, falling back to `base` for unset ones
+    fn or(&self, base: &CommandDefaults) -> CommandDefaults {
+        CommandDefaults {
+            timeout: self.timeout.or(base.timeout),
+            parallel: self.parallel.or(base.parallel),
+            retries: self.retries.or(base.retries),
+            connect: self.connect.clone().or_else(|| base.connect.clone()),
+            socks5: self.socks5.clone().or_else(|| base.socks5.clone()),
+            user_agent: self.user_agent.clone().or_else(|| base.user_agent.clone()),
+            allow_subdomain_redirects: self
+                .allow_subdomain_redirects
+                .or(base.allow_subdomain_redirects),
+        }
+    }
+}
+
+impl Config {
+    /// Find and load `.ref-tools.toml` from the current directory or `$HOME`
+    pub fn discover() -> Result<Option<(PathBuf, Config)>> {
+        let home = std::env::var_os("HOME").map(PathBuf::from);
+        let candidates = [
+            Some(PathBuf::from(CONFIG_FILE)),
+            home.map(|h| h.join(CONFIG_FILE)),
+        ];
+
+        for path in candidates.into_iter().flatten() {
+            if path.is_file() {
+                let config = Self::load(&path)?;
+                return Ok(Some((path, config)));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Load a config file
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config file: {}", path.display()))?;
+        Self::parse(&content)
+            .with_context(|| format!("Failed to parse config file: {}", path.display()))
+    }
+
+    /// Parse config file contents
+    pub fn parse(content: &str) -> Result<Self> {
+        Ok(toml::from_str(content)?)
+    }
+
+    /// Apply `[fetch]` defaults to flags not given explicitly
+    pub fn apply_fetch(&self, args: &mut FetchArgs, matches: &ArgMatches) {
+        let d = self.fetch.or(&self.defaults);
+        set(&mut args.timeout, d.timeout, matches, "timeout");
+        set(&mut args.parallel, d.parallel, matches, "parallel");
+        set(&mut args.retries, d.retries, matches, "retries");
+        set_route(&mut args.connect, &mut args.socks5, &d, matches);
+        set(
+            &mut args.user_agent,
+            d.user_agent.map(Some),
+            matches,
+            "user_agent",
+        );
+    }
+
+    /// Apply `[verify-refs]` defaults to flags not given explicitly
+    pub fn apply_verify_refs(&self, args: &mut VerifyRefsArgs, matches: &ArgMatches) {
+        let d = self.verify_refs.or(&self.defaults);
+        set(&mut args.timeout, d.timeout, matches, "timeout");
+        set(&mut args.parallel, d.parallel, matches, "parallel");
+        set(&mut args.retries, d.retries, matches, "retries");
+        set(
+            &mut args.allow_subdomain_redirects,
+            d.allow_subdomain_redirects,
+            matches,
+            "allow_subdomain_redirects",
+        );
+        set_route(&mut args.connect, &mut args.socks5, &d, matches);
+        set(
+            &mut args.user_agent,
+            d.user_agent.map(Some),
+            matches,
+            "user_agent",
+        );
+    }
+
+    /// Apply `[check-links]` defaults to flags not given explicitly
+    pub fn apply_check_links(&self, args: &mut CheckLinksArgs, matches: &ArgMatches) -> Result<()> {
+        let d = self.check_links.or(&self.defaults);
+        let concurrency = match d.parallel {
+            Some(n @ 1..=20) => Some(n as u8),
+            Some(n) => bail!("check-links: parallel must be 1-20, got {}", n),
+            None => None,
+        };
+        set(&mut args.timeout, d.timeout, matches, "timeout");
+        set(&mut args.concurrency, concurrency, matches, "concurrency");
+        set(&mut args.retries, d.retries, matches, "retries");
+        set(
+            &mut args.allow_subdomain_redirects,
+            d.allow_subdomain_redirects,
+            matches,
+            "allow_subdomain_redirects",
+        );
+        set_route(&mut args.connect, &mut args.socks5, &d, matches);
+        set(
+            &mut args.user_agent,
+            d.user_agent.map(Some),
+            matches,
+            "user_agent",
+        );
+        Ok(())
+    }
+
+    /// Apply `[refresh-data]` defaults to flags not given explicitly
+    pub fn apply_refresh_data(&self, args: &mut RefreshDataArgs, matches: &ArgMatches) {
+        let d = self.refresh_data.or(&self.defaults);
+        set(&mut args.timeout, d.timeout, matches, "timeout");
+        set(&mut args.parallel, d.parallel, matches, "parallel");
+        set_route(&mut args.connect, &mut args.socks5, &d, matches);
+        set(
+            &mut args.user_agent,
+            d.user_agent.map(Some),
+            matches,
+            "user_agent",
+        );
+    }
+}
+
+/// Apply `connect` and `socks5`; the flags conflict, so one given
+/// explicitly also drops the other's config value
+fn set_route(
+    connect: &mut Option<String>,
+    socks5: &mut Option<String>,
+    d: &CommandDefaults,
+    matches: &ArgMatches,
+) {
+    set(connect, d.connect.clone().map(Some), matches, "connect");
+    set(socks5, d.socks5.clone().map(Some), matches, "socks5");
+    if explicit(matches, "connect") && !explicit(matches, "socks5") {
+        *socks5 = None;
+    }
+    if explicit(matches, "socks5") && !explicit(matches, "connect") {
+        *connect = None;
+    }
+}
+
+/// Whether the flag was set on the command line or via env
+fn explicit(matches: &ArgMatches, id: &str) -> bool {
+    matches!(
+        matches.value_source(id),
+        Some(ValueSource::CommandLine | ValueSource::EnvVariable)
+    )
+}
+
+/// Use the config value unless the flag was set on the command line or via env
+fn set<T>(target: &mut T, value: Option<T>, matches: &ArgMatches, id: &str) {
+    if let (Some(value), false) = (value, explicit(matches, id)) {
+        *target = value;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::{CommandFactory, FromArgMatches, Parser};
+
+    #[derive(Parser)]
+    struct FetchCli {
+        #[command(flatten)]
+        args: FetchArgs,
+    }
+
+    #[derive(Parser)]
+    struct CheckLinksCli {
+        #[command(flatten)]
+        args: CheckLinksArgs,
+    }
+
+    fn fetch_args(config: &Config, argv: &[&str]) -> FetchArgs {
+        let matches = FetchCli::command().get_matches_from(argv);
+        let mut args = FetchCli::from_arg_matches(&matches).unwrap().args;
+        config.apply_fetch(&mut args, &matches);
+        args
+    }
+
+    #[test]
+    fn test_precedence() {
+        let config = Config::parse("[fetch]\ntimeout = 60000\nparallel = 8\n").unwrap();
+
+        // Built-in default when neither config nor flag sets it
+        let args = fetch_args(&Config::default(), &["ref", "https://a.com"]);
+        assert_eq!(args.timeout, 30000);
+
+        // Config beats the built-in default
+        let args = fetch_args(&config, &["ref", "https://a.com"]);
+        assert_eq!(args.timeout, 60000);
+        assert_eq!(args.parallel, 8);
+        assert_eq!(args.retries, 1);
+
+        // CLI flag beats config, even when equal to the built-in default
+        let args = fetch_args(
+            &config,
+            &["ref", "https://a.com", "--timeout", "30000", "-p", "2"],
+        );
+        assert_eq!(args.timeout, 30000);
+        assert_eq!(args.parallel, 2);
+    }
+
+    #[test]
+    fn test_section_overrides_defaults() {
+        let config = Config::parse(
+            "[defaults]\ntimeout = 45000\nuser-agent = \"chrome-latest\"\n\n[fetch]\ntimeout = 10000\nconnect = \"http://localhost:9222\"\n",
+        )
+        .unwrap();
+        let args = fetch_args(&config, &["ref", "https://a.com"]);
+        assert_eq!(args.timeout, 10000);
+        assert_eq!(args.connect.as_deref(), Some("http://localhost:9222"));
+        assert_eq!(args.user_agent.as_deref(), Some("chrome-latest"));
+    }
+
+    #[test]
+    fn test_check_links_parallel() {
+        let matches = CheckLinksCli::command().get_matches_from(["ref"]);
+        let mut args = CheckLinksCli::from_arg_matches(&matches).unwrap().args;
+
+        let config = Config::parse("[check-links]\nparallel = 12\n").unwrap();
+        config.apply_check_links(&mut args, &matches).unwrap();
+        assert_eq!(args.concurrency, 12);
+
+        let config = Config::parse("[check-links]\nparallel = 50\n").unwrap();
+        assert!(config.apply_check_links(&mut args, &matches).is_err());
+    }
+
+    #[test]
+    fn test_socks5() {
+        let config = Config::parse("[defaults]\nsocks5 = \"127.0.0.1:9050\"\n").unwrap();
+        let args = fetch_args(&config, &["ref", "https://a.com"]);
+        assert_eq!(args.socks5.as_deref(), Some("127.0.0.1:9050"));
+
+        let matches = CheckLinksCli::command().get_matches_from(["ref"]);
+        let mut args = CheckLinksCli::from_arg_matches(&matches).unwrap().args;
+        config.apply_check_links(&mut args, &matches).unwrap();
+        assert_eq!(args.socks5.as_deref(), Some("127.0.0.1:9050"));
+
+        // CLI flag beats config
+        let args = fetch_args(
+            &config,
+            &["ref", "https://a.com", "--socks5", "proxy.lan:1080"],
+        );
+        assert_eq!(args.socks5.as_deref(), Some("proxy.lan:1080"));
+
+        // --connect conflicts with --socks5, so it drops the config proxy
+        let args = fetch_args(
+            &config,
+            &["ref", "https://a.com", "--connect", "http://localhost:9222"],
+        );
+        assert_eq!(args.connect.as_deref(), Some("http://localhost:9222"));
+        assert_eq!(args.socks5, None);
+    }
+
+    #[test]
+    fn test_proxy_alias() {
+        let config = Config::parse("[fetch]\nproxy = \"127.0.0.1:9050\"\n").unwrap();
+        let args = fetch_args(&config, &["ref", "https://a.com"]);
+        assert_eq!(args.socks5.as_deref(), Some("127.0.0.1:9050"));
+
+        let args = fetch_args(
+            &config,
+            &["ref", "https://a.com", "--socks5", "proxy.lan:1080"],
+        );
+        assert_eq!(args.socks5.as_deref(), Some("proxy.lan:1080"));
+    }
+
+    #[test]
+    fn test_allow_subdomain_redirects() {
+        let config = Config::parse("[defaults]\nallow-subdomain-redirects = true\n").unwrap();
+        let matches = CheckLinksCli::command().get_matches_from(["ref"]);
+        let mut args = CheckLinksCli::from_arg_matches(&matches).unwrap().args;
+        config.apply_check_links(&mut args, &matches).unwrap();
+        assert!(args.allow_subdomain_redirects);
+
+        // Fetch has no such flag; the key is ignored there
+        let args = fetch_args(&config, &["ref", "https://a.com"]);
+        assert_eq!(args.timeout, 30000);
+    }
+
+    #[test]
+    fn test_parse_unknown_key() {
+        assert!(Config::parse("[fetch]\ntimeuot = 5\n").is_err());
+        assert!(Config::parse("[fetsh]\ntimeout = 5\n").is_err());
+    }
+}
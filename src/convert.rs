@@ -0,0 +1,43 @@
+//! convert command: Switch a references file between YAML and JSON
+//!
+//! The format of each side comes from its extension (`.json` = JSON).
+
+use crate::schema::{is_json_path, ReferencesFile};
+use anyhow::Result;
+use clap::Args;
+use serde::Serialize;
+use std::path::PathBuf;
+
+#[derive(Args)]
+pub struct ConvertArgs {
+    /// File to read (e.g. references.yaml)
+    pub input: PathBuf,
+
+    /// File to write (e.g. references.json)
+    pub output: PathBuf,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ConvertOutput {
+    pub file: String,
+    pub format: &'static str,
+    pub references: usize,
+}
+
+pub async fn run_convert(args: ConvertArgs) -> Result<()> {
+    let refs_file = ReferencesFile::load(&args.input).await?;
+    refs_file.save(&args.output).await?;
+
+    let output = ConvertOutput {
+        file: args.output.display().to_string(),
+        format: if is_json_path(&args.output) {
+            "json"
+        } else {
+            "yaml"
+        },
+        references: refs_file.references.len(),
+    };
+    println!("{}", serde_json::to_string(&output)?);
+
+    Ok(())
+}
@@ -0,0 +1,191 @@
+//! Cookie files for authenticated fetches
+//!
+//! Supports Netscape cookie jars (curl, wget, browser extensions) and JSON
+//! arrays of `{name, value, domain, path}` objects (DevTools/extension exports).
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use std::path::Path;
+
+/// A single cookie to install in the browser before navigation
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Cookie {
+    pub name: String,
+    pub value: String,
+    pub domain: String,
+    #[serde(default = "default_path")]
+    pub path: String,
+    #[serde(default)]
+    pub secure: bool,
+    #[serde(default)]
+    pub http_only: bool,
+    /// Expiry as seconds since epoch (None = session cookie)
+    #[serde(default, alias = "expirationDate")]
+    pub expires: Option<f64>,
+}
+
+fn default_path() -> String {
+    "/".to_string()
+}
+
+/// Load cookies from a Netscape or JSON cookie file
+pub async fn load_cookies(path: &Path) -> Result<Vec<Cookie>> {
+    let content = tokio::fs::read_to_string(path)
+        .await
+        .with_context(|| format!("Failed to read cookie file: {}", path.display()))?;
+    parse_cookies(&content)
+        .with_context(|| format!("Failed to parse cookie file: {}", path.display()))
+}
+
+/// Parse cookie file content, detecting the format (JSON array or Netscape)
+pub fn parse_cookies(content: &str) -> Result<Vec<Cookie>> {
+    if content.trim_start().starts_with('[') {
+        let cookies: Vec<Cookie> =
+            serde_json::from_str(content).context("Invalid JSON cookie array")?;
+        return Ok(cookies);
+    }
+    parse_netscape(content)
+}
+
+/// Parse a Netscape cookie jar (7 tab-separated fields per line)
+fn parse_netscape(content: &str) -> Result<Vec<Cookie>> {
+    let mut cookies = Vec::new();
+
+    for (i, line) in content.lines().enumerate() {
+        let line = line.trim_end_matches('\r');
+
+        // "#HttpOnly_" prefixed lines are cookies, other "#" lines are comments
+        let (line, http_only) = match line.strip_prefix("#HttpOnly_") {
+            Some(rest) => (rest, true),
+            None => (line, false),
+        };
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let fields: Vec<&str> = line.split('\t').collect();
+        if fields.len() != 7 {
+            bail!(
+                "line {}: expected 7 tab-separated fields (domain, subdomains, path, secure, expires, name, value), found {}",
+                i + 1,
+                fields.len()
+            );
+        }
+
+        let expires: f64 = fields[4]
+            .parse()
+            .with_context(|| format!("line {}: invalid expiry '{}'", i + 1, fields[4]))?;
+
+        cookies.push(Cookie {
+            name: fields[5].to_string(),
+            value: fields[6].to_string(),
+            domain: fields[0].to_string(),
+            path: fields[2].to_string(),
+            secure: fields[3].eq_ignore_ascii_case("TRUE"),
+            http_only,
+            expires: (expires > 0.0).then_some(expires),
+        });
+    }
+
+    Ok(cookies)
+}
+
+/// `Cookie` header value for a request to `url` outside the browser
+/// (None when no cookie applies)
+pub fn cookie_header(cookies: &[Cookie], url: &str) -> Option<String> {
+    let url = url::Url::parse(url).ok()?;
+    let host = url.host_str()?;
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0.0, |d| d.as_secs_f64());
+    let pairs: Vec<String> = cookies
+        .iter()
+        .filter(|c| {
+            let domain = c.domain.trim_start_matches('.');
+            (host == domain || host.ends_with(&format!(".{}", domain)))
+                && url.path().starts_with(&c.path)
+                && (!c.secure || url.scheme() == "https")
+                && c.expires.is_none_or(|expires| expires > now)
+        })
+        .map(|c| format!("{}={}", c.name, c.value))
+        .collect();
+    (!pairs.is_empty()).then(|| pairs.join("; "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_netscape() {
+        let content = "# Netscape HTTP Cookie File\n\
+            \n\
+            .example.com\tTRUE\t/\tTRUE\t1767225600\tsession\tabc123\n\
+            #HttpOnly_news.example.org\tFALSE\t/articles\tFALSE\t0\ttoken\txyz\n";
+        let cookies = parse_cookies(content).unwrap();
+        assert_eq!(cookies.len(), 2);
+        assert_eq!(cookies[0].name, "session");
+        assert_eq!(cookies[0].value, "abc123");
+        assert_eq!(cookies[0].domain, ".example.com");
+        assert!(cookies[0].secure);
+        assert!(!cookies[0].http_only);
+        assert_eq!(cookies[0].expires, Some(1767225600.0));
+        assert_eq!(cookies[1].domain, "news.example.org");
+        assert_eq!(cookies[1].path, "/articles");
+        assert!(cookies[1].http_only);
+        assert_eq!(cookies[1].expires, None);
+    }
+
+    #[test]
+    fn test_parse_json() {
+        let content = r#"[
+            {"name": "sid", "value": "42", "domain": ".example.com"},
+            {"name": "pref", "value": "dark", "domain": "example.com", "path": "/app",
+             "secure": true, "httpOnly": true, "expirationDate": 1767225600.5}
+        ]"#;
+        let cookies = parse_cookies(content).unwrap();
+        assert_eq!(cookies.len(), 2);
+        assert_eq!(cookies[0].path, "/");
+        assert!(!cookies[0].secure);
+        assert_eq!(cookies[1].path, "/app");
+        assert!(cookies[1].http_only);
+        assert_eq!(cookies[1].expires, Some(1767225600.5));
+    }
+
+    #[test]
+    fn test_parse_malformed() {
+        let err = parse_cookies("example.com\tTRUE\t/\n").unwrap_err();
+        assert!(err.to_string().contains("line 1"));
+
+        let err = parse_cookies("example.com\tTRUE\t/\tFALSE\tsoon\tname\tvalue\n").unwrap_err();
+        assert!(err.to_string().contains("invalid expiry"));
+
+        assert!(parse_cookies(r#"[{"name": "x"}]"#).is_err());
+    }
+
+    #[test]
+    fn test_cookie_header() {
+        let cookies = parse_cookies(
+            ".example.com\tTRUE\t/\tFALSE\t0\tsid\t42\n\
+             example.com\tFALSE\t/papers\tTRUE\t0\ttoken\txyz\n\
+             example.com\tFALSE\t/\tFALSE\t1000\told\tgone\n\
+             other.org\tFALSE\t/\tFALSE\t0\tx\ty\n",
+        )
+        .unwrap();
+        assert_eq!(
+            cookie_header(&cookies, "https://example.com/papers/a.pdf").as_deref(),
+            Some("sid=42; token=xyz")
+        );
+        // Secure cookies only over https, path must match, expired ones dropped
+        assert_eq!(
+            cookie_header(&cookies, "http://example.com/papers/a.pdf").as_deref(),
+            Some("sid=42")
+        );
+        assert_eq!(
+            cookie_header(&cookies, "https://example.com/about").as_deref(),
+            Some("sid=42")
+        );
+        assert_eq!(cookie_header(&cookies, "https://notexample.com/"), None);
+    }
+}
@@ -0,0 +1,352 @@
+//! DOI detection and Crossref lookup
+//!
+//! Used by `verify-refs --enrich-doi` to confirm a cited work exists and
+//! record its title, authors and year. DOIs from other registration agencies
+//! (DataCite for arXiv, Zenodo and most datasets) aren't in Crossref, so a
+//! miss there is checked against the doi.org handle API.
+
+use crate::browser::http_client;
+use anyhow::{bail, Context, Result};
+use regex::Regex;
+use serde::Deserialize;
+use std::time::Duration;
+
+/// Crossref REST API (metadata for Crossref-registered DOIs)
+pub const CROSSREF_API: &str = "https://api.crossref.org";
+
+/// doi.org handle API (existence of any DOI, whatever its agency)
+pub const HANDLE_API: &str = "https://doi.org/api/handles";
+
+/// Outcome of [`lookup_doi`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum DoiLookup {
+    /// In Crossref, with metadata
+    Work(DoiWork),
+    /// Registered with another agency (no metadata)
+    Registered,
+    /// Not registered anywhere
+    Unknown,
+}
+
+/// Work metadata resolved from a DOI
+#[derive(Debug, Clone, PartialEq)]
+pub struct DoiWork {
+    pub title: Option<String>,
+    /// "Given Family" names in author order
+    pub authors: Vec<String>,
+    pub year: Option<i32>,
+}
+
+impl DoiWork {
+    /// One-line summary for notes: "DOI 10.x/y: Title, A. Author et al. (2017)"
+    pub fn summary(&self, doi: &str) -> String {
+        let mut summary = format!("DOI {}", doi);
+        if let Some(title) = &self.title {
+            summary.push_str(&format!(": {}", title));
+        }
+        match self.authors.as_slice() {
+            [] => {}
+            [one] => summary.push_str(&format!(", {}", one)),
+            [first, ..] => summary.push_str(&format!(", {} et al.", first)),
+        }
+        if let Some(year) = self.year {
+            summary.push_str(&format!(" ({})", year));
+        }
+        summary
+    }
+}
+
+/// DOI in a reference URL: a doi.org link, or a `10.NNNN/...` path segment
+/// (publisher URLs like `https://dl.acm.org/doi/10.1145/3368089`)
+pub fn find_doi(url: &str) -> Option<String> {
+    let parsed = url::Url::parse(url).ok()?;
+    let path = percent_decode(parsed.path());
+    let re = Regex::new(r"(10\.\d{4,9}/[^\s?#]+)").unwrap();
+    let doi = re.captures(&path)?[1]
+        .trim_end_matches(['.', ',', ';'])
+        .to_string();
+
+    // Publisher paths may carry a suffix after the DOI ("/10.1145/3368089/full")
+    let is_doi_host = matches!(parsed.host_str(), Some("doi.org" | "dx.doi.org"));
+    Some(if is_doi_host {
+        doi
+    } else {
+        doi.split('/').take(2).collect::<Vec<_>>().join("/")
+    })
+}
+
+/// Client for DOI lookups (Crossref asks for an identifying user agent)
+pub fn client(socks5: Option<&str>) -> Result<reqwest::Client> {
+    Ok(http_client(socks5)?
+        .user_agent(concat!(
+            "ref/",
+            env!("CARGO_PKG_VERSION"),
+            " (https://github.com/royalbit/ref)"
+        ))
+        .timeout(Duration::from_secs(20))
+        .build()?)
+}
+
+/// Look up a DOI; `Ok(None)` when the registry doesn't know it
+pub async fn resolve_doi(
+    client: &reqwest::Client,
+    api: &str,
+    doi: &str,
+) -> Result<Option<DoiWork>> {
+    let url = format!("{}/works/{}", api.trim_end_matches('/'), doi);
+    let response = client
+        .get(&url)
+        .send()
+        .await
+        .with_context(|| format!("Failed to query {}", url))?;
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    if !response.status().is_success() {
+        bail!("DOI lookup returned {}", response.status());
+    }
+    let body: CrossrefResponse = response.json().await.context("Invalid Crossref response")?;
+    Ok(Some(body.message.into_work()))
+}
+
+/// Crossref first, then the doi.org handle API for DOIs Crossref doesn't know
+pub async fn lookup_doi(
+    client: &reqwest::Client,
+    crossref_api: &str,
+    handle_api: &str,
+    doi: &str,
+) -> Result<DoiLookup> {
+    if let Some(work) = resolve_doi(client, crossref_api, doi).await? {
+        return Ok(DoiLookup::Work(work));
+    }
+    Ok(if is_registered(client, handle_api, doi).await? {
+        DoiLookup::Registered
+    } else {
+        DoiLookup::Unknown
+    })
+}
+
+/// Whether doi.org has a handle for `doi` (responseCode 1; 100 = not found)
+async fn is_registered(client: &reqwest::Client, api: &str, doi: &str) -> Result<bool> {
+    let url = format!("{}/{}", api.trim_end_matches('/'), doi);
+    let response = client
+        .get(&url)
+        .send()
+        .await
+        .with_context(|| format!("Failed to query {}", url))?;
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(false);
+    }
+    if !response.status().is_success() {
+        bail!("DOI handle lookup returned {}", response.status());
+    }
+    let body: HandleResponse = response.json().await.context("Invalid handle response")?;
+    Ok(body.response_code == 1)
+}
+
+#[derive(Deserialize)]
+struct HandleResponse {
+    #[serde(rename = "responseCode")]
+    response_code: i32,
+}
+
+#[derive(Deserialize)]
+struct CrossrefResponse {
+    message: CrossrefWork,
+}
+
+#[derive(Deserialize)]
+struct CrossrefWork {
+    #[serde(default)]
+    title: Vec<String>,
+    #[serde(default)]
+    author: Vec<CrossrefAuthor>,
+    issued: Option<CrossrefDate>,
+}
+
+#[derive(Deserialize)]
+struct CrossrefAuthor {
+    given: Option<String>,
+    family: Option<String>,
+    /// Organizational authors
+    name: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct CrossrefDate {
+    #[serde(rename = "date-parts", default)]
+    date_parts: Vec<Vec<Option<i32>>>,
+}
+
+impl CrossrefWork {
+    fn into_work(self) -> DoiWork {
+        DoiWork {
+            title: self
+                .title
+                .into_iter()
+                .map(|t| t.split_whitespace().collect::<Vec<_>>().join(" "))
+                .find(|t| !t.is_empty()),
+            authors: self
+                .author
+                .into_iter()
+                .filter_map(|a| match (a.given, a.family, a.name) {
+                    (Some(given), Some(family), _) => Some(format!("{} {}", given, family)),
+                    (None, Some(family), _) => Some(family),
+                    (_, None, name) => name,
+                })
+                .collect(),
+            year: self
+                .issued
+                .and_then(|d| d.date_parts.first().and_then(|p| p.first().copied()))
+                .flatten(),
+        }
+    }
+}
+
+/// Decode %XX escapes (DOIs in URLs often have an encoded slash)
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|h| std::str::from_utf8(h).ok());
+        match (bytes[i], hex.and_then(|h| u8::from_str_radix(h, 16).ok())) {
+            (b'%', Some(byte)) => {
+                out.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                out.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_doi() {
+        assert_eq!(
+            find_doi("https://doi.org/10.48550/arXiv.1706.03762").as_deref(),
+            Some("10.48550/arXiv.1706.03762")
+        );
+        assert_eq!(
+            find_doi("https://dx.doi.org/10.1000%2F182").as_deref(),
+            Some("10.1000/182")
+        );
+        assert_eq!(
+            find_doi("https://dl.acm.org/doi/10.1145/3368089.3409742/full?x=1").as_deref(),
+            Some("10.1145/3368089.3409742")
+        );
+        assert_eq!(find_doi("https://example.com/10.5/short"), None);
+        assert_eq!(find_doi("https://arxiv.org/abs/1706.03762"), None);
+    }
+
+    #[test]
+    fn test_summary() {
+        let work = DoiWork {
+            title: Some("Attention Is All You Need".to_string()),
+            authors: vec!["Ashish Vaswani".to_string(), "Noam Shazeer".to_string()],
+            year: Some(2017),
+        };
+        assert_eq!(
+            work.summary("10.1/x"),
+            "DOI 10.1/x: Attention Is All You Need, Ashish Vaswani et al. (2017)"
+        );
+        let bare = DoiWork {
+            title: None,
+            authors: vec![],
+            year: None,
+        };
+        assert_eq!(bare.summary("10.1/x"), "DOI 10.1/x");
+    }
+
+    #[tokio::test]
+    async fn test_resolve_doi() {
+        use wiremock::matchers::path;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(path("/works/10.1234/found"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "status": "ok",
+                "message": {
+                    "title": ["A  Study\n of Things"],
+                    "author": [
+                        {"given": "Ada", "family": "Lovelace"},
+                        {"name": "The Consortium"}
+                    ],
+                    "issued": {"date-parts": [[2019, 4, 2]]}
+                }
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(path("/works/10.1234/missing"))
+            .respond_with(ResponseTemplate::new(404).set_body_string("Resource not found."))
+            .mount(&server)
+            .await;
+
+        let client = client(None).unwrap();
+        let doi = find_doi("https://doi.org/10.1234/found").unwrap();
+        let work = resolve_doi(&client, &server.uri(), &doi)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            work,
+            DoiWork {
+                title: Some("A Study of Things".to_string()),
+                authors: vec!["Ada Lovelace".to_string(), "The Consortium".to_string()],
+                year: Some(2019),
+            }
+        );
+
+        let missing = resolve_doi(&client, &server.uri(), "10.1234/missing").await;
+        assert_eq!(missing.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_lookup_doi_outside_crossref() {
+        use wiremock::matchers::path;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        // Crossref knows neither; doi.org knows the arXiv (DataCite) one
+        let crossref = MockServer::start().await;
+        Mock::given(wiremock::matchers::any())
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&crossref)
+            .await;
+        let handles = MockServer::start().await;
+        Mock::given(path("/10.48550/arXiv.1706.03762"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "responseCode": 1,
+                "handle": "10.48550/arXiv.1706.03762"
+            })))
+            .mount(&handles)
+            .await;
+        Mock::given(path("/10.1234/missing"))
+            .respond_with(ResponseTemplate::new(404).set_body_json(serde_json::json!({
+                "responseCode": 100,
+                "handle": "10.1234/missing"
+            })))
+            .mount(&handles)
+            .await;
+
+        let client = client(None).unwrap();
+        let lookup = |doi: &'static str| {
+            let (client, crossref, handles) = (client.clone(), crossref.uri(), handles.uri());
+            async move { lookup_doi(&client, &crossref, &handles, doi).await.unwrap() }
+        };
+        assert_eq!(
+            lookup("10.48550/arXiv.1706.03762").await,
+            DoiLookup::Registered
+        );
+        assert_eq!(lookup("10.1234/missing").await, DoiLookup::Unknown);
+    }
+}
@@ -0,0 +1,164 @@
+//! Machine-readable progress events (`--progress jsonl`)
+//!
+//! The long-running commands (fetch, pdf, verify-refs, check-links,
+//! refresh-data) report a `start` event with the item count, an `item` event
+//! as each URL or file finishes and a `done` event with the totals, one JSON
+//! object per line on stderr:
+//!
+//! ```text
+//! {"event":"start","total":50}
+//! {"event":"item","done":12,"total":50,"url":"https://example.com/","ok":true}
+//! {"event":"done","ok":48,"failed":2}
+//! ```
+//!
+//! The free-text `progress!` lines are silenced in this mode. In the default
+//! text mode these functions do nothing.
+
+use clap::ValueEnum;
+use serde::Serialize;
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+static JSONL: AtomicBool = AtomicBool::new(false);
+
+/// The running command's events (only in jsonl mode)
+static TRACKER: Mutex<Option<Tracker<std::io::Stderr>>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum ProgressFormat {
+    /// Human-readable lines on stderr
+    #[default]
+    Text,
+    /// NDJSON events on stderr
+    Jsonl,
+}
+
+pub fn set_format(format: ProgressFormat) {
+    JSONL.store(format == ProgressFormat::Jsonl, Ordering::Relaxed);
+}
+
+pub fn is_jsonl() -> bool {
+    JSONL.load(Ordering::Relaxed)
+}
+
+/// Begin a batch of `total` items
+pub fn start(total: usize) {
+    if is_jsonl() {
+        *lock() = Some(Tracker::start(std::io::stderr(), total));
+    }
+}
+
+/// Report one finished item
+pub fn item(url: &str, ok: bool) {
+    if let Some(tracker) = lock().as_mut() {
+        tracker.item(url, ok);
+    }
+}
+
+/// End the batch with ok/failed totals
+pub fn done() {
+    if let Some(mut tracker) = lock().take() {
+        tracker.finish();
+    }
+}
+
+fn lock() -> std::sync::MutexGuard<'static, Option<Tracker<std::io::Stderr>>> {
+    TRACKER.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "event", rename_all = "lowercase")]
+enum Event<'a> {
+    Start {
+        total: usize,
+    },
+    Item {
+        done: usize,
+        total: usize,
+        url: &'a str,
+        ok: bool,
+    },
+    Done {
+        ok: usize,
+        failed: usize,
+    },
+}
+
+/// Counts finished items and writes their events to `out`
+struct Tracker<W: Write> {
+    out: W,
+    total: usize,
+    done: usize,
+    ok: usize,
+}
+
+impl<W: Write> Tracker<W> {
+    fn start(out: W, total: usize) -> Self {
+        let mut tracker = Self {
+            out,
+            total,
+            done: 0,
+            ok: 0,
+        };
+        tracker.write(&Event::Start { total });
+        tracker
+    }
+
+    fn item(&mut self, url: &str, ok: bool) {
+        self.done += 1;
+        self.ok += usize::from(ok);
+        let event = Event::Item {
+            done: self.done,
+            // Crawls find more pages than they started with
+            total: self.total.max(self.done),
+            url,
+            ok,
+        };
+        self.write(&event);
+    }
+
+    fn finish(&mut self) {
+        let event = Event::Done {
+            ok: self.ok,
+            failed: self.done - self.ok,
+        };
+        self.write(&event);
+    }
+
+    fn write(&mut self, event: &Event) {
+        if let Ok(json) = serde_json::to_string(event) {
+            let _ = writeln!(self.out, "{}", json);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tracker_events() {
+        let mut tracker = Tracker::start(Vec::new(), 3);
+        tracker.item("https://a.com/", true);
+        tracker.item("https://b.com/", false);
+        tracker.item("https://c.com/", true);
+        tracker.finish();
+
+        let events: Vec<serde_json::Value> = String::from_utf8(tracker.out)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(
+            events,
+            [
+                serde_json::json!({"event": "start", "total": 3}),
+                serde_json::json!({"event": "item", "done": 1, "total": 3, "url": "https://a.com/", "ok": true}),
+                serde_json::json!({"event": "item", "done": 2, "total": 3, "url": "https://b.com/", "ok": false}),
+                serde_json::json!({"event": "item", "done": 3, "total": 3, "url": "https://c.com/", "ok": true}),
+                serde_json::json!({"event": "done", "ok": 2, "failed": 1}),
+            ]
+        );
+    }
+}
@@ -0,0 +1,174 @@
+//! export command: Write references.yaml as a CSV or Markdown table
+//!
+//! For spreadsheets and status tables in docs; the only non-JSON output.
+
+use crate::progress;
+use crate::schema::{Reference, ReferencesFile};
+use anyhow::{Context, Result};
+use clap::{Args, ValueEnum};
+use std::path::PathBuf;
+
+#[derive(Args)]
+pub struct ExportArgs {
+    /// Path to references.yaml file
+    #[arg(default_value = "references.yaml")]
+    pub file: PathBuf,
+
+    /// Output format
+    #[arg(long, short, value_enum, default_value = "csv")]
+    pub format: ExportFormat,
+
+    /// Write to a file instead of stdout
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    Csv,
+    #[value(alias = "markdown")]
+    Md,
+}
+
+const COLUMNS: [&str; 5] = ["url", "title", "status", "categories", "verified"];
+
+pub async fn run_export(args: ExportArgs) -> Result<()> {
+    let refs_file = ReferencesFile::load(&args.file).await?;
+
+    let table = match args.format {
+        ExportFormat::Csv => to_csv(&refs_file.references),
+        ExportFormat::Md => to_markdown(&refs_file.references),
+    };
+
+    match &args.output {
+        Some(path) => {
+            tokio::fs::write(path, table)
+                .await
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+            progress!(
+                "Wrote {} references to {}",
+                refs_file.references.len(),
+                path.display()
+            );
+        }
+        None => print!("{}", table),
+    }
+
+    Ok(())
+}
+
+fn row(reference: &Reference) -> [String; 5] {
+    [
+        reference.url.clone(),
+        reference.title.clone(),
+        reference.status.to_string(),
+        reference.categories.join("; "),
+        reference.verified.clone().unwrap_or_default(),
+    ]
+}
+
+/// RFC 4180 CSV with a header row
+pub fn to_csv(references: &[Reference]) -> String {
+    let mut out = COLUMNS.join(",") + "\n";
+    for reference in references {
+        let fields: Vec<String> = row(reference).iter().map(|f| csv_field(f)).collect();
+        out.push_str(&fields.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+/// Quote fields containing commas, quotes or line breaks; double inner quotes
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// GitHub-flavored Markdown table
+pub fn to_markdown(references: &[Reference]) -> String {
+    let mut out = format!("| {} |\n", COLUMNS.join(" | "));
+    out.push_str(&format!("|{}\n", "---|".repeat(COLUMNS.len())));
+    for reference in references {
+        let fields: Vec<String> = row(reference).iter().map(|f| md_cell(f)).collect();
+        out.push_str(&format!("| {} |\n", fields.join(" | ")));
+    }
+    out
+}
+
+/// Escape pipes and flatten line breaks so a cell stays in its column
+fn md_cell(field: &str) -> String {
+    field
+        .replace('\\', "\\\\")
+        .replace('|', "\\|")
+        .replace(['\r', '\n'], " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::Status;
+
+    fn references() -> Vec<Reference> {
+        let yaml = std::fs::read_to_string("test-data/references.yaml").unwrap();
+        let refs_file: ReferencesFile = serde_yaml::from_str(&yaml).unwrap();
+        refs_file.references
+    }
+
+    fn tricky() -> Reference {
+        Reference {
+            title: r#"Rust | "The Book", 2nd ed."#.to_string(),
+            categories: vec!["docs".to_string(), "rust".to_string()],
+            status: Status::Pending,
+            verified: None,
+            ..references()[0].clone()
+        }
+    }
+
+    #[test]
+    fn test_to_csv() {
+        let csv = to_csv(&references());
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "url,title,status,categories,verified");
+        assert_eq!(
+            lines[1],
+            "https://example.com/paper,Example Paper,ok,research,2025-12-16T10:00:00+00:00"
+        );
+        assert_eq!(
+            lines[2],
+            "https://example.org/old,https://example.org/old,redirect,general,"
+        );
+    }
+
+    #[test]
+    fn test_to_csv_escapes() {
+        let csv = to_csv(&[tricky()]);
+        assert_eq!(
+            csv.lines().nth(1).unwrap(),
+            r#"https://example.com/paper,"Rust | ""The Book"", 2nd ed.",pending,docs; rust,"#
+        );
+    }
+
+    #[test]
+    fn test_to_markdown() {
+        let md = to_markdown(&references());
+        let lines: Vec<&str> = md.lines().collect();
+        assert_eq!(lines[0], "| url | title | status | categories | verified |");
+        assert_eq!(lines[1], "|---|---|---|---|---|");
+        assert_eq!(
+            lines[2],
+            "| https://example.com/paper | Example Paper | ok | research | 2025-12-16T10:00:00+00:00 |"
+        );
+    }
+
+    #[test]
+    fn test_to_markdown_escapes() {
+        let md = to_markdown(&[tricky()]);
+        assert_eq!(
+            md.lines().nth(2).unwrap(),
+            r#"| https://example.com/paper | Rust \| "The Book", 2nd ed. | pending | docs; rust |  |"#
+        );
+    }
+}
@@ -1,19 +1,90 @@
-//! URL extraction from markdown and text content
+//! URL, amount, and contact extraction from markdown and text content
 
+use clap::ValueEnum;
 use regex::Regex;
 use std::collections::HashSet;
 
-/// Extract unique URLs from text content
+/// Bare http(s) URL in Markdown or text; balanced brackets (`?filter[]=x`,
+/// `?a[0]=1`) are part of the URL, a lone `]` ends it
+pub const URL_PATTERN: &str = r#"https?://(?:[^\s\)>\[\]"'`]|\[[^\s\[\]"'`<>]*\])+"#;
+
+/// Bare URL in AsciiDoc, where `[` starts the link text (`url[text]`)
+pub const ASCIIDOC_URL_PATTERN: &str = r#"https?://[^\s\)>\[\]"'`]+"#;
+
+/// Extract unique URLs from Markdown or text content
 pub fn extract_urls(content: &str) -> Vec<String> {
-    let re = Regex::new(r#"https?://[^\s\)>\]"'`]+"#).unwrap();
+    unique_matches(&Regex::new(URL_PATTERN).unwrap(), content)
+}
+
+/// Extract unique URLs from AsciiDoc content
+pub fn extract_asciidoc_urls(content: &str) -> Vec<String> {
+    unique_matches(&Regex::new(ASCIIDOC_URL_PATTERN).unwrap(), content)
+}
+
+/// Strip trailing punctuation from a matched URL; `]` only when unbalanced
+pub fn trim_url(url: &str) -> &str {
+    let mut url = url;
+    loop {
+        let trimmed = url.trim_end_matches([',', '.', ')', ';', ':']);
+        let trimmed = match trimmed.strip_suffix(']') {
+            Some(rest) if trimmed.matches('[').count() < trimmed.matches(']').count() => rest,
+            _ => trimmed,
+        };
+        if trimmed == url {
+            return url;
+        }
+        url = trimmed;
+    }
+}
+
+/// Non-HTTP link schemes (`check-links --schemes`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Scheme {
+    Mailto,
+    /// ftp:// and ftps://
+    Ftp,
+    Data,
+}
+
+impl Scheme {
+    /// Scheme of a URL, if it is one of these
+    pub fn of(url: &str) -> Option<Self> {
+        let (scheme, _) = url.split_once(':')?;
+        match scheme.to_ascii_lowercase().as_str() {
+            "mailto" => Some(Self::Mailto),
+            "ftp" | "ftps" => Some(Self::Ftp),
+            "data" => Some(Self::Data),
+            _ => None,
+        }
+    }
+
+    fn pattern(self) -> &'static str {
+        match self {
+            Self::Mailto => r#"mailto:[^\s\)>\[\]"'`]+"#,
+            Self::Ftp => r#"ftps?://[^\s\)>\[\]"'`]+"#,
+            // Needs a media type or `,` so prose like "data: foo" isn't a link
+            Self::Data => r#"data:(?:[A-Za-z]+/[A-Za-z0-9.+-]+(?:;[^\s,;]+)*,|,)[^\s\)>\[\]"'`]*"#,
+        }
+    }
+}
 
+/// Extract unique links with the given non-HTTP schemes
+pub fn extract_scheme_urls(content: &str, schemes: &[Scheme]) -> Vec<String> {
+    if schemes.is_empty() {
+        return vec![];
+    }
+    let patterns: Vec<&str> = schemes.iter().map(|s| s.pattern()).collect();
+    let re = Regex::new(&format!("(?i)(?:{})", patterns.join("|"))).unwrap();
+    unique_matches(&re, content)
+}
+
+/// Matches of `re` in order of first appearance, trailing punctuation trimmed
+fn unique_matches(re: &Regex, content: &str) -> Vec<String> {
     let mut seen = HashSet::new();
     let mut urls = Vec::new();
 
     for mat in re.find_iter(content) {
-        let url = mat.as_str();
-        // Clean trailing punctuation
-        let url = url.trim_end_matches([',', '.', ')', ']', ';', ':']);
+        let url = trim_url(mat.as_str());
 
         if !seen.contains(url) {
             seen.insert(url.to_string());
@@ -24,20 +95,83 @@ pub fn extract_urls(content: &str) -> Vec<String> {
     urls
 }
 
-/// Extract dollar amounts from text
+/// Query parameters added by ad and analytics tooling (plus any `utm_*`)
+pub const TRACKING_PARAMS: [&str; 9] = [
+    "fbclid", "gclid", "msclkid", "yclid", "mc_cid", "mc_eid", "igshid", "ref_src", "_ga",
+];
+
+/// Remove tracking query parameters (`utm_*`, `TRACKING_PARAMS` and `extra`)
+///
+/// Remaining parameters keep their order and encoding. `extra` entries ending
+/// in `*` match by prefix. Unparseable URLs are returned unchanged.
+pub fn strip_tracking_params(url: &str, extra: &[String]) -> String {
+    let Ok(mut parsed) = url::Url::parse(url) else {
+        return url.to_string();
+    };
+    let Some(query) = parsed.query() else {
+        return url.to_string();
+    };
+
+    let kept: Vec<&str> = query
+        .split('&')
+        .filter(|pair| {
+            let key = pair.split('=').next().unwrap_or(pair);
+            !pair.is_empty() && !is_tracking_param(key, extra)
+        })
+        .collect();
+    if kept.len() == query.split('&').count() {
+        return url.to_string();
+    }
+
+    let kept = kept.join("&");
+    parsed.set_query((!kept.is_empty()).then_some(kept.as_str()));
+    parsed.to_string()
+}
+
+/// Whether a query parameter name is a known tracking parameter
+pub fn is_tracking_param(key: &str, extra: &[String]) -> bool {
+    key.starts_with("utm_")
+        || TRACKING_PARAMS.contains(&key)
+        || extra.iter().any(|p| match p.strip_suffix('*') {
+            Some(prefix) => key.starts_with(prefix),
+            None => key == p,
+        })
+}
+
+/// Extract currency amounts from text ($, €, £, ¥ or USD/EUR/GBP/JPY)
 pub fn extract_amounts(text: &str) -> Vec<AmountMatch> {
-    let re = Regex::new(r"\$([0-9,.]+)\s*(billion|million|B|M|K)?").unwrap();
+    // Currency before the number ("$33 billion", "EUR 500M") or after it ("1,200 JPY")
+    let re = Regex::new(
+        r"(?:(?P<pre>[$€£¥]|\b(?:USD|EUR|GBP|JPY)\b)\s*(?P<value>[0-9,.]+)\s*(?P<unit>billion|million|B|M|K)?|\b(?P<value2>[0-9][0-9,.]*)\s*(?P<unit2>billion|million|B|M|K)?\s*(?P<post>[€£¥]|\b(?:USD|EUR|GBP|JPY)\b))",
+    )
+    .unwrap();
 
     re.captures_iter(text)
         .take(10)
-        .map(|cap| AmountMatch {
-            value: cap[1].to_string(),
-            unit: cap.get(2).map(|m| m.as_str().to_string()),
-            raw: cap[0].to_string(),
+        .map(|cap| {
+            let value = cap.name("value").or_else(|| cap.name("value2"));
+            let unit = cap.name("unit").or_else(|| cap.name("unit2"));
+            let currency = cap.name("pre").or_else(|| cap.name("post"));
+            AmountMatch {
+                value: value.map(|m| m.as_str().to_string()).unwrap_or_default(),
+                unit: unit.map(|m| m.as_str().to_string()),
+                currency: currency_code(currency.map(|m| m.as_str()).unwrap_or("$")).to_string(),
+                raw: cap[0].to_string(),
+            }
         })
         .collect()
 }
 
+/// Map a currency symbol or ISO code to its ISO 4217 code
+fn currency_code(symbol: &str) -> &'static str {
+    match symbol {
+        "€" | "EUR" => "EUR",
+        "£" | "GBP" => "GBP",
+        "¥" | "JPY" => "JPY",
+        _ => "USD",
+    }
+}
+
 /// Extract percentages from text
 pub fn extract_percentages(text: &str) -> Vec<String> {
     let re = Regex::new(r"([0-9,.]+)\s*%").unwrap();
@@ -48,10 +182,76 @@ pub fn extract_percentages(text: &str) -> Vec<String> {
         .collect()
 }
 
+/// local@domain.tld, without anchors
+const EMAIL_PATTERN: &str =
+    r"[A-Za-z0-9][A-Za-z0-9._%+-]*@(?:[A-Za-z0-9](?:[A-Za-z0-9-]*[A-Za-z0-9])?\.)+[A-Za-z]{2,24}";
+
+/// Extract unique email addresses (domains lowercased, asset names like logo@2x.png skipped)
+pub fn extract_emails(text: &str) -> Vec<String> {
+    let re = Regex::new(&format!(r"\b{}\b", EMAIL_PATTERN)).unwrap();
+
+    let mut seen = HashSet::new();
+    re.find_iter(text)
+        .filter_map(|m| {
+            let (local, domain) = m.as_str().split_once('@')?;
+            let domain = domain.to_lowercase();
+            let tld = domain.rsplit('.').next()?;
+            if !is_valid_local_part(local) || is_file_extension(tld) {
+                return None;
+            }
+            Some(format!("{}@{}", local, domain))
+        })
+        .filter(|email| seen.insert(email.to_lowercase()))
+        .take(10)
+        .collect()
+}
+
+/// Whether `address` is a single well-formed email address
+pub fn is_valid_email(address: &str) -> bool {
+    let re = Regex::new(&format!("^{}$", EMAIL_PATTERN)).unwrap();
+    re.is_match(address)
+        && address
+            .split_once('@')
+            .is_some_and(|(local, _)| is_valid_local_part(local))
+}
+
+fn is_valid_local_part(local: &str) -> bool {
+    !local.ends_with('.') && !local.contains("..")
+}
+
+/// Image/asset extensions that look like TLDs in retina file names (icon@2x.png)
+fn is_file_extension(tld: &str) -> bool {
+    matches!(
+        tld,
+        "png" | "jpg" | "jpeg" | "gif" | "svg" | "webp" | "avif" | "ico" | "css" | "js"
+    )
+}
+
+/// Extract unique phone numbers: international (+44 20 7946 0958) or
+/// North American ((415) 555-0132, 415-555-0132); deduplicated by digits
+pub fn extract_phones(text: &str) -> Vec<String> {
+    let re = Regex::new(
+        r"(?:\+\d{1,3}[\s.-]?(?:\(\d{1,4}\)[\s.-]?)?\d{1,4}(?:[\s.-]\d{2,4}){1,4}|\(\d{3}\)\s?\d{3}[\s.-]\d{4}|\b\d{3}[.-]\d{3}[.-]\d{4})\b",
+    )
+    .unwrap();
+
+    let mut seen = HashSet::new();
+    re.find_iter(text)
+        .map(|m| m.as_str().trim().to_string())
+        .filter(|phone| {
+            let digits: String = phone.chars().filter(char::is_ascii_digit).collect();
+            (7..=15).contains(&digits.len()) && seen.insert(digits)
+        })
+        .take(10)
+        .collect()
+}
+
 #[derive(Debug, Clone, serde::Serialize)]
 pub struct AmountMatch {
     pub value: String,
     pub unit: Option<String>,
+    /// ISO 4217 currency code (USD, EUR, GBP, JPY)
+    pub currency: String,
     pub raw: String,
 }
 
@@ -74,6 +274,64 @@ mod tests {
         assert!(urls.contains(&"http://old.site.org".to_string()));
     }
 
+    #[test]
+    fn test_extract_scheme_urls() {
+        let content = r#"
+            Mail [us](mailto:team@example.com?subject=Hi), grab ftp://ftp.example.org/pub/file.tgz.
+            Pixel: ![dot](data:image/png;base64,iVBORw0KGgo=) and data:,Hello
+            The data: field is prose. See https://example.com too.
+        "#;
+        assert_eq!(
+            extract_scheme_urls(content, &[Scheme::Mailto, Scheme::Ftp, Scheme::Data]),
+            [
+                "mailto:team@example.com?subject=Hi",
+                "ftp://ftp.example.org/pub/file.tgz",
+                "data:image/png;base64,iVBORw0KGgo=",
+                "data:,Hello",
+            ]
+        );
+        assert_eq!(
+            extract_scheme_urls(content, &[Scheme::Ftp]),
+            ["ftp://ftp.example.org/pub/file.tgz"]
+        );
+        assert!(extract_scheme_urls(content, &[]).is_empty());
+
+        assert_eq!(Scheme::of("MAILTO:a@b.co"), Some(Scheme::Mailto));
+        assert_eq!(Scheme::of("ftps://x"), Some(Scheme::Ftp));
+        assert_eq!(Scheme::of("https://x"), None);
+    }
+
+    #[test]
+    fn test_is_valid_email() {
+        assert!(is_valid_email("team@example.com"));
+        assert!(is_valid_email("first.last+tag@mail.example.co.uk"));
+        assert!(!is_valid_email("team@example"));
+        assert!(!is_valid_email("first..last@example.com"));
+        assert!(!is_valid_email("a@b.com, c@d.com"));
+        assert!(!is_valid_email(""));
+    }
+
+    #[test]
+    fn test_extract_urls_brackets() {
+        let content = "List https://api.example.com/items?filter[]=x&a[0]=1 and \
+                       [https://example.com/docs](https://example.com/docs) or \
+                       (see https://example.com/a[1].)";
+        assert_eq!(
+            extract_urls(content),
+            [
+                "https://api.example.com/items?filter[]=x&a[0]=1",
+                "https://example.com/docs",
+                "https://example.com/a[1]"
+            ]
+        );
+
+        // AsciiDoc link text is not part of the URL
+        assert_eq!(
+            extract_asciidoc_urls("See https://example.com/guide[the guide]."),
+            ["https://example.com/guide"]
+        );
+    }
+
     #[test]
     fn test_extract_urls_dedup() {
         let content = "https://dup.com https://dup.com https://dup.com";
@@ -81,6 +339,37 @@ mod tests {
         assert_eq!(urls.len(), 1);
     }
 
+    #[test]
+    fn test_strip_tracking_params() {
+        assert_eq!(
+            strip_tracking_params(
+                "https://x.com/a?utm_source=news&id=7&utm_medium=email&sort=desc#top",
+                &[]
+            ),
+            "https://x.com/a?id=7&sort=desc#top"
+        );
+        assert_eq!(
+            strip_tracking_params("https://x.com/a?utm_source=news&fbclid=abc", &[]),
+            "https://x.com/a"
+        );
+        // Functional params untouched, encoding preserved
+        let url = "https://x.com/search?q=a%20b&page=2";
+        assert_eq!(strip_tracking_params(url, &[]), url);
+        assert_eq!(
+            strip_tracking_params("not a url?utm_x=1", &[]),
+            "not a url?utm_x=1"
+        );
+    }
+
+    #[test]
+    fn test_strip_tracking_params_extra() {
+        let extra = vec!["ref".to_string(), "hs_*".to_string()];
+        assert_eq!(
+            strip_tracking_params("https://x.com/?ref=hn&hs_cta=1&hsx=2", &extra),
+            "https://x.com/?hsx=2"
+        );
+    }
+
     #[test]
     fn test_extract_amounts() {
         let text = "The market is worth $33 billion and growing to $48.2M";
@@ -90,6 +379,63 @@ mod tests {
         assert_eq!(amounts[0].unit, Some("billion".to_string()));
         assert_eq!(amounts[1].value, "48.2");
         assert_eq!(amounts[1].unit, Some("M".to_string()));
+        assert_eq!(amounts[0].currency, "USD");
+    }
+
+    #[test]
+    fn test_extract_amounts_currencies() {
+        let text = "Europe at €33 billion, UK at £500M and Japan at ¥1,200";
+        let amounts = extract_amounts(text);
+        assert_eq!(amounts.len(), 3);
+        assert_eq!(amounts[0].value, "33");
+        assert_eq!(amounts[0].unit, Some("billion".to_string()));
+        assert_eq!(amounts[0].currency, "EUR");
+        assert_eq!(amounts[1].value, "500");
+        assert_eq!(amounts[1].unit, Some("M".to_string()));
+        assert_eq!(amounts[1].currency, "GBP");
+        assert_eq!(amounts[2].value, "1,200");
+        assert_eq!(amounts[2].unit, None);
+        assert_eq!(amounts[2].currency, "JPY");
+    }
+
+    #[test]
+    fn test_extract_amounts_iso_codes() {
+        let text = "Revenue of EUR 12.5 million versus 40 billion JPY";
+        let amounts = extract_amounts(text);
+        assert_eq!(amounts.len(), 2);
+        assert_eq!(amounts[0].value, "12.5");
+        assert_eq!(amounts[0].currency, "EUR");
+        assert_eq!(amounts[1].value, "40");
+        assert_eq!(amounts[1].unit, Some("billion".to_string()));
+        assert_eq!(amounts[1].currency, "JPY");
+    }
+
+    #[test]
+    fn test_extract_emails() {
+        let text = r#"Contact <a href="mailto:Sales@Example.COM">sales</a>,
+            press.office+eu@news.example.co.uk or sales@example.com.
+            Not emails: user@localhost, @example.com, a@b.c, icon@2x.png, a..b@example.com"#;
+        let emails = extract_emails(text);
+        assert_eq!(
+            emails,
+            vec!["Sales@example.com", "press.office+eu@news.example.co.uk"]
+        );
+    }
+
+    #[test]
+    fn test_extract_phones() {
+        let text = "Call +1 (415) 555-0132 or (415) 555-0132, London +44 20 7946 0958, \
+            support 212-555-0199. Order #20240115 shipped 2024-01-15, ISBN 978-3-16-148410-0.";
+        let phones = extract_phones(text);
+        assert_eq!(
+            phones,
+            vec![
+                "+1 (415) 555-0132",
+                "(415) 555-0132",
+                "+44 20 7946 0958",
+                "212-555-0199"
+            ]
+        );
     }
 
     #[test]
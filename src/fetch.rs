@@ -3,22 +3,71 @@
 //! LLM-optimized output - minimal tokens, maximum signal.
 //! JSON compact output only. No YAML, no pretty printing.
 
-use crate::browser::BrowserPool;
-use anyhow::Result;
+use crate::browser::{
+    http_client, parse_connect, parse_lang, retry_navigation, BrowserPool, WaitUntil,
+};
+use crate::cookies::{cookie_header, load_cookies, Cookie};
+use crate::events;
+use crate::extract::strip_tracking_params;
+use crate::normalize::is_cross_domain;
+use crate::pdf::{detect_language, extract_pdf_bytes};
+use crate::progress;
+use crate::robots::RobotsCache;
+use crate::util::{bare_host, floor_char_boundary, truncate, truncate_words};
+use anyhow::{Context, Result};
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
 use clap::Args;
-use futures::future::join_all;
-use scraper::{Html, Selector};
-use serde::Serialize;
-use std::collections::HashSet;
+use futures::stream::{FuturesUnordered, StreamExt};
+use futures::FutureExt;
+use regex::Regex;
+use scraper::{ElementRef, Html, Selector};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::io::Write;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tokio::time::Instant;
+use tracing::debug;
 use url::Url;
 
+/// Placeholder page URL for --stdin-html without --url
+const STDIN_URL: &str = "stdin";
+
+/// Largest PDF downloaded outside Chrome
+const MAX_PDF_BYTES: u64 = 100 * 1024 * 1024;
+
+/// Alert for URLs not fetched before --deadline
+const DEADLINE_EXCEEDED: &str = "deadline exceeded";
+
+/// Average adult silent reading speed, for `reading_minutes`
+const WORDS_PER_MINUTE: usize = 230;
+
+/// Rough English token size, for `--chunks`
+const CHARS_PER_TOKEN: usize = 4;
+
 #[derive(Args)]
 pub struct FetchArgs {
     /// URLs to fetch (multiple allowed)
-    #[arg(required = true)]
+    #[arg(required_unless_present_any = ["stdin_html", "jobs"])]
     pub urls: Vec<String>,
 
+    /// JSON array of jobs, `[{"url": ..., "max_chars": ..., "wait_for": ...}]`;
+    /// per-job options override the command line
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["stdin_html", "depth"])]
+    pub jobs: Option<PathBuf>,
+
+    /// Parse HTML from stdin instead of fetching (no browser)
+    #[arg(long, conflicts_with_all = ["urls", "depth"])]
+    pub stdin_html: bool,
+
+    /// Page URL for --stdin-html, used to resolve relative links
+    #[arg(long, requires = "stdin_html")]
+    pub url: Option<String>,
+
     /// Parallel fetches (browser tabs)
     #[arg(long, short, default_value = "4")]
     pub parallel: usize,
@@ -27,13 +76,307 @@ pub struct FetchArgs {
     #[arg(long, default_value = "30000")]
     pub timeout: u64,
 
+    /// Remove utm_*/fbclid/gclid-style parameters from URLs before fetching
+    #[arg(long)]
+    pub strip_tracking: bool,
+
+    /// Extra tracking parameter to strip (repeatable, `name*` = prefix)
+    #[arg(long, value_name = "NAME", requires = "strip_tracking")]
+    pub tracking_param: Vec<String>,
+
+    /// Overall time budget in seconds; unfinished URLs report "deadline exceeded"
+    #[arg(long, value_name = "SECS")]
+    pub deadline: Option<u64>,
+
+    /// Retries on network failure, with exponential backoff (never on 404)
+    #[arg(long, default_value = "1")]
+    pub retries: u8,
+
     /// Skip content cleaning (return raw extracted text)
     #[arg(long)]
     pub raw: bool,
 
-    /// Cookie file for authenticated fetches (Netscape format)
+    /// Cookie file for authenticated fetches (Netscape or JSON array)
+    #[arg(long)]
+    pub cookies: Option<PathBuf>,
+
+    /// Attach to a running Chrome (ws:// or http://host:9222) instead of launching one
+    #[arg(
+        long,
+        visible_alias = "remote-debugging-url",
+        env = "REF_BROWSER_URL",
+        value_parser = parse_connect
+    )]
+    pub connect: Option<String>,
+
+    /// Route traffic and DNS through a SOCKS5 proxy (host:port), no local lookups
+    #[arg(long, value_name = "HOST:PORT", conflicts_with = "connect")]
+    pub socks5: Option<String>,
+
+    /// User agent string, or "chrome-latest" for the browser's own version
+    #[arg(long, value_name = "UA")]
+    pub user_agent: Option<String>,
+
+    /// Ask for pages in this language: Accept-Language and navigator.language (e.g. de-DE)
+    #[arg(long, value_name = "TAG", value_parser = parse_lang)]
+    pub lang: Option<String>,
+
+    /// Load images, media, fonts and stylesheets (blocked by default for speed)
+    #[arg(long)]
+    pub load_resources: bool,
+
+    /// Page lifecycle event that ends navigation
+    #[arg(long, value_enum, default_value = "load")]
+    pub wait_until: WaitUntil,
+
+    /// Skip URLs disallowed by the host's robots.txt
+    #[arg(long)]
+    pub respect_robots: bool,
+
+    /// Extract <table> elements as rows of cells
+    #[arg(long)]
+    pub tables: bool,
+
+    /// Full link graph: every <a href> on the page incl. navigation (max 200)
+    #[arg(long)]
+    pub links: bool,
+
+    /// Include Open Graph / Twitter Card metadata (image, type, site name, ...) and the favicon URL
+    #[arg(long)]
+    pub meta: bool,
+
+    /// Include schema.org JSON-LD blocks (Article, Product, Organization, ...)
+    #[arg(long)]
+    pub jsonld: bool,
+
+    /// Re-fetch the page's rel=canonical URL when it differs from the requested one
+    #[arg(long)]
+    pub follow_canonical: bool,
+
+    /// Crawl depth: follow same-domain links this many levels from the seed URLs
+    #[arg(long, default_value = "0")]
+    pub depth: usize,
+
+    /// Maximum pages to fetch when crawling (--depth > 0)
+    #[arg(long, default_value = "100")]
+    pub max_pages: usize,
+
+    /// At most this many tabs on one host at a time (different hosts share --parallel)
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u64).range(1..))]
+    pub per_host: Option<u64>,
+
+    /// Print each page as a JSON line as soon as it completes (completion order, not input order)
+    #[arg(long)]
+    pub jsonl: bool,
+
+    /// Write JSON to this file instead of stdout (one line per page)
+    #[arg(short, long, value_name = "FILE")]
+    pub output: Option<PathBuf>,
+
+    /// Write one JSON file per page into this directory, named after the URL
+    #[arg(long, value_name = "DIR", conflicts_with = "output")]
+    pub output_dir: Option<PathBuf>,
+
+    /// Add per-page `elapsed_ms` and a final `metrics` line (min/median/p95/max, wall time)
     #[arg(long)]
-    pub cookies: Option<String>,
+    pub metrics: bool,
+
+    /// Add `chunks`: section text split into pieces of about this many tokens, for embedding
+    #[arg(long, value_name = "TOKENS", value_parser = clap::value_parser!(u64).range(16..))]
+    pub chunks: Option<u64>,
+
+    /// How section text is extracted (semantic: DOM walk keeping lists as `- ` items)
+    #[arg(long, value_enum, default_value = "html2text")]
+    pub text_mode: TextMode,
+
+    /// Cut each page's text (sections or body) to about this many characters
+    #[arg(long, value_name = "N")]
+    pub max_chars: Option<usize>,
+
+    /// Wait (up to --timeout) for an element matching this CSS selector before reading the page
+    #[arg(long, value_name = "SELECTOR")]
+    pub wait_for: Option<String>,
+}
+
+/// Per-page fetch and parse options, shared by all fetch tasks
+#[derive(Clone)]
+pub struct FetchOptions {
+    /// Navigation timeout in milliseconds
+    pub timeout: u64,
+    /// Retries on network failure
+    pub retries: u8,
+    /// Skip content cleaning
+    pub raw: bool,
+    /// Extract tables
+    pub tables: bool,
+    /// Extract all links instead of content links only
+    pub links: bool,
+    /// Extract social card metadata
+    pub meta: bool,
+    /// Extract JSON-LD structured data
+    pub jsonld: bool,
+    /// Fetch the canonical URL instead when it differs
+    pub follow_canonical: bool,
+    /// robots.txt cache (None = don't check)
+    pub robots: Option<Arc<RobotsCache>>,
+    /// Per-host tab limit (None = only the pool's limit)
+    pub host_limiter: Option<Arc<HostLimiter>>,
+    /// Record how long each page took
+    pub metrics: bool,
+    /// Target chunk size in tokens (None = no chunks)
+    pub chunks: Option<usize>,
+    /// Section text extraction
+    pub text_mode: TextMode,
+    /// Cut page text to about this many characters
+    pub max_chars: Option<usize>,
+    /// CSS selector to wait for after navigation
+    pub wait_for: Option<String>,
+    /// Options for URLs from --jobs, by URL
+    pub per_url: Option<Arc<HashMap<String, FetchOptions>>>,
+    /// Downloads PDFs outside Chrome
+    pub pdf: PdfClient,
+}
+
+impl Default for FetchOptions {
+    fn default() -> Self {
+        Self {
+            timeout: 30000,
+            retries: 1,
+            raw: false,
+            tables: false,
+            links: false,
+            meta: false,
+            jsonld: false,
+            follow_canonical: false,
+            robots: None,
+            host_limiter: None,
+            metrics: false,
+            chunks: None,
+            text_mode: TextMode::default(),
+            max_chars: None,
+            wait_for: None,
+            per_url: None,
+            pdf: PdfClient::default(),
+        }
+    }
+}
+
+impl FetchOptions {
+    /// Options from the command line (robots.txt checking off)
+    fn from_args(args: &FetchArgs) -> Self {
+        Self {
+            timeout: args.timeout,
+            retries: args.retries,
+            raw: args.raw,
+            tables: args.tables,
+            links: args.links,
+            meta: args.meta,
+            jsonld: args.jsonld,
+            follow_canonical: args.follow_canonical,
+            robots: None,
+            host_limiter: args
+                .per_host
+                .map(|n| Arc::new(HostLimiter::new(n as usize))),
+            metrics: args.metrics,
+            chunks: args.chunks.map(|n| n as usize),
+            text_mode: args.text_mode,
+            max_chars: args.max_chars,
+            wait_for: args.wait_for.clone(),
+            per_url: None,
+            pdf: PdfClient::default(),
+        }
+    }
+}
+
+/// One entry of a --jobs file; unset options fall back to the command line
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FetchJob {
+    pub url: String,
+    pub max_chars: Option<usize>,
+    pub wait_for: Option<String>,
+    /// Navigation timeout in milliseconds
+    pub timeout: Option<u64>,
+    pub tables: Option<bool>,
+    pub links: Option<bool>,
+    pub meta: Option<bool>,
+    pub jsonld: Option<bool>,
+}
+
+impl FetchJob {
+    /// `defaults` with this job's options applied
+    fn options(&self, defaults: &FetchOptions) -> FetchOptions {
+        FetchOptions {
+            timeout: self.timeout.unwrap_or(defaults.timeout),
+            tables: self.tables.unwrap_or(defaults.tables),
+            links: self.links.unwrap_or(defaults.links),
+            meta: self.meta.unwrap_or(defaults.meta),
+            jsonld: self.jsonld.unwrap_or(defaults.jsonld),
+            max_chars: self.max_chars.or(defaults.max_chars),
+            wait_for: self.wait_for.clone().or_else(|| defaults.wait_for.clone()),
+            per_url: None,
+            ..defaults.clone()
+        }
+    }
+}
+
+/// Read a --jobs file (a JSON array of jobs)
+pub fn load_jobs(path: &Path) -> Result<Vec<FetchJob>> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    serde_json::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+/// Per-URL options for `jobs` over `defaults` (a repeated URL keeps its last job)
+fn job_options(jobs: &[FetchJob], defaults: &FetchOptions) -> HashMap<String, FetchOptions> {
+    jobs.iter()
+        .map(|job| (job.url.clone(), job.options(defaults)))
+        .collect()
+}
+
+/// Caps concurrent fetches per host; each host gets its own semaphore
+pub struct HostLimiter {
+    limit: usize,
+    hosts: std::sync::Mutex<HashMap<String, Arc<Semaphore>>>,
+}
+
+impl HostLimiter {
+    pub fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            hosts: std::sync::Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Wait for a slot on `url`'s host, held until the permit is dropped
+    pub async fn acquire(&self, url: &str) -> OwnedSemaphorePermit {
+        let host = Url::parse(url)
+            .ok()
+            .and_then(|u| u.host_str().map(str::to_lowercase))
+            .unwrap_or_default();
+        let semaphore = {
+            let mut hosts = self.hosts.lock().unwrap_or_else(|e| e.into_inner());
+            Arc::clone(
+                hosts
+                    .entry(host)
+                    .or_insert_with(|| Arc::new(Semaphore::new(self.limit))),
+            )
+        };
+        semaphore
+            .acquire_owned()
+            .await
+            .expect("host semaphore is never closed")
+    }
+}
+
+/// How page text becomes section content
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum TextMode {
+    /// Headings and paragraphs; html2text for pages without them
+    #[default]
+    Html2text,
+    /// Headings, paragraphs and list items from a DOM walk, skipping script/style/nav/footer
+    Semantic,
 }
 
 /// Page status
@@ -45,6 +388,10 @@ pub enum PageStatus {
     Login,
     Dead,
     Redirect,
+    /// Disallowed by robots.txt (not fetched)
+    Blocked,
+    /// Cut off by --deadline before it finished (no verdict on the page)
+    Skipped,
 }
 
 /// A content section with heading hierarchy
@@ -58,6 +405,16 @@ pub struct Section {
     pub content: String,
 }
 
+/// A piece of one section's text, for embedding (only with --chunks)
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct Chunk {
+    /// Hash of URL, heading and text: unchanged content keeps its id across fetches
+    pub id: String,
+    /// Heading of the section the text comes from
+    pub heading: String,
+    pub text: String,
+}
+
 /// A content link (not navigation)
 #[derive(Debug, Serialize, Clone)]
 pub struct Link {
@@ -73,6 +430,26 @@ pub struct CodeBlock {
     pub source: String,
 }
 
+/// Open Graph / Twitter Card metadata (only with --meta)
+#[derive(Debug, Default, Serialize, Clone, PartialEq)]
+pub struct PageMeta {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image: Option<String>,
+    /// og:type ("article", "website", ...)
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub og_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub site_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub published_time: Option<String>,
+    /// twitter:card ("summary", "summary_large_image", ...)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub twitter_card: Option<String>,
+    /// Absolute site icon URL (not fetched)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub favicon: Option<String>,
+}
+
 /// LLM-optimized page output
 #[derive(Debug, Serialize, Clone)]
 pub struct Page {
@@ -88,225 +465,1053 @@ pub struct Page {
     pub date: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub doi: Option<String>,
+    /// Absolute URL from <link rel="canonical">
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub canonical: Option<String>,
+    /// Primary language (ISO 639-1 where known), from <html lang> or detected from text
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lang: Option<String>,
+    /// Document subject (PDF Info dictionary)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subject: Option<String>,
+    /// Number of pages extracted (PDF only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pages: Option<usize>,
+    /// Social card metadata (only with --meta)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub meta: Option<PageMeta>,
+    /// JSON-LD items, `@graph` arrays flattened (only with --jsonld)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub jsonld: Vec<serde_json::Value>,
+    /// MIME type of a non-HTML document returned as `text`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_type: Option<String>,
+    /// Raw body of JSON, plain text and XML documents (not parsed as HTML)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub sections: Vec<Section>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub links: Vec<Link>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub code: Vec<CodeBlock>,
+    /// Tables as rows of cells (HTML with --tables; aligned PDF columns always)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tables: Vec<Vec<Vec<String>>>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub chunks: Vec<Chunk>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub alerts: Vec<String>,
     pub chars: usize,
+    pub words: usize,
+    /// Estimated at 230 words per minute, rounded up
+    pub reading_minutes: u32,
+    /// Navigation and parse time (only with --metrics)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub elapsed_ms: Option<u64>,
+}
+
+/// Fetch timing summary (only with --metrics)
+#[derive(Debug, Serialize, PartialEq)]
+pub struct FetchMetrics {
+    /// Pages timed (excludes pages cut off by --deadline)
+    pub pages: usize,
+    pub min_ms: u64,
+    pub median_ms: u64,
+    pub p95_ms: u64,
+    pub max_ms: u64,
+    /// Wall time for the whole run, including browser startup
+    pub wall_ms: u64,
+}
+
+impl FetchMetrics {
+    /// Summarize per-page durations (nearest-rank percentiles)
+    fn from_durations(mut durations: Vec<u64>, wall: Duration) -> Self {
+        durations.sort_unstable();
+        let percentile = |p: usize| match durations.len() {
+            0 => 0,
+            n => durations[(n * p).div_ceil(100).max(1) - 1],
+        };
+        Self {
+            pages: durations.len(),
+            min_ms: durations.first().copied().unwrap_or(0),
+            median_ms: percentile(50),
+            p95_ms: percentile(95),
+            max_ms: durations.last().copied().unwrap_or(0),
+            wall_ms: wall.as_millis() as u64,
+        }
+    }
 }
 
-pub async fn run_fetch(args: FetchArgs) -> Result<()> {
+pub async fn run_fetch(mut args: FetchArgs) -> Result<()> {
+    if args.stdin_html {
+        return parse_stdin_html(&args);
+    }
+    let mut jobs = match &args.jobs {
+        Some(path) => load_jobs(path)?,
+        None => vec![],
+    };
+    if args.strip_tracking {
+        let job_urls = jobs.iter_mut().map(|job| &mut job.url);
+        for url in args.urls.iter_mut().chain(job_urls) {
+            *url = strip_tracking_params(url, &args.tracking_param);
+        }
+    }
+    args.urls.extend(jobs.iter().map(|job| job.url.clone()));
+    let started = Instant::now();
+    let deadline = args
+        .deadline
+        .map(|secs| started + Duration::from_secs(secs));
+
     let url_count = args.urls.len();
-    let parallel = args.parallel.min(url_count).max(1);
+    // Crawling discovers more URLs than the seeds, so don't cap tabs by seed count
+    let parallel = if args.depth > 0 {
+        args.parallel.max(1)
+    } else {
+        args.parallel.min(url_count).max(1)
+    };
 
-    eprintln!(
-        "Fetching {} URL{} ({} parallel)...",
-        url_count,
-        if url_count == 1 { "" } else { "s" },
-        parallel
-    );
+    if args.depth > 0 {
+        progress!(
+            "Crawling {} seed URL{} (depth {}, max {} pages, {} parallel)...",
+            url_count,
+            if url_count == 1 { "" } else { "s" },
+            args.depth,
+            args.max_pages,
+            parallel
+        );
+    } else {
+        progress!(
+            "Fetching {} URL{} ({} parallel)...",
+            url_count,
+            if url_count == 1 { "" } else { "s" },
+            parallel
+        );
+    }
 
-    let pool = Arc::new(BrowserPool::new(parallel).await?);
-    let timeout = args.timeout;
-    let raw = args.raw;
+    let cookies = match &args.cookies {
+        Some(path) => load_cookies(path).await?,
+        None => vec![],
+    };
 
-    // Spawn parallel fetch tasks
-    let tasks: Vec<_> = args
-        .urls
-        .into_iter()
-        .map(|url| {
-            let pool = Arc::clone(&pool);
-            tokio::spawn(async move { fetch_one(&pool, &url, timeout, raw).await })
-        })
-        .collect();
+    let pool = Arc::new(
+        BrowserPool::launch_or_connect(parallel, args.connect.as_deref(), args.socks5.as_deref())
+            .await?
+            .with_cookies(&cookies)
+            .with_user_agent(args.user_agent.as_deref())
+            .await?
+            .with_resources(args.load_resources)
+            .with_lang(args.lang.as_deref())
+            .with_wait_until(args.wait_until),
+    );
+    let options = FetchOptions {
+        robots: args
+            .respect_robots
+            .then(|| RobotsCache::new(pool.user_agent(), args.socks5.as_deref()))
+            .transpose()?
+            .map(Arc::new),
+        pdf: PdfClient::new(
+            pool.user_agent(),
+            cookies,
+            args.socks5.as_deref(),
+            args.connect.is_none(),
+        )?,
+        ..FetchOptions::from_args(&args)
+    };
+    let options = FetchOptions {
+        per_url: (!jobs.is_empty()).then(|| Arc::new(job_options(&jobs, &options))),
+        ..options
+    };
 
-    // Await all tasks
-    let results: Vec<Page> = join_all(tasks)
-        .await
-        .into_iter()
-        .filter_map(|r| r.ok())
-        .collect();
+    let mut sink = PageSink::open(&args)?;
+    events::start(url_count);
+
+    // --jsonl writes pages as they complete; otherwise buffer and write in input order
+    let jsonl = args.jsonl;
+    let mut results = Vec::new();
+    let mut page_count = 0;
+    let mut ok_count = 0;
+    let mut cut_off = 0;
+    let mut write_error = None;
+    let mut durations = Vec::new();
+    let mut emit = |page: Page| {
+        page_count += 1;
+        durations.extend(page.elapsed_ms);
+        if page.status == PageStatus::Ok {
+            ok_count += 1;
+        } else if page.status == PageStatus::Skipped {
+            cut_off += 1;
+        }
+        if jsonl {
+            if let Err(e) = sink.write(&page) {
+                write_error.get_or_insert(e);
+            }
+        } else {
+            results.push(page);
+        }
+    };
+
+    if args.depth > 0 {
+        crawl(
+            &pool,
+            args.urls,
+            &options,
+            args.depth,
+            args.max_pages,
+            deadline,
+            &mut emit,
+        )
+        .await;
+    } else if jsonl {
+        fetch_each(&pool, args.urls, &options, deadline, &mut emit).await;
+    } else {
+        fetch_batch(&pool, args.urls, &options, deadline)
+            .await
+            .into_iter()
+            .for_each(&mut emit);
+    }
 
     // Close browser
     if let Ok(pool) = Arc::try_unwrap(pool) {
         pool.close().await?;
     }
 
-    let ok_count = results
-        .iter()
-        .filter(|p| p.status == PageStatus::Ok)
-        .count();
+    events::done();
 
-    // Output compact JSON (one line per page for multiple, or single object)
-    if results.len() == 1 {
-        println!("{}", serde_json::to_string(&results[0])?);
-    } else {
-        for page in &results {
-            println!("{}", serde_json::to_string(page)?);
-        }
+    if let Some(e) = write_error {
+        return Err(e);
+    }
+
+    // Compact JSON, one line per page
+    for page in &results {
+        sink.write(page)?;
     }
 
-    eprintln!("Done: {}/{} OK", ok_count, url_count);
+    // Metrics go wherever the pages went, after them
+    if args.metrics {
+        let metrics = FetchMetrics::from_durations(durations, started.elapsed());
+        progress!(
+            "Timing: min {}ms, median {}ms, p95 {}ms, max {}ms, wall {}ms",
+            metrics.min_ms,
+            metrics.median_ms,
+            metrics.p95_ms,
+            metrics.max_ms,
+            metrics.wall_ms
+        );
+        sink.write_metrics(&metrics)?;
+    }
+    sink.finish()?;
+
+    if cut_off > 0 {
+        progress!(
+            "Done: {}/{} OK, {} cut off by --deadline",
+            ok_count,
+            page_count,
+            cut_off
+        );
+    } else {
+        progress!("Done: {}/{} OK", ok_count, page_count);
+    }
     Ok(())
 }
 
-async fn fetch_one(pool: &BrowserPool, url: &str, timeout: u64, raw: bool) -> Page {
-    eprintln!("  -> {}", truncate(url, 60));
-
-    let page = match pool.new_page().await {
-        Ok(p) => p,
-        Err(e) => return error_page(url, &e.to_string()),
-    };
+/// Destination for page JSON: stdout, a single file, or one file per page
+enum PageSink {
+    Stdout,
+    File(PathBuf, std::io::BufWriter<std::fs::File>),
+    Dir(PathBuf, HashSet<String>),
+}
 
-    // Parse original URL for redirect detection
-    let original_host = Url::parse(url)
-        .ok()
-        .and_then(|u| u.host_str().map(String::from));
+impl PageSink {
+    fn open(args: &FetchArgs) -> Result<Self> {
+        if let Some(path) = &args.output {
+            let file = std::fs::File::create(path)
+                .with_context(|| format!("Failed to create {}", path.display()))?;
+            return Ok(Self::File(path.clone(), std::io::BufWriter::new(file)));
+        }
+        if let Some(dir) = &args.output_dir {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+            return Ok(Self::Dir(dir.clone(), HashSet::new()));
+        }
+        Ok(Self::Stdout)
+    }
 
-    let nav = match page.goto(url, timeout).await {
-        Ok(n) => n,
-        Err(e) => return error_page(url, &e.to_string()),
-    };
+    fn write(&mut self, page: &Page) -> Result<()> {
+        let json = serde_json::to_string(page)?;
+        self.write_json(json, &page.url)
+    }
 
-    if nav.error.is_some() {
-        return Page {
-            url: url.to_string(),
-            status: PageStatus::Dead,
-            title: nav.title,
-            site: None,
-            author: None,
-            date: None,
-            doi: None,
-            sections: vec![],
-            links: vec![],
-            code: vec![],
-            alerts: vec![nav.error.unwrap_or_default()],
-            chars: 0,
-        };
+    /// The `{"metrics": ...}` line, or `metrics.json` with --output-dir
+    fn write_metrics(&mut self, metrics: &FetchMetrics) -> Result<()> {
+        let json = serde_json::json!({ "metrics": metrics }).to_string();
+        self.write_json(json, "metrics")
     }
 
-    // Check for redirect
-    let final_url = page.current_url().await;
-    if let (Some(orig), Some(final_u)) = (&original_host, &final_url) {
-        if let Ok(parsed) = Url::parse(final_u) {
-            if let Some(final_host) = parsed.host_str() {
-                let orig_norm = orig.trim_start_matches("www.");
-                let final_norm = final_host.trim_start_matches("www.");
-                if orig_norm != final_norm {
-                    return Page {
-                        url: url.to_string(),
-                        status: PageStatus::Redirect,
-                        title: nav.title,
-                        site: None,
-                        author: None,
-                        date: None,
-                        doi: None,
-                        sections: vec![],
-                        links: vec![],
-                        code: vec![],
-                        alerts: vec![format!("Redirected to: {}", final_u)],
-                        chars: 0,
-                    };
-                }
+    /// One JSON document; `name` picks the file name in a directory
+    fn write_json(&mut self, json: String, name: &str) -> Result<()> {
+        match self {
+            Self::Stdout => println!("{}", json),
+            Self::File(path, writer) => writeln!(writer, "{}", json)
+                .with_context(|| format!("Failed to write {}", path.display()))?,
+            Self::Dir(dir, used) => {
+                let path = dir.join(unique_file_name(name, used));
+                std::fs::write(&path, json)
+                    .with_context(|| format!("Failed to write {}", path.display()))?;
             }
         }
+        Ok(())
     }
 
-    let html = match page.content().await {
-        Ok(h) => h,
-        Err(e) => return error_page(url, &e.to_string()),
-    };
+    /// Flush and report where output went
+    fn finish(self) -> Result<()> {
+        match self {
+            Self::Stdout => {}
+            Self::File(path, mut writer) => {
+                writer.flush()?;
+                progress!("Wrote {}", path.display());
+            }
+            Self::Dir(dir, used) => {
+                progress!("Wrote {} files to {}", used.len(), dir.display());
+            }
+        }
+        Ok(())
+    }
+}
 
-    parse_page(&html, url, raw)
+/// File name for a page URL ("https://a.com/x?y=1" -> "a.com_x_y_1.json"), unique within `used`
+fn unique_file_name(url: &str, used: &mut HashSet<String>) -> String {
+    let base = sanitize_file_name(url);
+    let mut name = format!("{}.json", base);
+    let mut n = 2;
+    while !used.insert(name.clone()) {
+        name = format!("{}-{}.json", base, n);
+        n += 1;
+    }
+    name
 }
 
-fn error_page(url: &str, error: &str) -> Page {
-    Page {
-        url: url.to_string(),
-        status: PageStatus::Dead,
-        title: None,
-        site: None,
-        author: None,
-        date: None,
-        doi: None,
-        sections: vec![],
-        links: vec![],
-        code: vec![],
-        alerts: vec![error.to_string()],
-        chars: 0,
+fn sanitize_file_name(url: &str) -> String {
+    let stripped = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let mut name = String::new();
+    for c in stripped.chars() {
+        if c.is_ascii_alphanumeric() || c == '.' || c == '-' {
+            name.push(c);
+        } else if !name.ends_with('_') {
+            name.push('_');
+        }
+    }
+    let name = name.trim_matches(['_', '.']);
+    let end = floor_char_boundary(name, 150);
+    match &name[..end] {
+        "" => "page".to_string(),
+        s => s.to_string(),
     }
 }
 
-fn parse_page(html: &str, url: &str, raw: bool) -> Page {
-    let doc = Html::parse_document(html);
-    let mut alerts = Vec::new();
+/// Fetch URLs in parallel (bounded by the pool's tab semaphore), in input order
+async fn fetch_batch(
+    pool: &Arc<BrowserPool>,
+    urls: Vec<String>,
+    options: &FetchOptions,
+    deadline: Option<Instant>,
+) -> Vec<Page> {
+    let mut pages: Vec<Option<Page>> = (0..urls.len()).map(|_| None).collect();
+    let task = fetch_task(pool, options);
+    spawn_until(urls, deadline, task, |idx, page| pages[idx] = Some(page)).await;
+    pages.into_iter().flatten().collect()
+}
 
-    // Extract metadata
-    let title = extract_title(&doc);
-    let site = extract_meta(&doc, "og:site_name");
-    let author = extract_meta(&doc, "author").or_else(|| extract_meta(&doc, "article:author"));
-    let date = extract_meta(&doc, "article:published_time")
-        .or_else(|| extract_meta(&doc, "date"))
-        .or_else(|| extract_meta(&doc, "pubdate"));
-    let doi = extract_doi(&doc);
+/// Fetch URLs in parallel, passing each page to `on_page` in completion order
+async fn fetch_each(
+    pool: &Arc<BrowserPool>,
+    urls: Vec<String>,
+    options: &FetchOptions,
+    deadline: Option<Instant>,
+    mut on_page: impl FnMut(Page),
+) {
+    let task = fetch_task(pool, options);
+    spawn_until(urls, deadline, task, |_, page| on_page(page)).await;
+}
 
-    // Check for paywall/login
-    let status = detect_status(html);
-    if status == PageStatus::Paywall {
-        alerts.push("Paywall detected".to_string());
-    } else if status == PageStatus::Login {
-        alerts.push("Login required".to_string());
+fn fetch_task(
+    pool: &Arc<BrowserPool>,
+    options: &FetchOptions,
+) -> impl Fn(String) -> std::pin::Pin<Box<dyn Future<Output = Page> + Send>> {
+    let pool = Arc::clone(pool);
+    let options = options.clone();
+    move |url| {
+        let pool = Arc::clone(&pool);
+        let options = match options.per_url.as_ref().and_then(|jobs| jobs.get(&url)) {
+            Some(job) => job.clone(),
+            None => options.clone(),
+        };
+        Box::pin(async move {
+            // Taken before a tab, so waiting on a busy host doesn't hold one
+            let _host_permit = match &options.host_limiter {
+                Some(limiter) => Some(limiter.acquire(&url).await),
+                None => None,
+            };
+            let start = Instant::now();
+            let mut page = fetch_one(&pool, &url, &options).await;
+            if options.follow_canonical {
+                page = follow_canonical(&pool, page, &options).await;
+            }
+            if let Some(max_chars) = options.max_chars {
+                limit_chars(&mut page, max_chars, options.chunks);
+            }
+            if options.metrics {
+                page.elapsed_ms = Some(start.elapsed().as_millis() as u64);
+            }
+            page
+        })
     }
+}
 
-    // Extract content
-    let content_html = if raw {
-        html.to_string()
-    } else {
-        extract_main_content(&doc)
-    };
+/// Spawn `task` per URL and pass `(index, page)` to `on_page` in completion order
+///
+/// At `deadline`, tasks still running are aborted and their URLs (plus any never
+/// started) are passed on as skipped "deadline exceeded" pages. Every page is also
+/// reported as a progress item.
+async fn spawn_until<F, Fut>(
+    urls: Vec<String>,
+    deadline: Option<Instant>,
+    task: F,
+    mut on_page: impl FnMut(usize, Page),
+) where
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = Page> + Send + 'static,
+{
+    if deadline.is_some_and(|d| Instant::now() >= d) {
+        for (idx, url) in urls.iter().enumerate() {
+            events::item(url, false);
+            on_page(idx, deadline_page(url));
+        }
+        return;
+    }
 
-    let content_doc = Html::parse_document(&content_html);
+    let mut pending: Vec<Option<String>> = urls.iter().cloned().map(Some).collect();
+    let mut aborts = Vec::with_capacity(urls.len());
+    let mut tasks: FuturesUnordered<_> = urls
+        .into_iter()
+        .enumerate()
+        .map(|(idx, url)| {
+            let handle = tokio::spawn(task(url));
+            aborts.push(handle.abort_handle());
+            handle.map(move |result| (idx, result))
+        })
+        .collect();
 
-    // Extract sections
-    let sections = extract_sections(&content_doc);
+    loop {
+        let next = match deadline {
+            Some(deadline) => match tokio::time::timeout_at(deadline, tasks.next()).await {
+                Ok(next) => next,
+                Err(_) => break,
+            },
+            None => tasks.next().await,
+        };
+        let Some((idx, result)) = next else {
+            break;
+        };
+        pending[idx] = None;
+        if let Ok(page) = result {
+            events::item(&page.url, page.status == PageStatus::Ok);
+            on_page(idx, page);
+        }
+    }
 
-    // Extract links (content only, not nav)
-    let links = if raw {
-        extract_all_links(&doc, url)
-    } else {
-        extract_content_links(&content_doc, url)
-    };
+    // Finished tasks ignore abort; in-flight ones are cancelled
+    for abort in aborts {
+        abort.abort();
+    }
+    for (idx, url) in pending.into_iter().enumerate() {
+        if let Some(url) = url {
+            events::item(&url, false);
+            on_page(idx, deadline_page(&url));
+        }
+    }
+}
 
-    // Extract code blocks
-    let code = extract_code_blocks(&content_doc);
+/// Breadth-first crawl from seed URLs, following same-domain links up to `depth` levels
+///
+/// Pages are passed to `on_page` as they complete.
+async fn crawl(
+    pool: &Arc<BrowserPool>,
+    seeds: Vec<String>,
+    options: &FetchOptions,
+    depth: usize,
+    max_pages: usize,
+    deadline: Option<Instant>,
+    mut on_page: impl FnMut(Page),
+) {
+    let mut visited = HashSet::new();
+    let mut frontier: Vec<String> = seeds
+        .into_iter()
+        .filter(|u| visited.insert(crawl_key(u)))
+        .take(max_pages)
+        .collect();
+    // Pages fetched or queued so far
+    let mut scheduled = frontier.len();
 
-    // Calculate total chars
-    let chars: usize = sections
-        .iter()
-        .map(|s| s.content.len() + s.heading.len())
-        .sum();
+    for level in 0..=depth {
+        if frontier.is_empty() {
+            break;
+        }
+        if level > 0 {
+            progress!("Depth {}: {} new URLs", level, frontier.len());
+        }
 
-    Page {
-        url: url.to_string(),
-        status,
-        title,
-        site,
-        author,
-        date,
-        doi,
-        sections,
-        links,
-        code,
-        alerts,
-        chars,
+        let mut next = Vec::new();
+        fetch_each(pool, frontier, options, deadline, |page| {
+            if level < depth {
+                let remaining = max_pages.saturating_sub(scheduled);
+                let urls = next_crawl_urls(std::slice::from_ref(&page), &mut visited, remaining);
+                scheduled += urls.len();
+                next.extend(urls);
+            }
+            on_page(page);
+        })
+        .await;
+        frontier = next;
     }
 }
 
-fn extract_title(doc: &Html) -> Option<String> {
+/// Same-domain links from fetched pages that haven't been visited yet
+fn next_crawl_urls(pages: &[Page], visited: &mut HashSet<String>, limit: usize) -> Vec<String> {
+    let mut next = Vec::new();
+
+    for page in pages.iter().filter(|p| p.status == PageStatus::Ok) {
+        let Some(page_host) = bare_host(&page.url) else {
+            continue;
+        };
+
+        for link in &page.links {
+            if next.len() >= limit {
+                return next;
+            }
+            let Ok(parsed) = Url::parse(&link.url) else {
+                continue;
+            };
+            let same_host = bare_host(&link.url).is_some_and(|h| h == page_host);
+            if !same_host || !matches!(parsed.scheme(), "http" | "https") {
+                continue;
+            }
+            if visited.insert(crawl_key(&link.url)) {
+                next.push(link.url.clone());
+            }
+        }
+    }
+
+    next
+}
+
+/// Dedup key for crawling: URL without fragment or trailing slash
+fn crawl_key(url: &str) -> String {
+    match Url::parse(url) {
+        Ok(mut u) => {
+            u.set_fragment(None);
+            u.to_string().trim_end_matches('/').to_string()
+        }
+        Err(_) => url.to_string(),
+    }
+}
+
+/// The page at `page.canonical` when it differs and loads, otherwise `page`
+async fn follow_canonical(pool: &BrowserPool, page: Page, options: &FetchOptions) -> Page {
+    let Some(canonical) = page
+        .canonical
+        .as_deref()
+        .filter(|c| crawl_key(c) != crawl_key(&page.url))
+    else {
+        return page;
+    };
+    let mut followed = fetch_one(pool, canonical, options).await;
+    if followed.status != PageStatus::Ok {
+        return page;
+    }
+    followed
+        .alerts
+        .push(format!("Followed rel=canonical from {}", page.url));
+    followed
+}
+
+async fn fetch_one(pool: &BrowserPool, url: &str, options: &FetchOptions) -> Page {
+    progress!("  -> {}", truncate(url, 60));
+
+    if let Some(robots) = &options.robots {
+        if !robots.is_allowed(url).await {
+            let mut page = error_page(url, "blocked by robots.txt");
+            page.status = PageStatus::Blocked;
+            return page;
+        }
+    }
+
+    // Chrome only renders a viewer for PDFs, so download and extract them directly
+    // (a remote Chrome may reach what this machine can't, so it goes first there)
+    if is_pdf_url(url) && options.pdf.direct {
+        if let Some(page) = fetch_pdf(&options.pdf, url, options.timeout).await {
+            return page;
+        }
+    }
+
+    let started = Instant::now();
+    let page = match pool.new_page().await {
+        Ok(p) => p,
+        Err(e) => return error_page(url, &e.to_string()),
+    };
+    debug!(url, ms = started.elapsed().as_millis() as u64, "new page");
+
+    let started = Instant::now();
+    let nav_started = started;
+    let nav = match retry_navigation(options.retries, || page.goto(url, options.timeout)).await {
+        Ok(n) => n,
+        Err(e) => return error_page(url, &e.to_string()),
+    };
+    debug!(
+        url,
+        ms = started.elapsed().as_millis() as u64,
+        status = nav.status,
+        "navigate"
+    );
+
+    if let Some(error) = nav.error {
+        // Chrome may abort a PDF navigation as a download
+        if is_pdf_url(url) && !options.pdf.direct {
+            if let Some(page) = fetch_pdf(&options.pdf, url, options.timeout).await {
+                return page;
+            }
+        }
+        let mut page = error_page(url, &error);
+        page.title = nav.title;
+        return page;
+    }
+
+    // Check for redirect
+    let final_url = page.current_url().await;
+    if let Some(final_u) = final_url.filter(|u| is_cross_domain(url, u, false)) {
+        let mut page = error_page(url, &format!("Redirected to: {}", final_u));
+        page.status = PageStatus::Redirect;
+        page.title = nav.title;
+        return page;
+    }
+
+    // PDF served without a .pdf extension: use the bytes Chrome already has
+    if nav.content_type.as_deref() == Some("application/pdf") {
+        if let Some(bytes) = nav.pdf.filter(|b| b.starts_with(b"%PDF")) {
+            return extract_pdf_bytes(&bytes, url);
+        }
+        if let Some(page) = fetch_pdf(&options.pdf, url, options.timeout).await {
+            return page;
+        }
+    }
+
+    // JSON APIs, plain text and XML: return the body instead of Chrome's viewer markup
+    if let (Some(content_type), Some(body)) = (nav.content_type, nav.body) {
+        return raw_page(url, content_type, body);
+    }
+
+    let mut alerts = Vec::new();
+    if let Some(selector) = &options.wait_for {
+        // Shares --timeout with the navigation rather than getting a second one
+        let remaining = options
+            .timeout
+            .saturating_sub(nav_started.elapsed().as_millis() as u64);
+        if !page.wait_for_selector(selector, remaining).await {
+            alerts.push(format!("Selector not found: {}", selector));
+        }
+    }
+
+    let started = Instant::now();
+    let html = match page.content().await {
+        Ok(h) => h,
+        Err(e) => return error_page(url, &e.to_string()),
+    };
+    debug!(
+        url,
+        ms = started.elapsed().as_millis() as u64,
+        bytes = html.len(),
+        "content"
+    );
+
+    let started = Instant::now();
+    let mut parsed = parse_page(&html, url, options);
+    debug!(url, ms = started.elapsed().as_millis() as u64, "parse");
+    parsed.alerts.extend(alerts);
+    parsed
+}
+
+/// Cut sections (or a raw body) to about `max_chars` characters, then
+/// rebuild `chunks` (of `chunk_tokens`) from what is left
+fn limit_chars(page: &mut Page, max_chars: usize, chunk_tokens: Option<usize>) {
+    if page.chars <= max_chars {
+        return;
+    }
+    // truncate_words counts bytes: give it the byte length of `n` chars
+    let cut =
+        |s: &str, n: usize| truncate_words(s, s.char_indices().nth(n).map_or(s.len(), |(i, _)| i));
+    if let Some(text) = &mut page.text {
+        *text = cut(text, max_chars);
+        page.chars = text.chars().count();
+        page.words = text.split_whitespace().count();
+    } else {
+        let mut budget = max_chars;
+        let mut kept = Vec::new();
+        for mut section in std::mem::take(&mut page.sections) {
+            let heading = section.heading.chars().count();
+            let size = heading + section.content.chars().count();
+            if size > budget {
+                let room = budget.saturating_sub(heading);
+                if room > 3 {
+                    section.content = cut(&section.content, room);
+                    kept.push(section);
+                }
+                break;
+            }
+            budget -= size;
+            kept.push(section);
+        }
+        page.sections = kept;
+        page.chars = char_count(&page.sections);
+        page.words = word_count(&page.sections);
+        if let Some(tokens) = chunk_tokens {
+            page.chunks = chunk_sections(&page.sections, &page.url, tokens);
+        }
+    }
+    page.reading_minutes = reading_minutes(page.words);
+    page.alerts
+        .push(format!("Truncated to {} chars", max_chars));
+}
+
+/// URL path ends in .pdf
+fn is_pdf_url(url: &str) -> bool {
+    Url::parse(url)
+        .map(|u| u.path().to_lowercase().ends_with(".pdf"))
+        .unwrap_or(false)
+}
+
+/// HTTP client for PDFs downloaded outside Chrome, with the browser's cookies and proxy
+#[derive(Clone)]
+pub struct PdfClient {
+    client: reqwest::Client,
+    cookies: Arc<Vec<Cookie>>,
+    /// Download `.pdf` URLs before trying Chrome (false with a remote Chrome)
+    direct: bool,
+    max_bytes: u64,
+}
+
+impl Default for PdfClient {
+    fn default() -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            cookies: Arc::default(),
+            direct: true,
+            max_bytes: MAX_PDF_BYTES,
+        }
+    }
+}
+
+impl PdfClient {
+    pub fn new(
+        user_agent: &str,
+        cookies: Vec<Cookie>,
+        socks5: Option<&str>,
+        direct: bool,
+    ) -> Result<Self> {
+        Ok(Self {
+            client: http_client(socks5)?.user_agent(user_agent).build()?,
+            cookies: Arc::new(cookies),
+            direct,
+            max_bytes: MAX_PDF_BYTES,
+        })
+    }
+}
+
+/// Download and extract a PDF (None if the response turns out not to be a PDF)
+async fn fetch_pdf(client: &PdfClient, url: &str, timeout_ms: u64) -> Option<Page> {
+    match download_pdf(client, url, timeout_ms).await {
+        Ok(Some(bytes)) => Some(extract_pdf_bytes(&bytes, url)),
+        Ok(None) => None,
+        Err(e) => Some(error_page(url, &e.to_string())),
+    }
+}
+
+async fn download_pdf(client: &PdfClient, url: &str, timeout_ms: u64) -> Result<Option<Vec<u8>>> {
+    let mut request = client
+        .client
+        .get(url)
+        .timeout(Duration::from_millis(timeout_ms));
+    if let Some(cookies) = cookie_header(&client.cookies, url) {
+        request = request.header(reqwest::header::COOKIE, cookies);
+    }
+
+    let mut response = request.send().await?;
+    if !response.status().is_success() {
+        anyhow::bail!("HTTP {}", response.status());
+    }
+    let too_large = || anyhow::anyhow!("PDF larger than {} bytes", client.max_bytes);
+    if response
+        .content_length()
+        .is_some_and(|n| n > client.max_bytes)
+    {
+        return Err(too_large());
+    }
+
+    let mut bytes = Vec::new();
+    while let Some(chunk) = response.chunk().await? {
+        bytes.extend_from_slice(&chunk);
+        if bytes.len() as u64 > client.max_bytes {
+            return Err(too_large());
+        }
+    }
+    // Trust the file signature over the URL or Content-Type
+    Ok(bytes.starts_with(b"%PDF").then_some(bytes))
+}
+
+/// --stdin-html: run already-fetched HTML through the parser, no browser
+fn parse_stdin_html(args: &FetchArgs) -> Result<()> {
+    let html = std::io::read_to_string(std::io::stdin()).context("Failed to read stdin")?;
+    let url = args.url.as_deref().unwrap_or(STDIN_URL);
+    let page = parse_page(&html, url, &FetchOptions::from_args(args));
+    let mut sink = PageSink::open(args)?;
+    sink.write(&page)?;
+    sink.finish()
+}
+
+/// Page for a non-HTML document: the body as-is in `text`
+fn raw_page(url: &str, content_type: String, body: String) -> Page {
+    let words = body.split_whitespace().count();
+    Page {
+        status: PageStatus::Ok,
+        content_type: Some(content_type),
+        chars: body.chars().count(),
+        words,
+        reading_minutes: reading_minutes(words),
+        alerts: vec![],
+        text: Some(body),
+        ..error_page(url, "")
+    }
+}
+
+/// Page for a URL cut off by --deadline
+fn deadline_page(url: &str) -> Page {
+    Page {
+        status: PageStatus::Skipped,
+        ..error_page(url, DEADLINE_EXCEEDED)
+    }
+}
+
+fn error_page(url: &str, error: &str) -> Page {
+    Page {
+        url: url.to_string(),
+        status: PageStatus::Dead,
+        title: None,
+        site: None,
+        author: None,
+        date: None,
+        doi: None,
+        canonical: None,
+        lang: None,
+        subject: None,
+        pages: None,
+        meta: None,
+        jsonld: vec![],
+        content_type: None,
+        text: None,
+        sections: vec![],
+        links: vec![],
+        code: vec![],
+        tables: vec![],
+        chunks: vec![],
+        alerts: vec![error.to_string()],
+        chars: 0,
+        words: 0,
+        reading_minutes: 0,
+        elapsed_ms: None,
+    }
+}
+
+fn parse_page(html: &str, url: &str, options: &FetchOptions) -> Page {
+    let raw = options.raw;
+    let doc = Html::parse_document(html);
+    let mut alerts = Vec::new();
+
+    // Extract metadata
+    let title = extract_title(&doc);
+    let site = extract_meta(&doc, "og:site_name");
+    let author = extract_author(&doc);
+    let date = extract_date(&doc);
+    let doi = extract_doi(&doc);
+    let canonical = extract_canonical(&doc, url);
+    let meta = options.meta.then(|| extract_page_meta(&doc, url));
+    let jsonld = if options.jsonld {
+        extract_jsonld(&doc)
+    } else {
+        vec![]
+    };
+
+    // Check for paywall/login
+    let status = detect_status(html);
+    if status == PageStatus::Paywall {
+        alerts.push("Paywall detected".to_string());
+    } else if status == PageStatus::Login {
+        alerts.push("Login required".to_string());
+    }
+
+    // Extract content
+    let content_html = if raw {
+        html.to_string()
+    } else {
+        extract_main_content(&doc)
+    };
+
+    let content_doc = Html::parse_document(&content_html);
+
+    // Extract sections
+    let sections = match options.text_mode {
+        TextMode::Html2text => extract_sections(&content_doc),
+        TextMode::Semantic => semantic_sections(&content_doc),
+    };
+
+    // Extract links (content only, not nav)
+    let links = if options.links {
+        extract_link_graph(&doc, url)
+    } else if raw {
+        extract_all_links(&doc, url)
+    } else {
+        extract_content_links(&content_doc, url)
+    };
+
+    // Extract code blocks
+    let code = extract_code_blocks(&content_doc);
+
+    // Extract tables
+    let tables = if options.tables {
+        extract_tables(&content_doc)
+    } else {
+        vec![]
+    };
+
+    let chunks = match options.chunks {
+        Some(tokens) => chunk_sections(&sections, url, tokens),
+        None => vec![],
+    };
+
+    let chars = char_count(&sections);
+    let words = word_count(&sections);
+
+    // Declared language wins, otherwise guess from the extracted text
+    let lang = extract_lang(&doc).or_else(|| {
+        let text: Vec<&str> = sections.iter().map(|s| s.content.as_str()).collect();
+        detect_text_language(&text.join("\n"))
+    });
+
+    Page {
+        url: url.to_string(),
+        status,
+        title,
+        site,
+        author,
+        date,
+        doi,
+        canonical,
+        lang,
+        subject: None,
+        pages: None,
+        meta,
+        jsonld,
+        content_type: None,
+        text: None,
+        sections,
+        links,
+        code,
+        tables,
+        chunks,
+        alerts,
+        chars,
+        words,
+        reading_minutes: reading_minutes(words),
+        elapsed_ms: None,
+    }
+}
+
+/// Split sections into chunks of about `tokens` tokens
+///
+/// Chunks never cross a section, and break between paragraphs where possible;
+/// a paragraph longer than a chunk is split between words.
+fn chunk_sections(sections: &[Section], url: &str, tokens: usize) -> Vec<Chunk> {
+    let max_chars = tokens * CHARS_PER_TOKEN;
+    let mut chunks = Vec::new();
+    for section in sections {
+        let mut text = String::new();
+        let pieces = section
+            .content
+            .split("\n\n")
+            .flat_map(|p| split_words(p.trim(), max_chars));
+        for piece in pieces {
+            if !text.is_empty() && text.len() + 2 + piece.len() > max_chars {
+                chunks.push(chunk(url, &section.heading, std::mem::take(&mut text)));
+            }
+            if !text.is_empty() {
+                text.push_str("\n\n");
+            }
+            text.push_str(&piece);
+        }
+        if !text.is_empty() {
+            chunks.push(chunk(url, &section.heading, text));
+        }
+    }
+    chunks
+}
+
+/// `text` in pieces of at most `max_chars`, broken between words
+fn split_words(text: &str, max_chars: usize) -> Vec<String> {
+    let mut pieces = vec![String::new()];
+    for word in text.split_whitespace() {
+        let piece = pieces.last_mut().unwrap();
+        if !piece.is_empty() && piece.len() + 1 + word.len() > max_chars {
+            pieces.push(word.to_string());
+        } else {
+            if !piece.is_empty() {
+                piece.push(' ');
+            }
+            piece.push_str(word);
+        }
+    }
+    pieces.retain(|p| !p.is_empty());
+    pieces
+}
+
+fn chunk(url: &str, heading: &str, text: String) -> Chunk {
+    let digest = Sha256::digest(format!("{}\n{}\n{}", url, heading, text));
+    Chunk {
+        id: digest[..6].iter().map(|b| format!("{:02x}", b)).collect(),
+        heading: heading.to_string(),
+        text,
+    }
+}
+
+/// Characters (not bytes) in section headings and text, for `chars`
+pub(crate) fn char_count(sections: &[Section]) -> usize {
+    sections
+        .iter()
+        .map(|s| s.heading.chars().count() + s.content.chars().count())
+        .sum()
+}
+
+/// Whitespace-separated words across section headings and content
+pub(crate) fn word_count(sections: &[Section]) -> usize {
+    sections
+        .iter()
+        .map(|s| s.heading.split_whitespace().count() + s.content.split_whitespace().count())
+        .sum()
+}
+
+/// Minutes to read `words` at 230 wpm, rounded up
+pub(crate) fn reading_minutes(words: usize) -> u32 {
+    words.div_ceil(WORDS_PER_MINUTE) as u32
+}
+
+pub(crate) fn extract_title(doc: &Html) -> Option<String> {
     select_text(doc, "title")
         .or_else(|| select_attr(doc, "meta[property='og:title']", "content"))
         .or_else(|| select_text(doc, "h1"))
@@ -317,6 +1522,293 @@ fn extract_meta(doc: &Html, name: &str) -> Option<String> {
         .or_else(|| select_attr(doc, &format!("meta[name='{}']", name), "content"))
 }
 
+/// `<link rel="canonical">` resolved against the page URL
+fn extract_canonical(doc: &Html, url: &str) -> Option<String> {
+    let href = select_attr(doc, "link[rel~='canonical']", "href")?;
+    resolve_href(Url::parse(url).ok().as_ref(), &href)
+}
+
+/// Byline from `<meta name="author">`, JSON-LD `author`, `rel="author"`
+/// links, then `<meta property="article:author">`; several authors are
+/// comma-joined
+fn extract_author(doc: &Html) -> Option<String> {
+    join_names(select_all(doc, "meta[name='author']", |el| {
+        el.value().attr("content")
+    }))
+    .or_else(|| {
+        jsonld_blocks(doc)
+            .iter()
+            .find_map(|block| find_json_value(block, "author"))
+            .and_then(|author| join_names(json_names(author)))
+    })
+    .or_else(|| {
+        join_names(select_all(
+            doc,
+            "a[rel~='author'], link[rel~='author']",
+            |el| {
+                let text = el.text().collect::<String>();
+                Some(text)
+                    .filter(|t| !t.trim().is_empty())
+                    .or_else(|| el.value().attr("title").map(str::to_string))
+            },
+        ))
+    })
+    .or_else(|| {
+        join_names(select_all(doc, "meta[property='article:author']", |el| {
+            el.value().attr("content")
+        }))
+    })
+}
+
+/// Trimmed, non-empty `value` of each element matching `sel`
+fn select_all<'a, F, S>(doc: &'a Html, sel: &str, value: F) -> Vec<String>
+where
+    F: Fn(ElementRef<'a>) -> Option<S>,
+    S: AsRef<str>,
+{
+    let Ok(selector) = Selector::parse(sel) else {
+        return vec![];
+    };
+    doc.select(&selector)
+        .filter_map(value)
+        .map(|s| collapse_whitespace(s.as_ref()))
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+/// Names in a JSON-LD `author`: a string, a `{"name": ..}` object or a list of either
+fn json_names(value: &serde_json::Value) -> Vec<String> {
+    match value {
+        serde_json::Value::String(name) => vec![name.trim().to_string()],
+        serde_json::Value::Object(map) => map.get("name").map(json_names).unwrap_or_default(),
+        serde_json::Value::Array(items) => items.iter().flat_map(json_names).collect(),
+        _ => vec![],
+    }
+}
+
+/// Distinct names joined with ", "; `None` when there are none
+fn join_names(names: Vec<String>) -> Option<String> {
+    let mut seen = HashSet::new();
+    let names: Vec<String> = names
+        .into_iter()
+        .filter(|name| !name.is_empty() && seen.insert(name.clone()))
+        .collect();
+    (!names.is_empty()).then(|| names.join(", "))
+}
+
+/// Social card tags; og:image is resolved against the page URL
+fn extract_page_meta(doc: &Html, url: &str) -> PageMeta {
+    let base = Url::parse(url).ok();
+    PageMeta {
+        image: extract_meta(doc, "og:image")
+            .or_else(|| extract_meta(doc, "twitter:image"))
+            .and_then(|src| resolve_href(base.as_ref(), &src)),
+        og_type: extract_meta(doc, "og:type"),
+        site_name: extract_meta(doc, "og:site_name"),
+        published_time: extract_meta(doc, "article:published_time"),
+        twitter_card: extract_meta(doc, "twitter:card"),
+        favicon: extract_favicon(doc, base.as_ref()),
+    }
+}
+
+/// `<link rel="icon">`, then `apple-touch-icon`, else the site's `/favicon.ico`
+fn extract_favicon(doc: &Html, base: Option<&Url>) -> Option<String> {
+    select_attr(doc, "link[rel~='icon'][href]", "href")
+        .or_else(|| select_attr(doc, "link[rel^='apple-touch-icon'][href]", "href"))
+        .and_then(|href| resolve_href(base, &href))
+        .or_else(|| base?.join("/favicon.ico").ok().map(|u| u.to_string()))
+}
+
+/// Publication date from meta tags, `<time datetime>`, JSON-LD
+/// `datePublished`, or a "Published: ..." line in the body, as ISO 8601
+fn extract_date(doc: &Html) -> Option<String> {
+    let raw = extract_meta(doc, "article:published_time")
+        .or_else(|| extract_meta(doc, "date"))
+        .or_else(|| extract_meta(doc, "pubdate"))
+        .or_else(|| select_attr(doc, "time[datetime]", "datetime"))
+        .or_else(|| {
+            jsonld_blocks(doc)
+                .iter()
+                .find_map(|block| find_json_key(block, "datePublished"))
+        })
+        .or_else(|| body_text_date(doc))?;
+    Some(normalize_date(&raw).unwrap_or(raw))
+}
+
+/// Parsed `<script type="application/ld+json">` blocks (malformed ones skipped)
+fn jsonld_blocks(doc: &Html) -> Vec<serde_json::Value> {
+    let Ok(sel) = Selector::parse(r#"script[type="application/ld+json"]"#) else {
+        return vec![];
+    };
+    doc.select(&sel)
+        .filter_map(|el| serde_json::from_str(&el.text().collect::<String>()).ok())
+        .collect()
+}
+
+/// JSON-LD items from all blocks, with top-level arrays and `@graph` flattened
+fn extract_jsonld(doc: &Html) -> Vec<serde_json::Value> {
+    let mut items = Vec::new();
+    for block in jsonld_blocks(doc) {
+        flatten_jsonld(block, &mut items);
+    }
+    items
+}
+
+fn flatten_jsonld(value: serde_json::Value, items: &mut Vec<serde_json::Value>) {
+    match value {
+        serde_json::Value::Array(list) => {
+            for item in list {
+                flatten_jsonld(item, items);
+            }
+        }
+        serde_json::Value::Object(mut map) => match map.remove("@graph") {
+            Some(graph) => flatten_jsonld(graph, items),
+            None => items.push(serde_json::Value::Object(map)),
+        },
+        _ => {}
+    }
+}
+
+/// First string value for `key`, searching nested objects and arrays (`@graph`)
+fn find_json_key(value: &serde_json::Value, key: &str) -> Option<String> {
+    match value {
+        serde_json::Value::Object(map) => map
+            .get(key)
+            .and_then(|v| v.as_str())
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .or_else(|| map.values().find_map(|v| find_json_key(v, key))),
+        serde_json::Value::Array(items) => items.iter().find_map(|v| find_json_key(v, key)),
+        _ => None,
+    }
+}
+
+/// First value for `key` of any type, searching like [`find_json_key`]
+fn find_json_value<'a>(value: &'a serde_json::Value, key: &str) -> Option<&'a serde_json::Value> {
+    match value {
+        serde_json::Value::Object(map) => map
+            .get(key)
+            .or_else(|| map.values().find_map(|v| find_json_value(v, key))),
+        serde_json::Value::Array(items) => items.iter().find_map(|v| find_json_value(v, key)),
+        _ => None,
+    }
+}
+
+/// Date after a "Published"/"Posted"/"Updated"/"Date" label in the body text
+fn body_text_date(doc: &Html) -> Option<String> {
+    let re = Regex::new(
+        r"(?i)\b(?:published|posted|updated|date)\b:?\s+(?:on\s+)?(\d{4}-\d{2}-\d{2}|(?:jan|feb|mar|apr|may|jun|jul|aug|sep|oct|nov|dec)[a-z]*\.?\s+\d{1,2},?\s+\d{4}|\d{1,2}\s+(?:jan|feb|mar|apr|may|jun|jul|aug|sep|oct|nov|dec)[a-z]*\.?\s+\d{4})",
+    )
+    .ok()?;
+    let sel = Selector::parse("body").ok()?;
+    let body = doc.select(&sel).next()?;
+    let text = body.text().collect::<Vec<_>>().join(" ");
+    re.captures(&text).map(|c| c[1].to_string())
+}
+
+/// Normalize a date string to ISO 8601 (None if the format isn't recognized)
+fn normalize_date(raw: &str) -> Option<String> {
+    let raw = raw.trim();
+    if DateTime::parse_from_rfc3339(raw).is_ok() {
+        return Some(raw.to_string());
+    }
+    if let Ok(dt) = DateTime::parse_from_rfc2822(raw) {
+        return Some(dt.to_rfc3339());
+    }
+    if let Ok(dt) = NaiveDateTime::parse_from_str(raw, "%Y-%m-%dT%H:%M:%S") {
+        return Some(dt.format("%Y-%m-%dT%H:%M:%S").to_string());
+    }
+
+    // "Jan. 5, 2024" -> "Jan 5 2024"
+    let cleaned = raw.replace([',', '.'], "");
+    let cleaned = cleaned.split_whitespace().collect::<Vec<_>>().join(" ");
+    [
+        "%Y-%m-%d", "%Y/%m/%d", "%B %d %Y", "%b %d %Y", "%d %B %Y", "%d %b %Y",
+    ]
+    .iter()
+    .find_map(|fmt| {
+        NaiveDate::parse_from_str(raw, fmt)
+            .or_else(|_| NaiveDate::parse_from_str(&cleaned, fmt))
+            .ok()
+    })
+    .map(|d| d.format("%Y-%m-%d").to_string())
+}
+
+/// Primary subtag of <html lang> ("en-US" -> "en")
+fn extract_lang(doc: &Html) -> Option<String> {
+    let lang = select_attr(doc, "html", "lang")?;
+    let primary = lang.split(['-', '_']).next()?.trim().to_lowercase();
+    (!primary.is_empty()).then_some(primary)
+}
+
+/// Detect the language of extracted text (None if too short or ambiguous)
+pub fn detect_text_language(text: &str) -> Option<String> {
+    // Trigram detection is unreliable on a handful of words
+    if text.split_whitespace().count() < 20 {
+        return None;
+    }
+
+    let info = whatlang::detect(text)?;
+    if !info.is_reliable() {
+        return None;
+    }
+    Some(
+        iso_639_1(info.lang())
+            .unwrap_or(info.lang().code())
+            .to_string(),
+    )
+}
+
+/// Two-letter code for a detected language (whatlang reports ISO 639-3)
+fn iso_639_1(lang: whatlang::Lang) -> Option<&'static str> {
+    use whatlang::Lang::*;
+    Some(match lang {
+        Eng => "en",
+        Fra => "fr",
+        Deu => "de",
+        Spa => "es",
+        Por => "pt",
+        Ita => "it",
+        Nld => "nl",
+        Rus => "ru",
+        Ukr => "uk",
+        Pol => "pl",
+        Ces => "cs",
+        Slk => "sk",
+        Hun => "hu",
+        Ron => "ro",
+        Bul => "bg",
+        Hrv => "hr",
+        Srp => "sr",
+        Slv => "sl",
+        Ell => "el",
+        Tur => "tr",
+        Swe => "sv",
+        Dan => "da",
+        Nob => "nb",
+        Fin => "fi",
+        Est => "et",
+        Lav => "lv",
+        Lit => "lt",
+        Cat => "ca",
+        Lat => "la",
+        Epo => "eo",
+        Ara => "ar",
+        Heb => "he",
+        Pes => "fa",
+        Hin => "hi",
+        Ben => "bn",
+        Urd => "ur",
+        Cmn => "zh",
+        Jpn => "ja",
+        Kor => "ko",
+        Vie => "vi",
+        Tha => "th",
+        Ind => "id",
+        _ => return None,
+    })
+}
+
 fn extract_doi(doc: &Html) -> Option<String> {
     // Check meta tags
     if let Some(doi) = select_attr(doc, "meta[name='citation_doi']", "content") {
@@ -429,48 +1921,42 @@ fn extract_main_content(doc: &Html) -> String {
 }
 
 fn extract_sections(doc: &Html) -> Vec<Section> {
-    let mut sections = Vec::new();
+    let mut sections: Vec<Section> = Vec::new();
     let mut current_section: Option<Section> = None;
 
-    // Simple approach: extract headings and paragraphs
-    for tag in ["h1", "h2", "h3", "h4", "h5", "h6", "p"] {
-        if let Ok(sel) = Selector::parse(tag) {
-            for el in doc.select(&sel) {
-                let text: String = el.text().collect::<String>().trim().to_string();
-                if text.is_empty() || text.len() < 3 {
-                    continue;
-                }
+    // Headings and paragraphs in document order, so sections follow the outline
+    let sel = Selector::parse("h1, h2, h3, h4, h5, h6, p").unwrap();
+    for el in doc.select(&sel) {
+        let text: String = el.text().collect::<String>().trim().to_string();
+        if text.is_empty() || text.len() < 3 {
+            continue;
+        }
 
-                if tag.starts_with('h') {
-                    // Flush current section
-                    if let Some(s) = current_section.take() {
-                        if !s.content.is_empty() {
-                            sections.push(s);
-                        }
-                    }
+        let tag = el.value().name();
+        if tag.starts_with('h') {
+            // Flush current section
+            if let Some(s) = current_section.take() {
+                push_section(&mut sections, s);
+            }
 
-                    let level = tag.chars().nth(1).unwrap_or('1').to_digit(10).unwrap_or(1) as u8;
-                    current_section = Some(Section {
-                        level,
-                        heading: truncate_section(&text, 200),
-                        content: String::new(),
-                    });
-                } else if let Some(ref mut s) = current_section {
-                    // Add paragraph to current section
-                    if !s.content.is_empty() {
-                        s.content.push_str("\n\n");
-                    }
-                    s.content.push_str(&truncate_section(&text, 2000));
-                }
+            let level = tag.chars().nth(1).unwrap_or('1').to_digit(10).unwrap_or(1) as u8;
+            current_section = Some(Section {
+                level,
+                heading: truncate_section(&text, 200),
+                content: String::new(),
+            });
+        } else if let Some(ref mut s) = current_section {
+            // Add paragraph to current section
+            if !s.content.is_empty() {
+                s.content.push_str("\n\n");
             }
+            s.content.push_str(&truncate_section(&text, 2000));
         }
     }
 
     // Flush final section
     if let Some(s) = current_section {
-        if !s.content.is_empty() {
-            sections.push(s);
-        }
+        push_section(&mut sections, s);
     }
 
     // If no sections found, create one from all text
@@ -497,6 +1983,211 @@ fn extract_sections(doc: &Html) -> Vec<Section> {
     sections
 }
 
+/// A block of text found by the semantic DOM walk
+#[derive(Debug, PartialEq)]
+enum TextBlock {
+    Heading(u8, String),
+    Paragraph(String),
+    /// List item at a nesting depth (0 = top level)
+    Item(usize, String),
+}
+
+/// Elements that never hold readable content
+const SKIPPED_TAGS: [&str; 7] = [
+    "script", "style", "noscript", "template", "head", "nav", "footer",
+];
+
+/// Elements that start a new block; anything else is inline text
+const BLOCK_TAGS: [&str; 24] = [
+    "html",
+    "body",
+    "main",
+    "article",
+    "section",
+    "div",
+    "header",
+    "aside",
+    "p",
+    "ul",
+    "ol",
+    "li",
+    "dl",
+    "dt",
+    "dd",
+    "blockquote",
+    "figure",
+    "figcaption",
+    "table",
+    "tr",
+    "td",
+    "th",
+    "pre",
+    "form",
+];
+
+/// Sections from a DOM walk: lists become `- ` items (indented when nested)
+///
+/// Text before the first heading goes in a "Content" section, as does a page
+/// without headings.
+fn semantic_sections(doc: &Html) -> Vec<Section> {
+    let mut blocks = Vec::new();
+    walk_blocks(doc.root_element(), 0, &mut blocks);
+
+    let mut sections: Vec<Section> = Vec::new();
+    let mut current: Option<Section> = None;
+    let mut after_item = false;
+    for block in blocks {
+        let (line, item) = match block {
+            TextBlock::Heading(level, text) => {
+                if let Some(s) = current.take() {
+                    push_section(&mut sections, s);
+                }
+                current = Some(Section {
+                    level,
+                    heading: truncate_section(&text, 200),
+                    content: String::new(),
+                });
+                continue;
+            }
+            TextBlock::Paragraph(text) => (text, false),
+            TextBlock::Item(depth, text) => (format!("{}- {}", "  ".repeat(depth), text), true),
+        };
+        let section = current.get_or_insert_with(|| Section {
+            level: 1,
+            heading: "Content".to_string(),
+            content: String::new(),
+        });
+        if !section.content.is_empty() {
+            // List items stay on consecutive lines
+            section
+                .content
+                .push_str(if item && after_item { "\n" } else { "\n\n" });
+        }
+        section.content.push_str(&truncate_section(&line, 2000));
+        after_item = item;
+    }
+    if let Some(s) = current {
+        push_section(&mut sections, s);
+    }
+
+    sections.truncate(50);
+    sections
+}
+
+/// Collect the text blocks under `el`; `depth` counts enclosing lists
+fn walk_blocks(el: ElementRef, depth: usize, blocks: &mut Vec<TextBlock>) {
+    let mut inline = String::new();
+    for child in el.children() {
+        let Some(child_el) = ElementRef::wrap(child) else {
+            if let Some(text) = child.value().as_text() {
+                inline.push_str(text);
+            }
+            continue;
+        };
+        let name = child_el.value().name();
+        if SKIPPED_TAGS.contains(&name) {
+            continue;
+        }
+        if name == "br" {
+            inline.push(' ');
+            continue;
+        }
+        if !BLOCK_TAGS.contains(&name) && !is_heading(name) {
+            inline.push_str(&inline_text(child_el));
+            continue;
+        }
+
+        flush_paragraph(&mut inline, blocks);
+        match name {
+            h if is_heading(h) => {
+                let text = collapse_whitespace(&inline_text(child_el));
+                if !text.is_empty() {
+                    let level = h[1..].parse().unwrap_or(1);
+                    blocks.push(TextBlock::Heading(level, text));
+                }
+            }
+            "ul" | "ol" => walk_blocks(child_el, depth + 1, blocks),
+            "li" => {
+                let text = collapse_whitespace(&item_text(child_el));
+                if !text.is_empty() {
+                    blocks.push(TextBlock::Item(depth.saturating_sub(1), text));
+                }
+                // Nested lists follow their item
+                for list in child_el
+                    .children()
+                    .filter_map(ElementRef::wrap)
+                    .filter(|e| matches!(e.value().name(), "ul" | "ol"))
+                {
+                    walk_blocks(list, depth + 1, blocks);
+                }
+            }
+            _ => walk_blocks(child_el, depth, blocks),
+        }
+    }
+    flush_paragraph(&mut inline, blocks);
+}
+
+fn is_heading(name: &str) -> bool {
+    matches!(name, "h1" | "h2" | "h3" | "h4" | "h5" | "h6")
+}
+
+fn flush_paragraph(inline: &mut String, blocks: &mut Vec<TextBlock>) {
+    let text = collapse_whitespace(inline);
+    if !text.is_empty() {
+        blocks.push(TextBlock::Paragraph(text));
+    }
+    inline.clear();
+}
+
+/// Text of an element, minus skipped elements
+fn inline_text(el: ElementRef) -> String {
+    child_text(el, &[])
+}
+
+/// A list item's own text, without its nested lists
+fn item_text(li: ElementRef) -> String {
+    child_text(li, &["ul", "ol"])
+}
+
+/// Text under `el` leaving out `exclude` and skipped elements; blocks and
+/// `<br>` separate words
+fn child_text(el: ElementRef, exclude: &[&str]) -> String {
+    let mut text = String::new();
+    for child in el.children() {
+        let Some(e) = ElementRef::wrap(child) else {
+            text.push_str(child.value().as_text().map_or("", |t| &**t));
+            continue;
+        };
+        let name = e.value().name();
+        if exclude.contains(&name) || SKIPPED_TAGS.contains(&name) {
+            continue;
+        }
+        let block = name == "br" || BLOCK_TAGS.contains(&name) || is_heading(name);
+        if block {
+            text.push(' ');
+        }
+        text.push_str(&inline_text(e));
+        if block {
+            text.push(' ');
+        }
+    }
+    text
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Keep sections with content, dropping exact repeats (nav blocks rendered twice)
+fn push_section(sections: &mut Vec<Section>, section: Section) {
+    let repeated = sections.iter().any(|s| {
+        s.level == section.level && s.heading == section.heading && s.content == section.content
+    });
+    if !section.content.is_empty() && !repeated {
+        sections.push(section);
+    }
+}
+
 fn extract_content_links(doc: &Html, base_url: &str) -> Vec<Link> {
     let mut links = Vec::new();
     let mut seen = HashSet::new();
@@ -507,23 +2198,14 @@ fn extract_content_links(doc: &Html, base_url: &str) -> Vec<Link> {
             let text: String = el.text().collect::<String>().trim().to_string();
             let href = el.value().attr("href").unwrap_or("");
 
-            // Skip empty, anchor-only, or javascript links
-            if href.is_empty() || href.starts_with('#') || href.starts_with("javascript:") {
-                continue;
-            }
-
             // Skip short link text (likely nav)
             if text.len() < 3 {
                 continue;
             }
 
-            // Resolve relative URLs
-            let full_url = if let Some(ref base) = base {
-                base.join(href)
-                    .map(|u| u.to_string())
-                    .unwrap_or_else(|_| href.to_string())
-            } else {
-                href.to_string()
+            // Resolve relative URLs, skipping empty, anchor-only or javascript links
+            let Some(full_url) = resolve_href(base.as_ref(), href) else {
+                continue;
             };
 
             // Dedupe
@@ -546,6 +2228,67 @@ fn extract_content_links(doc: &Html, base_url: &str) -> Vec<Link> {
     links
 }
 
+/// Every link on the page (navigation included), for agents walking the link graph
+fn extract_link_graph(doc: &Html, base_url: &str) -> Vec<Link> {
+    let mut links = Vec::new();
+    let mut seen = HashSet::new();
+    let base = Url::parse(base_url).ok();
+
+    let Ok(sel) = Selector::parse("a[href]") else {
+        return links;
+    };
+    for el in doc.select(&sel) {
+        let href = el.value().attr("href").unwrap_or("");
+        let Some(full_url) = resolve_href(base.as_ref(), href) else {
+            continue;
+        };
+        if !seen.insert(full_url.clone()) {
+            continue;
+        }
+
+        // Image-only links have no text; fall back to the link's label
+        let text = el.text().collect::<Vec<_>>().join(" ");
+        let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        let text = if text.is_empty() {
+            el.value()
+                .attr("aria-label")
+                .or_else(|| el.value().attr("title"))
+                .unwrap_or("")
+                .trim()
+                .to_string()
+        } else {
+            text
+        };
+
+        links.push(Link {
+            text: truncate(&text, 100),
+            url: full_url,
+        });
+
+        if links.len() >= 200 {
+            break;
+        }
+    }
+
+    links
+}
+
+/// Resolve an href against the page URL (None for empty, anchor-only or javascript: links)
+fn resolve_href(base: Option<&Url>, href: &str) -> Option<String> {
+    let href = href.trim();
+    if href.is_empty() || href.starts_with('#') || href.starts_with("javascript:") {
+        return None;
+    }
+
+    Some(match base {
+        Some(base) => base
+            .join(href)
+            .map(|u| u.to_string())
+            .unwrap_or_else(|_| href.to_string()),
+        None => href.to_string(),
+    })
+}
+
 fn extract_all_links(doc: &Html, base_url: &str) -> Vec<Link> {
     // In raw mode, extract all links
     extract_content_links(doc, base_url)
@@ -572,29 +2315,138 @@ fn extract_code_blocks(doc: &Html) -> Vec<CodeBlock> {
             }
             seen_sources.insert(source.clone());
 
-            // Try to detect language from class
-            let lang = el.value().attr("class").and_then(|c| {
-                c.split_whitespace()
-                    .find(|cls| cls.starts_with("language-") || cls.starts_with("lang-"))
-                    .map(|cls| {
-                        cls.trim_start_matches("language-")
-                            .trim_start_matches("lang-")
-                            .to_string()
-                    })
+            // Language from the markup, else guessed from the source
+            let lang = code_block_language(el).or_else(|| detect_language(&source));
+
+            blocks.push(CodeBlock {
+                lang,
+                source: truncate_section(&source, 5000),
+            });
+
+            if blocks.len() >= 20 {
+                break;
+            }
+        }
+    }
+
+    blocks
+}
+
+/// Language hint on a code element or its wrappers (`<pre>`, GitHub's `<div>`)
+///
+/// Recognizes `language-x`/`lang-x`/`highlight-source-x` classes and `data-lang`.
+fn code_block_language(el: ElementRef) -> Option<String> {
+    std::iter::once(el)
+        .chain(el.ancestors().filter_map(ElementRef::wrap).take(2))
+        .find_map(|el| {
+            let class_lang = el.value().attr("class").and_then(|c| {
+                c.split_whitespace().find_map(|cls| {
+                    ["language-", "lang-", "highlight-source-"]
+                        .iter()
+                        .find_map(|prefix| cls.strip_prefix(prefix))
+                })
             });
+            class_lang
+                .or_else(|| el.value().attr("data-lang"))
+                .filter(|lang| !lang.is_empty())
+                .map(str::to_lowercase)
+        })
+}
+
+fn extract_tables(doc: &Html) -> Vec<Vec<Vec<String>>> {
+    let mut tables = Vec::new();
+    let Ok(table_sel) = Selector::parse("table") else {
+        return tables;
+    };
+    let Ok(row_sel) = Selector::parse("tr") else {
+        return tables;
+    };
 
-            blocks.push(CodeBlock {
-                lang,
-                source: truncate_section(&source, 5000),
-            });
+    for table in doc.select(&table_sel) {
+        // Rows of this table only, not of tables nested inside it
+        let rows: Vec<_> = table
+            .select(&row_sel)
+            .filter(|tr| {
+                tr.ancestors()
+                    .filter_map(ElementRef::wrap)
+                    .find(|el| el.value().name() == "table")
+                    .is_some_and(|t| t.id() == table.id())
+            })
+            .collect();
+
+        let mut grid: Vec<Vec<String>> = Vec::new();
+        // Cells spanning down from previous rows: (text, rows remaining) per column
+        let mut pending: Vec<Option<(String, usize)>> = Vec::new();
+
+        for tr in rows {
+            let mut row = Vec::new();
+            let mut col = 0;
+
+            for cell in tr
+                .children()
+                .filter_map(ElementRef::wrap)
+                .filter(|el| matches!(el.value().name(), "td" | "th"))
+            {
+                fill_spanned_cells(&mut row, &mut col, &mut pending);
+
+                let text = cell.text().collect::<Vec<_>>().join(" ");
+                let text = truncate(&text.split_whitespace().collect::<Vec<_>>().join(" "), 500);
+                let span = |attr: &str, max: usize| {
+                    cell.value()
+                        .attr(attr)
+                        .and_then(|v| v.trim().parse::<usize>().ok())
+                        .unwrap_or(1)
+                        .clamp(1, max)
+                };
+                let colspan = span("colspan", 50);
+                let rowspan = span("rowspan", 100);
+
+                for _ in 0..colspan {
+                    row.push(text.clone());
+                    if rowspan > 1 {
+                        if pending.len() <= col {
+                            pending.resize(col + 1, None);
+                        }
+                        pending[col] = Some((text.clone(), rowspan - 1));
+                    }
+                    col += 1;
+                }
+            }
+            fill_spanned_cells(&mut row, &mut col, &mut pending);
 
-            if blocks.len() >= 20 {
+            if row.iter().any(|c| !c.is_empty()) {
+                grid.push(row);
+            }
+            if grid.len() >= 200 {
                 break;
             }
         }
+
+        if !grid.is_empty() {
+            tables.push(grid);
+        }
+        if tables.len() >= 20 {
+            break;
+        }
     }
 
-    blocks
+    tables
+}
+
+/// Repeat cells spanning down from previous rows into the current row
+fn fill_spanned_cells(
+    row: &mut Vec<String>,
+    col: &mut usize,
+    pending: &mut [Option<(String, usize)>],
+) {
+    while let Some(Some((text, remaining))) = pending.get_mut(*col) {
+        row.push(text.clone());
+        *remaining -= 1;
+        if *remaining == 0 {
+            pending[*col] = None;
+        }
+        *col += 1;
+    }
 }
 
 fn select_text(doc: &Html, sel: &str) -> Option<String> {
@@ -614,27 +2466,6 @@ fn select_attr(doc: &Html, sel: &str, attr: &str) -> Option<String> {
         .filter(|s| !s.is_empty())
 }
 
-/// Find the largest valid char boundary <= pos
-fn floor_char_boundary(s: &str, pos: usize) -> usize {
-    if pos >= s.len() {
-        return s.len();
-    }
-    let mut i = pos;
-    while i > 0 && !s.is_char_boundary(i) {
-        i -= 1;
-    }
-    i
-}
-
-fn truncate(s: &str, max: usize) -> String {
-    if s.len() <= max {
-        s.to_string()
-    } else {
-        let end = floor_char_boundary(s, max - 3);
-        format!("{}...", &s[..end])
-    }
-}
-
 fn truncate_section(s: &str, max: usize) -> String {
     if s.len() <= max {
         s.to_string()
@@ -667,12 +2498,905 @@ mod tests {
             </body>
             </html>
         "#;
-        let page = parse_page(html, "https://test.com", false);
+        let page = parse_page(html, "https://test.com", &FetchOptions::default());
         assert_eq!(page.status, PageStatus::Ok);
         assert_eq!(page.title, Some("Test Page".to_string()));
         assert!(!page.sections.is_empty());
     }
 
+    #[test]
+    fn test_extract_sections_document_order() {
+        let html = r#"<html><body>
+            <h2>Menu</h2><p>Home About</p>
+            <h1>Guide</h1><p>Intro text.</p>
+            <h2>Install</h2><p>Run the installer.</p>
+            <h3>Linux</h3><p>Use the tarball.</p>
+            <h2>Usage</h2><p>Call it.</p>
+            <h2>Menu</h2><p>Home About</p>
+        </body></html>"#;
+        let sections = extract_sections(&Html::parse_document(html));
+        let outline: Vec<_> = sections
+            .iter()
+            .map(|s| (s.level, s.heading.as_str(), s.content.as_str()))
+            .collect();
+        assert_eq!(
+            outline,
+            [
+                (2, "Menu", "Home About"),
+                (1, "Guide", "Intro text."),
+                (2, "Install", "Run the installer."),
+                (3, "Linux", "Use the tarball."),
+                (2, "Usage", "Call it."),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_semantic_sections_nested_lists() {
+        let html = std::fs::read_to_string("test-data/nested-lists.html").unwrap();
+        let sections = semantic_sections(&Html::parse_document(&html));
+        let outline: Vec<_> = sections
+            .iter()
+            .map(|s| (s.level, s.heading.as_str(), s.content.as_str()))
+            .collect();
+        assert_eq!(
+            outline,
+            [
+                (1, "Content", "Everything you need to get started."),
+                (
+                    2,
+                    "Requirements",
+                    "- A Rust toolchain\n  - rustc 1.80 or newer\n  - cargo\n    - from rustup\n- Chrome or Chromium"
+                ),
+                (2, "Install", "Run the installer, then check the version."),
+            ]
+        );
+
+        // The default mode drops the list entirely
+        let options = FetchOptions {
+            text_mode: TextMode::Semantic,
+            ..FetchOptions::default()
+        };
+        let page = parse_page(&html, "https://example.com/install", &options);
+        assert!(page.sections[1].content.contains("  - rustc"));
+        let page = parse_page(
+            &html,
+            "https://example.com/install",
+            &FetchOptions::default(),
+        );
+        assert!(!page.sections.iter().any(|s| s.content.contains("rustc")));
+    }
+
+    #[test]
+    fn test_chunk_sections() {
+        let section = |heading: &str, content: &str| Section {
+            level: 2,
+            heading: heading.to_string(),
+            content: content.to_string(),
+        };
+        let long = "word ".repeat(50);
+        let sections = [
+            section("Intro", "Short opening paragraph.\n\nSecond one."),
+            section("Install", "First step here.\n\nSecond step here.\n\nThird."),
+            section("Long", long.trim()),
+        ];
+        // 16 tokens = 64 chars
+        let chunks = chunk_sections(&sections, "https://example.com/", 16);
+        let outline: Vec<_> = chunks
+            .iter()
+            .map(|c| (c.heading.as_str(), c.text.as_str()))
+            .collect();
+        assert_eq!(
+            outline[..3],
+            [
+                ("Intro", "Short opening paragraph.\n\nSecond one."),
+                ("Install", "First step here.\n\nSecond step here.\n\nThird."),
+                ("Long", long[..64].trim_end()),
+            ]
+        );
+        // Oversized paragraph split between words, all under the limit
+        assert!(chunks[2..]
+            .iter()
+            .all(|c| c.heading == "Long" && c.text.len() <= 64));
+        let words: usize = chunks[2..].iter().map(|c| c.text.split(' ').count()).sum();
+        assert_eq!(words, 50);
+
+        // Ids are stable and distinct
+        let again = chunk_sections(&sections, "https://example.com/", 16);
+        assert_eq!(chunks, again);
+        assert_eq!(chunks[0].id.len(), 12);
+        assert_ne!(chunks[0].id, chunks[1].id);
+
+        // Paragraphs that don't fit together start a new chunk
+        let first = "a".repeat(40);
+        let second = "b".repeat(40);
+        let content = format!("{}\n\n{}", first, second);
+        let chunks = chunk_sections(&[section("S", &content)], "https://example.com/", 16);
+        let texts: Vec<_> = chunks.iter().map(|c| c.text.as_str()).collect();
+        assert_eq!(texts, [first, second]);
+    }
+
+    #[test]
+    fn test_extract_tables() {
+        let html = r#"
+            <table>
+                <tr><th>Plan</th><th>Price</th><th>Seats</th></tr>
+                <tr><td>Basic</td><td>$10</td><td>1</td></tr>
+                <tr><td>Team</td><td>$50</td><td>10</td></tr>
+            </table>
+        "#;
+        let doc = Html::parse_document(html);
+        let tables = extract_tables(&doc);
+        assert_eq!(tables.len(), 1);
+        assert_eq!(
+            tables[0],
+            vec![
+                vec!["Plan", "Price", "Seats"],
+                vec!["Basic", "$10", "1"],
+                vec!["Team", "$50", "10"],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extract_tables_spans() {
+        let html = r#"
+            <table>
+                <tr><th colspan="2">Name</th><th>Score</th></tr>
+                <tr><td rowspan="2">A</td><td>x</td><td>1</td></tr>
+                <tr><td>y</td><td>2</td></tr>
+            </table>
+        "#;
+        let doc = Html::parse_document(html);
+        let tables = extract_tables(&doc);
+        assert_eq!(
+            tables[0],
+            vec![
+                vec!["Name", "Name", "Score"],
+                vec!["A", "x", "1"],
+                vec!["A", "y", "2"],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tables_flag() {
+        let html = "<html><body><main><p>Intro paragraph text.</p><table><tr><td>a</td><td>b</td></tr></table></main></body></html>";
+        let page = parse_page(html, "https://test.com", &FetchOptions::default());
+        assert!(page.tables.is_empty());
+
+        let options = FetchOptions {
+            tables: true,
+            ..FetchOptions::default()
+        };
+        let page = parse_page(html, "https://test.com", &options);
+        assert_eq!(page.tables, vec![vec![vec!["a", "b"]]]);
+    }
+
+    #[test]
+    fn test_extract_author_precedence() {
+        let meta_name = r#"<meta name="author" content="Ada Lovelace">"#;
+        let jsonld = r#"<script type="application/ld+json">
+            {"@graph": [{"@type": "Article", "author": [
+                {"@type": "Person", "name": "Grace Hopper"}, "Alan Turing"]}]}
+            </script>"#;
+        let rel = r#"<a rel="author" href="/u/kay">Alan  Kay</a>
+            <a rel="nofollow author" href="/u/kay">Alan Kay</a>"#;
+        let article = r#"<meta property="article:author" content="https://example.com/barbara">"#;
+        let author = |html: &str| extract_author(&Html::parse_document(html));
+
+        // Each source wins over the ones after it
+        let all = format!("{meta_name}{jsonld}{rel}{article}");
+        assert_eq!(author(&all).as_deref(), Some("Ada Lovelace"));
+        let rest = format!("{jsonld}{rel}{article}");
+        assert_eq!(author(&rest).as_deref(), Some("Grace Hopper, Alan Turing"));
+        let rest = format!("{rel}{article}");
+        assert_eq!(author(&rest).as_deref(), Some("Alan Kay"));
+        assert_eq!(
+            author(article).as_deref(),
+            Some("https://example.com/barbara")
+        );
+
+        // Multiple meta tags and a bare JSON-LD string
+        let html =
+            r#"<meta name="author" content="A. One"><meta name="author" content=" B. Two ">"#;
+        assert_eq!(author(html).as_deref(), Some("A. One, B. Two"));
+        let html = r#"<script type="application/ld+json">{"author": "Solo"}</script>"#;
+        assert_eq!(author(html).as_deref(), Some("Solo"));
+        assert_eq!(author(r#"<meta name="author" content=" ">"#), None);
+    }
+
+    #[test]
+    fn test_extract_canonical() {
+        let html = r#"<html><head>
+            <link rel="stylesheet" href="/style.css">
+            <link rel="canonical" href="../guide/?ref=nav">
+            </head><body><p>Body text.</p></body></html>"#;
+        let page = parse_page(
+            html,
+            "https://docs.example.com/v2/intro/page.html?utm_source=x",
+            &FetchOptions::default(),
+        );
+        assert_eq!(
+            page.canonical.as_deref(),
+            Some("https://docs.example.com/v2/guide/?ref=nav")
+        );
+
+        // Absolute hrefs and multi-valued rel attributes
+        let doc = Html::parse_document(
+            r#"<link rel="alternate canonical" href="https://example.org/a">"#,
+        );
+        assert_eq!(
+            extract_canonical(&doc, "https://example.com/b").as_deref(),
+            Some("https://example.org/a")
+        );
+        assert_eq!(
+            extract_canonical(&Html::parse_document("<p>x</p>"), "https://example.com/"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_extract_page_meta() {
+        let html = r#"<html><head>
+            <title>Launch</title>
+            <meta property="og:title" content="Launch">
+            <meta property="og:image" content="/img/card.png">
+            <meta property="og:type" content="article">
+            <meta property="og:site_name" content="Example News">
+            <meta property="article:published_time" content="2025-03-01T09:00:00Z">
+            <meta name="twitter:card" content="summary_large_image">
+            </head><body><main><p>Body text.</p></main></body></html>"#;
+
+        let page = parse_page(
+            html,
+            "https://news.example.com/a/1",
+            &FetchOptions::default(),
+        );
+        assert!(page.meta.is_none());
+
+        let options = FetchOptions {
+            meta: true,
+            ..FetchOptions::default()
+        };
+        let page = parse_page(html, "https://news.example.com/a/1", &options);
+        assert_eq!(
+            page.meta,
+            Some(PageMeta {
+                image: Some("https://news.example.com/img/card.png".to_string()),
+                og_type: Some("article".to_string()),
+                site_name: Some("Example News".to_string()),
+                published_time: Some("2025-03-01T09:00:00Z".to_string()),
+                twitter_card: Some("summary_large_image".to_string()),
+                favicon: Some("https://news.example.com/favicon.ico".to_string()),
+            })
+        );
+    }
+
+    #[test]
+    fn test_extract_favicon() {
+        let favicon = |head: &str, url: &str| {
+            let doc = Html::parse_document(&format!("<html><head>{}</head></html>", head));
+            extract_favicon(&doc, Url::parse(url).ok().as_ref())
+        };
+        let page = "https://docs.example.com/guide/intro.html";
+
+        // Relative href resolves against the page URL
+        assert_eq!(
+            favicon(
+                r#"<link rel="shortcut icon" href="../static/icon.png">"#,
+                page
+            )
+            .as_deref(),
+            Some("https://docs.example.com/static/icon.png")
+        );
+        // rel=icon wins over apple-touch-icon, which wins over /favicon.ico
+        let both = r#"<link rel="apple-touch-icon" href="/touch.png">
+            <link rel="icon" type="image/svg+xml" href="//cdn.example.net/i.svg">"#;
+        assert_eq!(
+            favicon(both, page).as_deref(),
+            Some("https://cdn.example.net/i.svg")
+        );
+        assert_eq!(
+            favicon(
+                r#"<link rel="apple-touch-icon-precomposed" href="touch.png">"#,
+                page
+            )
+            .as_deref(),
+            Some("https://docs.example.com/guide/touch.png")
+        );
+        assert_eq!(
+            favicon(r#"<link rel="stylesheet" href="a.css">"#, page).as_deref(),
+            Some("https://docs.example.com/favicon.ico")
+        );
+        assert_eq!(favicon("", "not a url"), None);
+    }
+
+    #[test]
+    fn test_detect_text_language() {
+        let english = "The committee published its annual report on Tuesday, \
+            describing how the new water treatment plant reduced costs for \
+            residents while improving the quality of drinking water across \
+            the region over the last two years.";
+        assert_eq!(detect_text_language(english), Some("en".to_string()));
+        assert_eq!(detect_text_language("Hello world"), None);
+    }
+
+    #[test]
+    fn test_extract_date_sources() {
+        let date = |html: &str| extract_date(&Html::parse_document(html));
+
+        assert_eq!(
+            date(
+                r#"<head><meta property="article:published_time" content="2024-03-01T09:00:00Z"></head>"#
+            ),
+            Some("2024-03-01T09:00:00Z".to_string())
+        );
+        assert_eq!(
+            date(r#"<body><p>Posted <time datetime="2023-11-20">Nov 20</time></p></body>"#),
+            Some("2023-11-20".to_string())
+        );
+        assert_eq!(
+            date(
+                r#"<head><script type="application/ld+json">
+                {"@context":"https://schema.org","@graph":[{"@type":"WebSite"},
+                 {"@type":"Article","datePublished":"2022-07-04T12:30:00+02:00"}]}
+                </script></head>"#
+            ),
+            Some("2022-07-04T12:30:00+02:00".to_string())
+        );
+        assert_eq!(
+            date("<body><p class='byline'>Published: January 5, 2021 by Staff</p></body>"),
+            Some("2021-01-05".to_string())
+        );
+        assert_eq!(date("<body><p>Founded in 1999.</p></body>"), None);
+    }
+
+    #[test]
+    fn test_extract_date_precedence() {
+        // Meta tag wins over <time> and JSON-LD
+        let html = r#"<head>
+            <meta name="date" content="2024-02-02">
+            <script type="application/ld+json">{"datePublished":"2020-01-01"}</script>
+            </head><body><time datetime="2021-01-01">x</time></body>"#;
+        assert_eq!(
+            extract_date(&Html::parse_document(html)),
+            Some("2024-02-02".to_string())
+        );
+    }
+
+    #[test]
+    fn test_unique_file_name() {
+        let mut used = HashSet::new();
+        assert_eq!(
+            unique_file_name("https://docs.rs/serde/latest/serde/?search=de#x", &mut used),
+            "docs.rs_serde_latest_serde_search_de_x.json"
+        );
+        assert_eq!(
+            unique_file_name("https://a.com/x", &mut used),
+            "a.com_x.json"
+        );
+        assert_eq!(
+            unique_file_name("http://a.com/x/", &mut used),
+            "a.com_x-2.json"
+        );
+        assert_eq!(unique_file_name("stdin", &mut used), "stdin.json");
+    }
+
+    #[test]
+    fn test_metrics_follow_page_sink() {
+        let dir = tempfile::tempdir().unwrap();
+        let metrics = FetchMetrics::from_durations(vec![10, 20], Duration::from_millis(30));
+
+        let path = dir.path().join("pages.jsonl");
+        let file = std::fs::File::create(&path).unwrap();
+        let mut sink = PageSink::File(path.clone(), std::io::BufWriter::new(file));
+        sink.write(&error_page("https://a.com/", "x")).unwrap();
+        sink.write_metrics(&metrics).unwrap();
+        sink.finish().unwrap();
+        let lines: Vec<serde_json::Value> = std::fs::read_to_string(&path)
+            .unwrap()
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[1]["metrics"]["pages"], 2);
+
+        let mut sink = PageSink::Dir(dir.path().to_path_buf(), HashSet::new());
+        sink.write_metrics(&metrics).unwrap();
+        let json = std::fs::read_to_string(dir.path().join("metrics.json")).unwrap();
+        assert!(json.contains("\"median_ms\""));
+    }
+
+    #[test]
+    fn test_fetch_metrics() {
+        let durations = vec![
+            900, 100, 300, 200, 1000, 400, 600, 500, 800, 700, 1100, 1200, 1300, 1400, 1500, 1600,
+            1700, 1800, 1900, 5000,
+        ];
+        let metrics = FetchMetrics::from_durations(durations, Duration::from_millis(6000));
+        assert_eq!(
+            metrics,
+            FetchMetrics {
+                pages: 20,
+                min_ms: 100,
+                median_ms: 1000,
+                p95_ms: 1900,
+                max_ms: 5000,
+                wall_ms: 6000,
+            }
+        );
+
+        let single = FetchMetrics::from_durations(vec![42], Duration::from_millis(50));
+        assert_eq!((single.median_ms, single.p95_ms), (42, 42));
+
+        let empty = FetchMetrics::from_durations(vec![], Duration::ZERO);
+        assert_eq!((empty.pages, empty.p95_ms), (0, 0));
+    }
+
+    #[tokio::test]
+    async fn test_spawn_until_deadline() {
+        let urls: Vec<String> = ["fast", "slow", "fast2"].map(String::from).to_vec();
+        let deadline = Instant::now() + Duration::from_millis(200);
+        let task = |url: String| async move {
+            let delay = if url == "slow" { 5000 } else { 10 };
+            tokio::time::sleep(Duration::from_millis(delay)).await;
+            Page {
+                status: PageStatus::Ok,
+                alerts: vec![],
+                ..error_page(&url, "")
+            }
+        };
+
+        let start = std::time::Instant::now();
+        let mut pages = Vec::new();
+        spawn_until(urls, Some(deadline), task, |idx, page| {
+            pages.push((idx, page))
+        })
+        .await;
+
+        // Cut off at the deadline, not after the slow task
+        assert!(start.elapsed() < Duration::from_millis(1000));
+        pages.sort_by_key(|(idx, _)| *idx);
+        let statuses: Vec<_> = pages.iter().map(|(_, p)| p.status).collect();
+        assert_eq!(
+            statuses,
+            [PageStatus::Ok, PageStatus::Skipped, PageStatus::Ok]
+        );
+        assert_eq!(pages[1].1.url, "slow");
+        assert_eq!(pages[1].1.alerts, [DEADLINE_EXCEEDED]);
+    }
+
+    #[tokio::test]
+    async fn test_host_limiter_caps_each_host() {
+        use std::sync::Mutex;
+
+        let limiter = Arc::new(HostLimiter::new(2));
+        // Per host: (open now, most open at once)
+        let counts: Arc<Mutex<HashMap<String, (usize, usize)>>> = Arc::default();
+        let urls: Vec<String> = (0..12)
+            .map(|i| {
+                let host = if i % 3 == 0 { "b.example" } else { "a.example" };
+                format!("https://{}/page{}", host, i)
+            })
+            .collect();
+
+        let task = {
+            let counts = Arc::clone(&counts);
+            move |url: String| {
+                let limiter = Arc::clone(&limiter);
+                let counts = Arc::clone(&counts);
+                async move {
+                    let _permit = limiter.acquire(&url).await;
+                    let host = Url::parse(&url).unwrap().host_str().unwrap().to_string();
+                    {
+                        let mut counts = counts.lock().unwrap();
+                        let (open, peak) = counts.entry(host.clone()).or_default();
+                        *open += 1;
+                        *peak = (*peak).max(*open);
+                    }
+                    tokio::time::sleep(Duration::from_millis(20)).await;
+                    counts.lock().unwrap().get_mut(&host).unwrap().0 -= 1;
+                    error_page(&url, "")
+                }
+            }
+        };
+        let mut done = 0;
+        spawn_until(urls, None, task, |_, _| done += 1).await;
+
+        assert_eq!(done, 12);
+        let counts = counts.lock().unwrap();
+        assert_eq!(counts["a.example"], (0, 2));
+        assert_eq!(counts["b.example"], (0, 2));
+    }
+
+    #[tokio::test]
+    async fn test_spawn_until_past_deadline() {
+        let urls = vec!["a".to_string()];
+        let mut pages = Vec::new();
+        spawn_until(
+            urls,
+            Some(Instant::now()),
+            |url: String| async move { error_page(&url, "ran") },
+            |_, page| pages.push(page),
+        )
+        .await;
+        assert_eq!(pages[0].status, PageStatus::Skipped);
+        assert_eq!(pages[0].alerts, [DEADLINE_EXCEEDED]);
+    }
+
+    #[test]
+    fn test_jobs_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("jobs.json");
+        std::fs::write(
+            &path,
+            r##"[
+                {"url": "https://a.example/", "max_chars": 500, "wait_for": "#content"},
+                {"url": "https://b.example/", "max_chars": 4000, "tables": true}
+            ]"##,
+        )
+        .unwrap();
+        let jobs = load_jobs(&path).unwrap();
+        assert_eq!(jobs.len(), 2);
+
+        let defaults = FetchOptions {
+            timeout: 5000,
+            max_chars: Some(1000),
+            ..FetchOptions::default()
+        };
+        let options = job_options(&jobs, &defaults);
+        let a = &options["https://a.example/"];
+        let b = &options["https://b.example/"];
+        assert_eq!(a.max_chars, Some(500));
+        assert_eq!(b.max_chars, Some(4000));
+        assert_eq!(a.wait_for.as_deref(), Some("#content"));
+        assert_eq!(b.wait_for, None);
+        assert!(!a.tables && b.tables);
+        // Unset options keep the command line's
+        assert_eq!((a.timeout, b.timeout), (5000, 5000));
+
+        // Unknown keys are an error, not silently ignored
+        std::fs::write(
+            &path,
+            r#"[{"url": "https://a.example/", "format": "yaml"}]"#,
+        )
+        .unwrap();
+        let err = format!("{:#}", load_jobs(&path).unwrap_err());
+        assert!(err.contains("unknown field `format`"), "{}", err);
+    }
+
+    #[test]
+    fn test_limit_chars() {
+        let html = "<html><body><article><h1>One</h1><p>First section text here.</p>\
+            <h2>Two</h2><p>Second section has a few more words in it.</p>\
+            <h2>Three</h2><p>Third.</p></article></body></html>";
+        let mut page = parse_page(html, "https://example.com", &FetchOptions::default());
+        let full = page.chars;
+        limit_chars(&mut page, full, None);
+        assert!(page.alerts.is_empty());
+
+        limit_chars(&mut page, 40, None);
+        assert!(page.chars <= 40, "{}", page.chars);
+        assert_eq!(page.sections.len(), 2);
+        assert!(page.sections[1].content.ends_with("..."));
+        assert_eq!(page.alerts, ["Truncated to 40 chars"]);
+
+        let mut page = raw_page(
+            "https://example.com/a.txt",
+            "text/plain".to_string(),
+            "alpha beta gamma delta epsilon".to_string(),
+        );
+        limit_chars(&mut page, 20, None);
+        assert_eq!(page.text.as_deref(), Some("alpha beta gamma..."));
+        assert_eq!(page.words, 3);
+
+        // Characters, not bytes, and chunks rebuilt from the cut text
+        let html = "<html><body><article><h1>Été</h1>\
+            <p>Ça été très réussi, déjà vu à Noël et à Pâques.</p>\
+            <p>Deuxième paragraphe entièrement coupé.</p></article></body></html>";
+        let options = FetchOptions {
+            chunks: Some(16),
+            ..FetchOptions::default()
+        };
+        let mut page = parse_page(html, "https://example.com", &options);
+        assert_eq!(page.chars, char_count(&page.sections));
+        limit_chars(&mut page, 40, options.chunks);
+        assert!(page.chars <= 40, "{}", page.chars);
+        assert!(page.sections[0].content.len() > 40 - 3);
+        let chunked: String = page.chunks.iter().map(|c| c.text.as_str()).collect();
+        assert!(!chunked.contains("Deuxième"));
+        assert_eq!(chunked, page.sections[0].content);
+    }
+
+    #[test]
+    fn test_word_count_and_reading_minutes() {
+        let html = "<html><body><article><h1>Intro</h1>\
+            <p>The quick brown fox jumps over the lazy dog near the river bank.</p>\
+            </article></body></html>";
+        let page = parse_page(html, "https://example.com", &FetchOptions::default());
+        assert_eq!(page.words, 14);
+        assert_eq!(page.reading_minutes, 1);
+
+        assert_eq!(reading_minutes(0), 0);
+        assert_eq!(reading_minutes(230), 1);
+        assert_eq!(reading_minutes(231), 2);
+        assert_eq!(reading_minutes(1000), 5);
+    }
+
+    #[test]
+    fn test_extract_jsonld() {
+        let html = r#"<html><head>
+            <script type="application/ld+json">
+            {"@context":"https://schema.org","@type":"Article","headline":"Launch day",
+             "author":{"@type":"Person","name":"Ada"},"datePublished":"2024-05-01"}
+            </script>
+            <script type="application/ld+json">{"@graph":[{"@type":"Organization","name":"Acme"},{"@type":"WebSite"}]}</script>
+            <script type="application/ld+json">{ not json </script>
+            </head><body><main><p>Text.</p></main></body></html>"#;
+
+        let page = parse_page(html, "https://acme.test/a", &FetchOptions::default());
+        assert!(page.jsonld.is_empty());
+
+        let options = FetchOptions {
+            jsonld: true,
+            ..FetchOptions::default()
+        };
+        let page = parse_page(html, "https://acme.test/a", &options);
+        assert_eq!(page.jsonld.len(), 3);
+        assert_eq!(page.jsonld[0]["@type"], "Article");
+        assert_eq!(page.jsonld[0]["headline"], "Launch day");
+        assert_eq!(page.jsonld[0]["author"]["name"], "Ada");
+        assert_eq!(page.jsonld[1]["name"], "Acme");
+        assert_eq!(page.jsonld[2]["@type"], "WebSite");
+    }
+
+    #[test]
+    fn test_normalize_date() {
+        assert_eq!(
+            normalize_date("2024-03-01T09:00:00Z").as_deref(),
+            Some("2024-03-01T09:00:00Z")
+        );
+        assert_eq!(
+            normalize_date("Tue, 1 Jul 2003 10:52:37 +0200").as_deref(),
+            Some("2003-07-01T10:52:37+02:00")
+        );
+        assert_eq!(normalize_date("2024/03/01").as_deref(), Some("2024-03-01"));
+        assert_eq!(
+            normalize_date("Jan. 5, 2024").as_deref(),
+            Some("2024-01-05")
+        );
+        assert_eq!(
+            normalize_date("5 March 2024").as_deref(),
+            Some("2024-03-05")
+        );
+        assert_eq!(normalize_date("last Tuesday"), None);
+    }
+
+    #[test]
+    fn test_extract_lang() {
+        let doc = Html::parse_document(r#"<html lang="en-US"><body></body></html>"#);
+        assert_eq!(extract_lang(&doc), Some("en".to_string()));
+        let doc = Html::parse_document("<html><body></body></html>");
+        assert_eq!(extract_lang(&doc), None);
+    }
+
+    #[test]
+    fn test_is_pdf_url() {
+        assert!(is_pdf_url("https://arxiv.org/pdf/2401.00001.pdf"));
+        assert!(is_pdf_url("https://example.com/Report.PDF?download=1"));
+        assert!(!is_pdf_url("https://example.com/pdf/viewer"));
+        assert!(!is_pdf_url("not a url.pdf"));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_json_api() {
+        use wiremock::matchers::path;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        let body = r#"{"items":[{"id":1,"name":"<b>not html</b>"}],"total":1}"#;
+        Mock::given(path("/api"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(body, "application/json"))
+            .mount(&server)
+            .await;
+
+        // Needs Chrome; skip on machines without one
+        let Ok(pool) = BrowserPool::new(1).await else {
+            return;
+        };
+        let url = format!("{}/api", server.uri());
+        let page = fetch_one(&pool, &url, &FetchOptions::default()).await;
+        pool.close().await.unwrap();
+
+        assert_eq!(page.status, PageStatus::Ok);
+        assert_eq!(page.content_type.as_deref(), Some("application/json"));
+        assert_eq!(page.text.as_deref(), Some(body));
+        assert!(page.sections.is_empty());
+    }
+
+    #[test]
+    fn test_raw_page() {
+        let page = raw_page(
+            "https://example.com/robots.txt",
+            "text/plain".to_string(),
+            "User-agent: *\nDisallow: /private".to_string(),
+        );
+        assert_eq!(page.status, PageStatus::Ok);
+        assert!(page.alerts.is_empty());
+        assert_eq!(page.words, 4);
+        let json = serde_json::to_string(&page).unwrap();
+        assert!(json
+            .contains(r#""content_type":"text/plain","text":"User-agent: *\nDisallow: /private""#));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_pdf_local_server() {
+        use wiremock::matchers::path;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        let pdf = std::fs::read("test-data/sample.pdf").unwrap();
+        Mock::given(path("/paper"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(pdf, "application/pdf"))
+            .mount(&server)
+            .await;
+        Mock::given(path("/fake.pdf"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw("<html></html>", "text/html"))
+            .mount(&server)
+            .await;
+
+        let client = PdfClient::default();
+        let url = format!("{}/paper", server.uri());
+        let page = fetch_pdf(&client, &url, 5000).await.unwrap();
+        assert_eq!(page.status, PageStatus::Ok);
+        assert_eq!(page.url, url);
+        assert_eq!(page.title.as_deref(), Some("Sample Research Paper"));
+        assert!(page
+            .sections
+            .iter()
+            .any(|s| s.content.contains("three pages")));
+
+        // Not actually a PDF: fall back to the browser
+        let url = format!("{}/fake.pdf", server.uri());
+        assert!(fetch_pdf(&client, &url, 5000).await.is_none());
+
+        let url = format!("{}/missing.pdf", server.uri());
+        let page = fetch_pdf(&client, &url, 5000).await.unwrap();
+        assert_eq!(page.status, PageStatus::Dead);
+    }
+
+    #[tokio::test]
+    async fn test_download_pdf_cookies_and_size() {
+        use wiremock::matchers::{header, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        let pdf = std::fs::read("test-data/sample.pdf").unwrap();
+        Mock::given(path("/members/paper.pdf"))
+            .and(header("cookie", "sid=42"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(pdf.clone(), "application/pdf"))
+            .mount(&server)
+            .await;
+        Mock::given(path("/members/paper.pdf"))
+            .respond_with(ResponseTemplate::new(403))
+            .mount(&server)
+            .await;
+
+        // The --cookies file applies to downloads outside Chrome too
+        let cookies =
+            crate::cookies::parse_cookies("127.0.0.1\tFALSE\t/\tFALSE\t0\tsid\t42\n").unwrap();
+        let client = PdfClient::new("ref-test", cookies, None, true).unwrap();
+        let url = format!("{}/members/paper.pdf", server.uri());
+        let bytes = download_pdf(&client, &url, 5000).await.unwrap().unwrap();
+        assert_eq!(bytes, pdf);
+
+        let err = download_pdf(&PdfClient::default(), &url, 5000)
+            .await
+            .unwrap_err();
+        assert_eq!(err.to_string(), "HTTP 403 Forbidden");
+
+        // Bodies over the cap are refused instead of read into memory
+        let client = PdfClient {
+            max_bytes: 100,
+            ..client
+        };
+        let err = download_pdf(&client, &url, 5000).await.unwrap_err();
+        assert!(err.to_string().contains("larger than 100 bytes"), "{}", err);
+    }
+
+    #[test]
+    fn test_resolve_href() {
+        let base = Url::parse("https://example.com/docs/guide/intro.html").unwrap();
+        let resolve = |href| resolve_href(Some(&base), href);
+        assert_eq!(
+            resolve("setup.html"),
+            Some("https://example.com/docs/guide/setup.html".to_string())
+        );
+        assert_eq!(
+            resolve("../api/"),
+            Some("https://example.com/docs/api/".to_string())
+        );
+        assert_eq!(
+            resolve("/about"),
+            Some("https://example.com/about".to_string())
+        );
+        assert_eq!(
+            resolve("//cdn.example.org/file"),
+            Some("https://cdn.example.org/file".to_string())
+        );
+        assert_eq!(
+            resolve("https://other.com/x"),
+            Some("https://other.com/x".to_string())
+        );
+        assert_eq!(resolve(""), None);
+        assert_eq!(resolve("#top"), None);
+        assert_eq!(resolve("javascript:void(0)"), None);
+    }
+
+    #[test]
+    fn test_extract_link_graph() {
+        let html = r#"<html><body>
+            <nav><a href="/">Home</a><a href="/docs"><img alt=""></a></nav>
+            <main><p>See <a href="page2">next</a> and <a href="javascript:go()">x</a>.</p>
+            <a href="/docs" aria-label="Docs">dup</a></main>
+        </body></html>"#;
+        let doc = Html::parse_document(html);
+        let links = extract_link_graph(&doc, "https://example.com/a/b");
+        let urls: Vec<_> = links.iter().map(|l| l.url.as_str()).collect();
+        assert_eq!(
+            urls,
+            vec![
+                "https://example.com/",
+                "https://example.com/docs",
+                "https://example.com/a/page2"
+            ]
+        );
+        assert_eq!(links[0].text, "Home");
+        assert_eq!(links[2].text, "next");
+    }
+
+    #[test]
+    fn test_next_crawl_urls() {
+        let mut page = error_page("https://docs.example.com/", "");
+        page.status = PageStatus::Ok;
+        page.links = [
+            "https://docs.example.com/guide",
+            "https://docs.example.com/guide#install",
+            "https://docs.example.com/",
+            "https://other.com/page",
+            "mailto:team@example.com",
+            "https://docs.example.com/api",
+        ]
+        .iter()
+        .map(|u| Link {
+            text: "link".to_string(),
+            url: u.to_string(),
+        })
+        .collect();
+
+        let mut visited = HashSet::new();
+        visited.insert(crawl_key("https://docs.example.com/"));
+
+        let next = next_crawl_urls(std::slice::from_ref(&page), &mut visited, 10);
+        assert_eq!(
+            next,
+            vec![
+                "https://docs.example.com/guide",
+                "https://docs.example.com/api"
+            ]
+        );
+
+        // Second pass over the same page finds nothing new (no cycles)
+        let next = next_crawl_urls(std::slice::from_ref(&page), &mut visited, 10);
+        assert!(next.is_empty());
+
+        // Limit bounds the frontier
+        let mut visited = HashSet::new();
+        let next = next_crawl_urls(std::slice::from_ref(&page), &mut visited, 1);
+        assert_eq!(next.len(), 1);
+    }
+
     #[test]
     fn test_detect_paywall() {
         assert_eq!(
@@ -690,26 +3414,6 @@ mod tests {
         );
     }
 
-    #[test]
-    fn test_truncate() {
-        assert_eq!(truncate("short", 10), "short");
-        assert_eq!(truncate("this is a long string", 10), "this is...");
-        // Multi-byte chars: ─ is 3 bytes, should not panic
-        let dashes = "─".repeat(100);
-        let result = truncate(&dashes, 20);
-        assert!(result.ends_with("..."));
-        assert!(result.len() <= 23); // max 20 + safety margin for ...
-    }
-
-    #[test]
-    fn test_floor_char_boundary() {
-        let s = "hello─world"; // ─ is at bytes 5..8
-        assert_eq!(floor_char_boundary(s, 5), 5); // exactly at boundary
-        assert_eq!(floor_char_boundary(s, 6), 5); // inside ─, goes back
-        assert_eq!(floor_char_boundary(s, 7), 5); // inside ─, goes back
-        assert_eq!(floor_char_boundary(s, 8), 8); // after ─
-    }
-
     #[test]
     fn test_extract_code() {
         let html =
@@ -719,4 +3423,21 @@ mod tests {
         assert_eq!(blocks.len(), 1);
         assert_eq!(blocks[0].lang, Some("rust".to_string()));
     }
+
+    #[test]
+    fn test_extract_fenced_code_blocks() {
+        // Markdown renderers: class on <code>; GitHub: class on the wrapper <div>
+        let html = r#"<article>
+            <pre><code class="language-python">def greet(name):
+    return f"hi {name}"</code></pre>
+            <p>Then run it:</p>
+            <div class="highlight highlight-source-shell"><pre>pip install greeter &amp;&amp; greet</pre></div>
+            <pre><code>const answer = 42; console.log(answer);</code></pre>
+        </article>"#;
+        let blocks = extract_code_blocks(&Html::parse_document(html));
+        let langs: Vec<_> = blocks.iter().map(|b| b.lang.as_deref()).collect();
+        assert_eq!(langs, [Some("python"), Some("javascript"), Some("shell")]);
+        assert!(blocks[0].source.starts_with("def greet(name):\n"));
+        assert_eq!(blocks[2].source, "pip install greeter && greet");
+    }
 }
@@ -0,0 +1,105 @@
+//! URL include/exclude filters (`--include` / `--exclude`)
+
+use anyhow::{Context, Result};
+use regex::Regex;
+
+/// Regex allow/deny list for URLs; excludes win over includes
+#[derive(Debug, Default)]
+pub struct UrlFilter {
+    include: Vec<Regex>,
+    exclude: Vec<Regex>,
+}
+
+impl UrlFilter {
+    /// Compile the patterns (no includes = everything not excluded)
+    pub fn new(include: &[String], exclude: &[String]) -> Result<Self> {
+        Ok(Self {
+            include: compile(include, "--include")?,
+            exclude: compile(exclude, "--exclude")?,
+        })
+    }
+
+    /// Whether the URL should be checked
+    pub fn allows(&self, url: &str) -> bool {
+        if self.exclude.iter().any(|re| re.is_match(url)) {
+            return false;
+        }
+        self.include.is_empty() || self.include.iter().any(|re| re.is_match(url))
+    }
+
+    /// Split URLs into (allowed, skipped), keeping order
+    pub fn partition(&self, urls: Vec<String>) -> (Vec<String>, Vec<String>) {
+        urls.into_iter().partition(|url| self.allows(url))
+    }
+}
+
+fn compile(patterns: &[String], flag: &str) -> Result<Vec<Regex>> {
+    patterns
+        .iter()
+        .map(|p| Regex::new(p).with_context(|| format!("Invalid {} pattern: {}", flag, p)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn urls() -> Vec<String> {
+        [
+            "https://docs.rs/serde",
+            "http://localhost:8080/health",
+            "https://example.com/page",
+            "https://github.com/royalbit/ref",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect()
+    }
+
+    fn patterns(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_include_only() {
+        let filter = UrlFilter::new(&patterns(&[r"github\.com", r"docs\.rs"]), &[]).unwrap();
+        let (allowed, skipped) = filter.partition(urls());
+        assert_eq!(
+            allowed,
+            vec!["https://docs.rs/serde", "https://github.com/royalbit/ref"]
+        );
+        assert_eq!(
+            skipped,
+            vec!["http://localhost:8080/health", "https://example.com/page"]
+        );
+    }
+
+    #[test]
+    fn test_exclude_only() {
+        let filter =
+            UrlFilter::new(&[], &patterns(&["localhost", r"^https?://example\.com"])).unwrap();
+        let (allowed, skipped) = filter.partition(urls());
+        assert_eq!(
+            allowed,
+            vec!["https://docs.rs/serde", "https://github.com/royalbit/ref"]
+        );
+        assert_eq!(
+            skipped,
+            vec!["http://localhost:8080/health", "https://example.com/page"]
+        );
+    }
+
+    #[test]
+    fn test_exclude_wins_over_include() {
+        let filter = UrlFilter::new(&patterns(&["^https://"]), &patterns(&["example"])).unwrap();
+        assert!(filter.allows("https://docs.rs/serde"));
+        assert!(!filter.allows("https://example.com/page"));
+        assert!(!filter.allows("http://localhost:8080/health"));
+    }
+
+    #[test]
+    fn test_invalid_pattern() {
+        let err = UrlFilter::new(&patterns(&["("]), &[]).unwrap_err();
+        assert!(err.to_string().contains("--include"));
+    }
+}
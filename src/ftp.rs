@@ -0,0 +1,206 @@
+//! Minimal FTP probe for `check-links --schemes ftp`
+//!
+//! FTP has no HEAD, so the probe logs in (anonymously unless the URL has
+//! credentials) and asks for the file's SIZE, or CWDs into a directory URL
+//! (trailing `/`). ftps:// isn't probed: it needs TLS.
+
+use anyhow::{bail, Context, Result};
+use percent_encoding::percent_decode_str;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+use url::{Host, Url};
+
+/// Whether the file or directory exists: `Ok(false)` when the server answers
+/// 550, `Err` when it can't be reached or refuses the login
+pub async fn exists(url: &str, timeout: Duration) -> Result<bool> {
+    tokio::time::timeout(timeout, probe(url))
+        .await
+        .unwrap_or_else(|_| bail!("timed out"))
+}
+
+async fn probe(url: &str) -> Result<bool> {
+    let url = Url::parse(url).context("invalid FTP URL")?;
+    let host = url.host_str().context("FTP URL has no host")?;
+    if url.scheme() != "ftp" {
+        bail!("{}:// is not supported", url.scheme());
+    }
+    let user = match decode(url.username())? {
+        user if user.is_empty() => "anonymous".to_string(),
+        user => user,
+    };
+    let pass = match url.password() {
+        Some(pass) => decode(pass)?,
+        None => "anonymous@".to_string(),
+    };
+    let path = decode(url.path())?;
+
+    let port = url.port().unwrap_or(21);
+    // host_str() keeps the brackets of an IPv6 literal, which won't resolve
+    let stream = match url.host().context("FTP URL has no host")? {
+        Host::Domain(domain) => TcpStream::connect((domain, port)).await,
+        Host::Ipv4(ip) => TcpStream::connect((ip, port)).await,
+        Host::Ipv6(ip) => TcpStream::connect((ip, port)).await,
+    }
+    .with_context(|| format!("connect to {}:{}", host, port))?;
+
+    let mut ftp = Session {
+        stream: BufReader::new(stream),
+    };
+    expect(ftp.reply().await?, &[220], "greeting")?;
+
+    let reply = ftp.command(&format!("USER {}", user)).await?;
+    if reply.0 == 331 {
+        let reply = ftp.command(&format!("PASS {}", pass)).await?;
+        expect(reply, &[230, 202], "login")?;
+    } else {
+        expect(reply, &[230], "login")?;
+    }
+
+    let (code, text) = if path.is_empty() || path.ends_with('/') {
+        ftp.command(&format!("CWD {}", path)).await?
+    } else {
+        ftp.command("TYPE I").await?;
+        ftp.command(&format!("SIZE {}", path)).await?
+    };
+    let _ = ftp.command("QUIT").await;
+
+    match code {
+        200..=299 => Ok(true),
+        550 => Ok(false),
+        _ => bail!("{} {}", code, text),
+    }
+}
+
+/// URL components are percent-encoded; the server wants the raw bytes
+fn decode(part: &str) -> Result<String> {
+    let decoded = percent_decode_str(part)
+        .decode_utf8()
+        .context("FTP URL is not valid UTF-8")?;
+    if decoded.contains(['\r', '\n']) {
+        bail!("FTP URL contains a line break");
+    }
+    Ok(decoded.into_owned())
+}
+
+fn expect(reply: (u16, String), codes: &[u16], step: &str) -> Result<()> {
+    if !codes.contains(&reply.0) {
+        bail!("{} failed: {} {}", step, reply.0, reply.1);
+    }
+    Ok(())
+}
+
+struct Session {
+    stream: BufReader<TcpStream>,
+}
+
+impl Session {
+    async fn command(&mut self, line: &str) -> Result<(u16, String)> {
+        self.stream
+            .get_mut()
+            .write_all(format!("{}\r\n", line).as_bytes())
+            .await?;
+        self.reply().await
+    }
+
+    /// Code and text of the next reply; multi-line replies (`220-...`) run
+    /// until the line starting with the code and a space
+    async fn reply(&mut self) -> Result<(u16, String)> {
+        let mut first: Option<u16> = None;
+        loop {
+            let mut line = String::new();
+            if self.stream.read_line(&mut line).await? == 0 {
+                bail!("connection closed");
+            }
+            let line = line.trim_end();
+            let code = line.get(..3).and_then(|c| c.parse::<u16>().ok());
+            let first = *first.get_or_insert(code.context("malformed FTP reply")?);
+            if code == Some(first) && line.as_bytes().get(3) != Some(&b'-') {
+                return Ok((first, line.get(4..).unwrap_or("").to_string()));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::AsyncWriteExt;
+    use tokio::net::TcpListener;
+
+    /// One-connection FTP server knowing the files `/pub/file.tgz` and
+    /// `/pub/My File.pdf` and directory `/pub/`
+    async fn server() -> u16 {
+        serve(TcpListener::bind("127.0.0.1:0").await.unwrap())
+    }
+
+    fn serve(listener: TcpListener) -> u16 {
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let (read, mut write) = stream.into_split();
+            let mut lines = BufReader::new(read).lines();
+            write
+                .write_all(b"220-Welcome\r\n220 Ready\r\n")
+                .await
+                .unwrap();
+            while let Ok(Some(line)) = lines.next_line().await {
+                let reply: &[u8] = match line.as_str() {
+                    "USER anonymous" => b"331 Send password\r\n",
+                    l if l.starts_with("PASS ") => b"230 Logged in\r\n",
+                    "TYPE I" => b"200 Binary\r\n",
+                    "SIZE /pub/file.tgz" | "SIZE /pub/My File.pdf" => b"213 1024\r\n",
+                    "CWD /pub/" => b"250 OK\r\n",
+                    "QUIT" => b"221 Bye\r\n",
+                    _ => b"550 No such file\r\n",
+                };
+                write.write_all(reply).await.unwrap();
+            }
+        });
+        port
+    }
+
+    #[tokio::test]
+    async fn test_exists() {
+        let timeout = Duration::from_secs(5);
+        let port = server().await;
+        let url = format!("ftp://127.0.0.1:{}/pub/file.tgz", port);
+        assert!(exists(&url, timeout).await.unwrap());
+
+        let port = server().await;
+        let url = format!("ftp://127.0.0.1:{}/pub/", port);
+        assert!(exists(&url, timeout).await.unwrap());
+
+        let port = server().await;
+        let url = format!("ftp://127.0.0.1:{}/pub/My%20File.pdf", port);
+        assert!(exists(&url, timeout).await.unwrap());
+
+        let port = server().await;
+        let url = format!("ftp://127.0.0.1:{}/pub/gone.tgz", port);
+        assert!(!exists(&url, timeout).await.unwrap());
+
+        // Nothing listening
+        let port = TcpListener::bind("127.0.0.1:0")
+            .await
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let url = format!("ftp://127.0.0.1:{}/x", port);
+        assert!(exists(&url, timeout).await.is_err());
+
+        let url = format!("ftp://127.0.0.1:{}/a%0D%0ADELE%20b", port);
+        assert!(exists(&url, timeout).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_exists_ipv6() {
+        // Skipped where there is no IPv6 loopback
+        let Ok(listener) = TcpListener::bind("[::1]:0").await else {
+            return;
+        };
+        let port = serve(listener);
+        let url = format!("ftp://[::1]:{}/pub/file.tgz", port);
+        assert!(exists(&url, Duration::from_secs(5)).await.unwrap());
+    }
+}
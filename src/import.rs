@@ -0,0 +1,414 @@
+//! import command: Seed references.yaml from a BibTeX bibliography
+//!
+//! Entries with a `url` or `doi` field become `research` references; DOIs
+//! without a URL resolve through https://doi.org/.
+
+use crate::normalize::{normalize_url, NormalizeOptions};
+use crate::scan::merge_references;
+use crate::schema::{Meta, Reference, ReferencesFile, Status};
+use anyhow::{Context, Result};
+use chrono::Utc;
+use clap::Args;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+#[derive(Args)]
+pub struct ImportArgs {
+    /// BibTeX file (.bib) to import
+    pub file: PathBuf,
+
+    /// Output file (default: references.yaml), merged if it exists
+    #[arg(short, long, default_value = "references.yaml")]
+    pub output: PathBuf,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImportOutput {
+    pub file: String,
+    pub entries: usize,
+    /// Entries with neither url nor doi
+    pub skipped: usize,
+    pub total_urls: usize,
+    pub new_urls: usize,
+    pub updated_urls: usize,
+}
+
+/// A parsed `@type{key, field = value, ...}` entry; field names are lowercase
+#[derive(Debug, Clone, PartialEq)]
+pub struct BibEntry {
+    pub entry_type: String,
+    pub key: String,
+    pub fields: HashMap<String, String>,
+}
+
+pub async fn run_import(args: ImportArgs) -> Result<()> {
+    let content = tokio::fs::read_to_string(&args.file)
+        .await
+        .with_context(|| format!("Failed to read {}", args.file.display()))?;
+    let entries = parse_bibtex(&content);
+
+    let normalize = NormalizeOptions::default();
+    let source = args.file.display().to_string();
+    let now = Utc::now().to_rfc3339();
+    let mut url_map: HashMap<String, Reference> = HashMap::new();
+    let mut skipped = 0;
+    for entry in &entries {
+        match entry_reference(entry, &source, &now) {
+            Some(reference) => {
+                url_map
+                    .entry(normalize_url(&reference.url, &normalize))
+                    .or_insert(reference);
+            }
+            None => skipped += 1,
+        }
+    }
+
+    let mut refs_file = if args.output.exists() {
+        ReferencesFile::load(&args.output).await?
+    } else {
+        ReferencesFile {
+            meta: Meta {
+                project: None,
+                created: Utc::now().format("%Y-%m-%d").to_string(),
+                last_verified: None,
+                tool: "ref".to_string(),
+                total_links: 0,
+            },
+            references: Vec::new(),
+        }
+    };
+
+    let (new_count, updated_count) = merge_references(&mut refs_file, url_map, &normalize);
+
+    refs_file.save(&args.output).await?;
+
+    let output = ImportOutput {
+        file: args.output.display().to_string(),
+        entries: entries.len(),
+        skipped,
+        total_urls: refs_file.references.len(),
+        new_urls: new_count,
+        updated_urls: updated_count,
+    };
+    println!("{}", serde_json::to_string(&output)?);
+
+    Ok(())
+}
+
+/// URLs a bibliography contributes, as `import` records them (prune re-scans
+/// .bib files with this, since DOI-only entries have no URL in the text)
+pub fn bibtex_urls(input: &str) -> Vec<String> {
+    parse_bibtex(input).iter().filter_map(entry_url).collect()
+}
+
+/// An entry's `url`, else its `doi` through doi.org
+fn entry_url(entry: &BibEntry) -> Option<String> {
+    let field = |name: &str| entry.fields.get(name).filter(|v| !v.is_empty());
+    match (field("url"), field("doi")) {
+        (Some(url), _) => Some(url.clone()),
+        (None, Some(doi)) => {
+            let doi = [
+                "https://doi.org/",
+                "http://doi.org/",
+                "https://dx.doi.org/",
+                "http://dx.doi.org/",
+                "doi:",
+            ]
+            .iter()
+            .fold(doi.trim(), |doi, prefix| doi.trim_start_matches(prefix));
+            Some(format!("https://doi.org/{}", doi))
+        }
+        (None, None) => None,
+    }
+}
+
+/// Reference for an entry's `url`, else its `doi`; `None` when it has neither
+fn entry_reference(entry: &BibEntry, source: &str, now: &str) -> Option<Reference> {
+    let field = |name: &str| entry.fields.get(name).filter(|v| !v.is_empty());
+    let url = entry_url(entry)?;
+    Some(Reference {
+        title: field("title").cloned().unwrap_or_else(|| url.clone()),
+        url,
+        categories: vec!["research".to_string()],
+        tags: Vec::new(),
+        cited_in: vec![source.to_string()],
+        status: Status::Pending,
+        verified: None,
+        first_seen: Some(now.to_string()),
+        last_seen: Some(now.to_string()),
+        notes: None,
+        content_hash: None,
+    })
+}
+
+/// Parse BibTeX entries, skipping `@comment`, `@preamble` and `@string`
+///
+/// Values may be `{braced}` (nested braces allowed), `"quoted"` or bare, and
+/// joined with `#`. Braces are dropped and whitespace collapsed; `@string`
+/// macros are not expanded. Malformed entries are skipped.
+pub fn parse_bibtex(input: &str) -> Vec<BibEntry> {
+    let mut parser = BibParser {
+        chars: input.chars().collect(),
+        pos: 0,
+    };
+    let mut entries = Vec::new();
+    while parser.skip_to('@') {
+        let entry_type = parser.identifier().to_lowercase();
+        parser.skip_whitespace();
+        let close = match parser.next() {
+            Some('{') => '}',
+            Some('(') => ')',
+            _ => continue,
+        };
+        if matches!(entry_type.as_str(), "comment" | "preamble" | "string") {
+            parser.skip_group(close);
+            continue;
+        }
+        if let Some(entry) = parser.entry(entry_type, close) {
+            entries.push(entry);
+        }
+    }
+    entries
+}
+
+struct BibParser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl BibParser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += 1;
+        Some(c)
+    }
+
+    /// Advance past the next `c`; false at end of input
+    fn skip_to(&mut self, c: char) -> bool {
+        while let Some(next) = self.next() {
+            if next == c {
+                return true;
+            }
+        }
+        false
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.pos += 1;
+        }
+    }
+
+    fn identifier(&mut self) -> String {
+        let mut ident = String::new();
+        while let Some(c) = self
+            .peek()
+            .filter(|c| c.is_alphanumeric() || "_-:.+/".contains(*c))
+        {
+            ident.push(c);
+            self.pos += 1;
+        }
+        ident
+    }
+
+    /// Skip to the `close` that ends the current group, minding nested braces
+    fn skip_group(&mut self, close: char) {
+        let mut depth = 0;
+        while let Some(c) = self.next() {
+            match c {
+                '{' => depth += 1,
+                '}' if depth > 0 => depth -= 1,
+                c if c == close && depth == 0 => return,
+                _ => {}
+            }
+        }
+    }
+
+    fn entry(&mut self, entry_type: String, close: char) -> Option<BibEntry> {
+        self.skip_whitespace();
+        let mut key = String::new();
+        while let Some(c) = self.peek().filter(|c| *c != ',' && *c != close) {
+            key.push(c);
+            self.pos += 1;
+        }
+        let mut fields = HashMap::new();
+        loop {
+            self.skip_whitespace();
+            match self.next()? {
+                ',' => {}
+                c if c == close => break,
+                _ => {
+                    self.skip_group(close);
+                    return None;
+                }
+            }
+            self.skip_whitespace();
+            if self.peek() == Some(close) {
+                self.pos += 1;
+                break;
+            }
+            let name = self.identifier().to_lowercase();
+            self.skip_whitespace();
+            if name.is_empty() || self.next() != Some('=') {
+                self.skip_group(close);
+                return None;
+            }
+            let value = self.value(close)?;
+            fields.insert(name, clean_value(&value));
+        }
+        Some(BibEntry {
+            entry_type,
+            key: key.trim().to_string(),
+            fields,
+        })
+    }
+
+    /// A field value: `#`-joined braced, quoted or bare parts
+    fn value(&mut self, close: char) -> Option<String> {
+        let mut value = String::new();
+        loop {
+            self.skip_whitespace();
+            match self.peek()? {
+                '{' => {
+                    self.pos += 1;
+                    value.push_str(&self.delimited('}')?);
+                }
+                '"' => {
+                    self.pos += 1;
+                    value.push_str(&self.delimited('"')?);
+                }
+                _ => {
+                    while let Some(c) = self
+                        .peek()
+                        .filter(|c| *c != ',' && *c != close && *c != '#')
+                    {
+                        value.push(c);
+                        self.pos += 1;
+                    }
+                    value = value.trim_end().to_string();
+                }
+            }
+            self.skip_whitespace();
+            if self.peek() != Some('#') {
+                return Some(value);
+            }
+            self.pos += 1;
+        }
+    }
+
+    /// Text up to an unnested `end`, keeping inner braces
+    fn delimited(&mut self, end: char) -> Option<String> {
+        let mut text = String::new();
+        let mut depth = 0;
+        loop {
+            let c = self.next()?;
+            match c {
+                '{' => depth += 1,
+                '}' if depth > 0 => depth -= 1,
+                c if c == end && depth == 0 => return Some(text),
+                _ => {}
+            }
+            text.push(c);
+        }
+    }
+}
+
+/// Drop grouping braces and collapse whitespace (`{BERT}:  Pre-training` -> `BERT: Pre-training`)
+fn clean_value(value: &str) -> String {
+    value
+        .replace(['{', '}'], "")
+        .replace("\\_", "_")
+        .replace("\\&", "&")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BIB: &str = r#"
+% Comment lines outside entries are ignored
+@comment{ignored, url = {https://ignored.example/}}
+
+@article{vaswani2017,
+  title   = {Attention Is {All} You
+             Need},
+  author  = "Vaswani, Ashish and Shazeer, Noam",
+  year    = 2017,
+  url     = {https://arxiv.org/abs/1706.03762},
+}
+
+@inproceedings(devlin2019,
+  title = "{BERT}: Pre-training of Deep " # "Bidirectional Transformers",
+  doi = {10.18653/v1/N19-1423}
+)
+
+@book{knuth1984, title = {The {\TeX}book}}
+"#;
+
+    #[test]
+    fn test_parse_bibtex() {
+        let entries = parse_bibtex(BIB);
+        assert_eq!(entries.len(), 3);
+
+        assert_eq!(entries[0].entry_type, "article");
+        assert_eq!(entries[0].key, "vaswani2017");
+        assert_eq!(entries[0].fields["title"], "Attention Is All You Need");
+        assert_eq!(entries[0].fields["year"], "2017");
+        assert_eq!(entries[0].fields["url"], "https://arxiv.org/abs/1706.03762");
+
+        assert_eq!(entries[1].key, "devlin2019");
+        assert_eq!(
+            entries[1].fields["title"],
+            "BERT: Pre-training of Deep Bidirectional Transformers"
+        );
+        assert_eq!(entries[1].fields["doi"], "10.18653/v1/N19-1423");
+
+        assert_eq!(entries[2].fields["title"], "The \\TeXbook");
+    }
+
+    #[test]
+    fn test_entry_reference() {
+        let entries = parse_bibtex(BIB);
+        let refs: Vec<_> = entries
+            .iter()
+            .filter_map(|e| entry_reference(e, "refs.bib", "now"))
+            .collect();
+        assert_eq!(refs.len(), 2);
+
+        assert_eq!(refs[0].url, "https://arxiv.org/abs/1706.03762");
+        assert_eq!(refs[0].title, "Attention Is All You Need");
+        assert_eq!(refs[0].categories, ["research"]);
+        assert_eq!(refs[0].cited_in, ["refs.bib"]);
+        assert_eq!(refs[0].status, Status::Pending);
+
+        // DOI without a url resolves via doi.org
+        assert_eq!(refs[1].url, "https://doi.org/10.18653/v1/N19-1423");
+        assert_eq!(
+            refs[1].title,
+            "BERT: Pre-training of Deep Bidirectional Transformers"
+        );
+    }
+
+    #[test]
+    fn test_bibtex_urls() {
+        assert_eq!(
+            bibtex_urls(BIB),
+            [
+                "https://arxiv.org/abs/1706.03762",
+                "https://doi.org/10.18653/v1/N19-1423"
+            ]
+        );
+        assert_eq!(
+            bibtex_urls("@misc{x, doi = {https://dx.doi.org/10.1000/182}}"),
+            ["https://doi.org/10.1000/182"]
+        );
+    }
+}
@@ -1,13 +1,21 @@
 //! init command: Create references.yaml template
 //!
-//! Creates a new references.yaml file with proper structure.
+//! Creates a new references.yaml file with proper structure. With
+//! `--interactive`, prompts for project metadata and can seed references from
+//! a scan of the current directory.
 
-use crate::schema::{Meta, Reference, ReferencesFile, Status};
+use crate::normalize::NormalizeOptions;
+use crate::progress;
+use crate::scan::{merge_references, scan_files};
+use crate::schema::{Meta, Reference, ReferencesFile, Status, JSON_FILE};
 use anyhow::{bail, Result};
 use chrono::Utc;
 use clap::Args;
+use dialoguer::{Confirm, Input};
 use serde::Serialize;
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
 
 #[derive(Args)]
 pub struct InitArgs {
@@ -15,18 +23,52 @@ pub struct InitArgs {
     #[arg(short, long, default_value = "references.yaml")]
     pub output: PathBuf,
 
+    /// Write references.json (same schema, as JSON) instead of references.yaml
+    #[arg(long, conflicts_with = "output")]
+    pub json: bool,
+
     /// Overwrite existing file
     #[arg(long)]
     pub force: bool,
+
+    /// Prompt for project name, categories, and seeding from a scan (needs a TTY)
+    #[arg(short, long)]
+    pub interactive: bool,
 }
 
 #[derive(Debug, Serialize)]
 pub struct InitOutput {
     pub created: String,
     pub file: String,
+    /// References seeded from a scan (--interactive)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seeded: Option<usize>,
+}
+
+/// Answers from the interactive wizard
+#[derive(Debug)]
+struct InitAnswers {
+    project: Option<String>,
+    categories: Vec<String>,
+    /// Scan this directory for references instead of writing the example entry
+    seed_from: Option<PathBuf>,
 }
 
-pub async fn run_init(args: InitArgs) -> Result<()> {
+impl Default for InitAnswers {
+    /// The non-interactive template: one example reference
+    fn default() -> Self {
+        Self {
+            project: None,
+            categories: vec!["example".to_string()],
+            seed_from: None,
+        }
+    }
+}
+
+pub async fn run_init(mut args: InitArgs) -> Result<()> {
+    if args.json {
+        args.output = PathBuf::from(JSON_FILE);
+    }
     // Check if file exists
     if args.output.exists() && !args.force {
         let error = serde_json::json!({
@@ -38,40 +80,182 @@ pub async fn run_init(args: InitArgs) -> Result<()> {
         bail!("File exists");
     }
 
-    let now = Utc::now();
-    let date = now.format("%Y-%m-%d").to_string();
+    let answers = if !args.interactive {
+        InitAnswers::default()
+    } else if std::io::stdin().is_terminal() && std::io::stderr().is_terminal() {
+        prompt_answers()?
+    } else {
+        eprintln!("Not a terminal; skipping prompts and writing the default template");
+        InitAnswers::default()
+    };
+
+    let date = Utc::now().format("%Y-%m-%d").to_string();
+    let (refs_file, seeded) = build_references(&answers, date).await?;
+
+    // Write file (YAML, or JSON for .json)
+    refs_file.save(&args.output).await?;
+
+    // Output JSON result
+    let output = InitOutput {
+        created: Utc::now().to_rfc3339(),
+        file: args.output.display().to_string(),
+        seeded,
+    };
+    println!("{}", serde_json::to_string(&output)?);
+
+    Ok(())
+}
+
+/// Ask for project name, default categories, and whether to seed from a scan
+fn prompt_answers() -> Result<InitAnswers> {
+    let dir_name = std::env::current_dir()
+        .ok()
+        .and_then(|d| d.file_name().map(|n| n.to_string_lossy().into_owned()))
+        .unwrap_or_default();
+
+    let project: String = Input::new()
+        .with_prompt("Project name")
+        .default(dir_name)
+        .allow_empty(true)
+        .interact_text()?;
+
+    let categories: String = Input::new()
+        .with_prompt("Default categories (comma-separated)")
+        .default("general".to_string())
+        .interact_text()?;
+
+    let seed = Confirm::new()
+        .with_prompt("Seed references from Markdown files in this directory?")
+        .default(true)
+        .interact()?;
 
-    // Create template with one example reference
-    let refs_file = ReferencesFile {
+    Ok(InitAnswers {
+        project: Some(project.trim().to_string()).filter(|p| !p.is_empty()),
+        categories: parse_categories(&categories),
+        seed_from: seed.then(|| PathBuf::from(".")),
+    })
+}
+
+/// Split "a, b,,c" into ["a", "b", "c"] (falls back to "general")
+fn parse_categories(input: &str) -> Vec<String> {
+    let categories: Vec<String> = input
+        .split(',')
+        .map(|c| c.trim().to_string())
+        .filter(|c| !c.is_empty())
+        .collect();
+    if categories.is_empty() {
+        vec!["general".to_string()]
+    } else {
+        categories
+    }
+}
+
+/// Build the references file, returning it with the seeded count (if seeded)
+async fn build_references(
+    answers: &InitAnswers,
+    date: String,
+) -> Result<(ReferencesFile, Option<usize>)> {
+    let mut refs_file = ReferencesFile {
         meta: Meta {
+            project: answers.project.clone(),
             created: date,
             last_verified: None,
             tool: "ref".to_string(),
-            total_links: 1,
+            total_links: 0,
         },
-        references: vec![Reference {
+        references: vec![],
+    };
+
+    let Some(dir) = &answers.seed_from else {
+        // Template with one example reference
+        refs_file.references.push(Reference {
             url: "https://example.com".to_string(),
             title: "Example Reference".to_string(),
-            categories: vec!["example".to_string()],
+            categories: answers.categories.clone(),
+            tags: vec![],
             cited_in: vec!["README.md".to_string()],
             status: Status::Pending,
             verified: None,
+            first_seen: None,
+            last_seen: None,
             notes: None,
-        }],
+            content_hash: None,
+        });
+        refs_file.meta.total_links = 1;
+        return Ok((refs_file, None));
     };
 
-    // Serialize to YAML
-    let yaml = serde_yaml::to_string(&refs_file)?;
+    let mut url_map = seed_references(dir).await?;
+    // Paths with no category hints get the project's defaults
+    for reference in url_map.values_mut() {
+        if reference.categories == ["general"] {
+            reference.categories = answers.categories.clone();
+        }
+    }
+    let (seeded, _) = merge_references(&mut refs_file, url_map, &NormalizeOptions::default());
+    Ok((refs_file, Some(seeded)))
+}
 
-    // Write file
-    tokio::fs::write(&args.output, yaml).await?;
+async fn seed_references(dir: &Path) -> Result<HashMap<String, Reference>> {
+    let (files, url_map) =
+        scan_files(&[dir.to_path_buf()], true, &NormalizeOptions::default()).await?;
+    progress!(
+        "Scanned {} files, found {} URLs",
+        files.len(),
+        url_map.len()
+    );
+    Ok(url_map)
+}
 
-    // Output JSON result
-    let output = InitOutput {
-        created: Utc::now().to_rfc3339(),
-        file: args.output.display().to_string(),
-    };
-    println!("{}", serde_json::to_string(&output)?);
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    Ok(())
+    #[test]
+    fn test_parse_categories() {
+        assert_eq!(
+            parse_categories("research, docs,,"),
+            vec!["research", "docs"]
+        );
+        assert_eq!(parse_categories("  "), vec!["general"]);
+    }
+
+    #[tokio::test]
+    async fn test_build_references_default_template() {
+        let (refs_file, seeded) = build_references(&InitAnswers::default(), "2025-01-01".into())
+            .await
+            .unwrap();
+        assert_eq!(seeded, None);
+        assert_eq!(refs_file.meta.project, None);
+        assert_eq!(refs_file.meta.total_links, 1);
+        assert_eq!(refs_file.references[0].url, "https://example.com");
+        assert_eq!(refs_file.references[0].categories, vec!["example"]);
+    }
+
+    #[tokio::test]
+    async fn test_build_references_seeded() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("notes.md"),
+            "See [Rust](https://www.rust-lang.org) and https://docs.rs/serde",
+        )
+        .unwrap();
+
+        let answers = InitAnswers {
+            project: Some("demo".to_string()),
+            categories: vec!["research".to_string()],
+            seed_from: Some(dir.path().to_path_buf()),
+        };
+        let (refs_file, seeded) = build_references(&answers, "2025-01-01".into())
+            .await
+            .unwrap();
+
+        assert_eq!(seeded, Some(2));
+        assert_eq!(refs_file.meta.project.as_deref(), Some("demo"));
+        assert_eq!(refs_file.meta.total_links, 2);
+        assert!(refs_file
+            .references
+            .iter()
+            .all(|r| r.categories == ["research"]));
+    }
 }
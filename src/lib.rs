@@ -5,16 +5,33 @@
 
 pub mod browser;
 pub mod check_links;
+pub mod config;
+pub mod convert;
+pub mod cookies;
+pub mod doi;
+pub mod events;
+pub mod export;
 pub mod extract;
 pub mod fetch;
+pub mod filter;
+pub mod ftp;
+pub mod import;
 pub mod init;
+pub mod normalize;
+pub mod output;
+pub mod patterns;
 pub mod pdf;
+pub mod prune;
 pub mod refresh_data;
+pub mod report;
+pub mod robots;
 pub mod scan;
 pub mod schema;
+pub mod sitemap;
 pub mod update;
+pub mod util;
 pub mod verify_refs;
 
-pub use check_links::{check_links, CheckLinksConfig, LinkReport, LinkResult};
-pub use refresh_data::{refresh_data, ExtractedData, RefreshConfig};
+pub use check_links::{check_links, check_links_each, CheckLinksConfig, LinkReport, LinkResult};
+pub use refresh_data::{default_extractors, refresh_data, ExtractedData, Extractor, RefreshConfig};
 pub use schema::{Meta, Reference, ReferencesFile, Status};
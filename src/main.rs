@@ -4,26 +4,50 @@
 //! Bypasses bot protection (403/999) that blocks curl/wget.
 
 use anyhow::Result;
-use clap::{Parser, Subcommand};
+use clap::{CommandFactory, FromArgMatches, Parser, Subcommand};
 
 mod browser;
 mod check_links;
+mod config;
+mod convert;
+mod cookies;
+mod doi;
+mod events;
+mod export;
 mod extract;
 mod fetch;
+mod filter;
+mod ftp;
+mod import;
 mod init;
+mod normalize;
+mod output;
+mod patterns;
 mod pdf;
+mod prune;
 mod refresh_data;
+mod report;
+mod robots;
 mod scan;
 mod schema;
+mod sitemap;
 mod update;
+mod util;
 mod verify_refs;
 
 use check_links::{run_check_links, CheckLinksArgs};
+use config::Config;
+use convert::{run_convert, ConvertArgs};
+use export::{run_export, ExportArgs};
 use fetch::{run_fetch, FetchArgs};
+use import::{run_import, ImportArgs};
 use init::{run_init, InitArgs};
 use pdf::{run_pdf, PdfArgs};
+use prune::{run_prune, PruneArgs};
 use refresh_data::{run_refresh_data, RefreshDataArgs};
+use report::{run_report, ReportArgs};
 use scan::{run_scan, ScanArgs};
+use schema::{run_schema, SchemaArgs};
 use update::{run_update, UpdateArgs};
 use verify_refs::{run_verify_refs, VerifyRefsArgs};
 
@@ -36,6 +60,18 @@ use verify_refs::{run_verify_refs, VerifyRefsArgs};
     long_about = "Reference verification and web fetching for AI agents.\nBypasses bot protection (403/999) that blocks curl/wget.\nAll output is JSON for LLM consumption."
 )]
 struct Cli {
+    /// Suppress progress output on stderr (errors and warnings still print)
+    #[arg(long, short, global = true)]
+    quiet: bool,
+
+    /// Progress on stderr: text lines, or jsonl events (start/item/done) for agents
+    #[arg(long, global = true, value_enum, default_value = "text")]
+    progress: events::ProgressFormat,
+
+    /// Log per-phase timings (launch, navigate, content, parse) and raw navigation errors to stderr
+    #[arg(long, global = true)]
+    debug: bool,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -50,8 +86,20 @@ enum Commands {
     Init(InitArgs),
     /// Scan markdown files for URLs, build references.yaml
     Scan(ScanArgs),
+    /// Import url/doi entries from a BibTeX file into references.yaml
+    Import(ImportArgs),
     /// Verify references.yaml entries and update status
     VerifyRefs(VerifyRefsArgs),
+    /// Remove references no longer cited in any scanned file
+    Prune(PruneArgs),
+    /// Print the JSON Schema for references.yaml
+    Schema(SchemaArgs),
+    /// Export references.yaml as a CSV or Markdown table
+    Export(ExportArgs),
+    /// Convert references.yaml to JSON or back (format from the file extension)
+    Convert(ConvertArgs),
+    /// Summarize references.yaml link health as JSON or HTML
+    Report(ReportArgs),
     /// Check URL health in markdown files or single URLs
     CheckLinks(CheckLinksArgs),
     /// Extract live data from URLs (market sizes, pricing, statistics)
@@ -62,16 +110,59 @@ enum Commands {
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    let cli = Cli::parse();
+    let matches = Cli::command().get_matches();
+    let mut cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    output::set_quiet(cli.quiet || cli.progress == events::ProgressFormat::Jsonl);
+    events::set_format(cli.progress);
+    if cli.debug {
+        init_debug_logging();
+    }
+
+    // .ref-tools.toml defaults sit between built-in defaults and explicit flags
+    if let (Some(config), Some((_, sub))) = (config_file()?, matches.subcommand()) {
+        match &mut cli.command {
+            Commands::Fetch(args) => config.apply_fetch(args, sub),
+            Commands::VerifyRefs(args) => config.apply_verify_refs(args, sub),
+            Commands::CheckLinks(args) => config.apply_check_links(args, sub)?,
+            Commands::RefreshData(args) => config.apply_refresh_data(args, sub),
+            _ => {}
+        }
+    }
 
     match cli.command {
         Commands::Fetch(args) => run_fetch(args).await,
         Commands::Pdf(args) => run_pdf(args).await,
         Commands::Init(args) => run_init(args).await,
         Commands::Scan(args) => run_scan(args).await,
+        Commands::Import(args) => run_import(args).await,
         Commands::CheckLinks(args) => run_check_links(args).await,
         Commands::RefreshData(args) => run_refresh_data(args).await,
         Commands::VerifyRefs(args) => run_verify_refs(args).await,
+        Commands::Prune(args) => run_prune(args).await,
+        Commands::Schema(args) => run_schema(args).await,
+        Commands::Export(args) => run_export(args).await,
+        Commands::Convert(args) => run_convert(args).await,
+        Commands::Report(args) => run_report(args).await,
         Commands::Update(args) => run_update(args).await,
     }
 }
+
+/// Debug events from this crate only (not chromiumoxide's CDP chatter), on stderr
+fn init_debug_logging() {
+    use tracing_subscriber::filter::{LevelFilter, Targets};
+    use tracing_subscriber::prelude::*;
+
+    let targets = Targets::new().with_target(module_path!(), LevelFilter::DEBUG);
+    tracing_subscriber::registry()
+        .with(tracing_subscriber::fmt::layer().with_writer(std::io::stderr))
+        .with(targets)
+        .init();
+}
+
+/// Load `.ref-tools.toml` if present
+fn config_file() -> Result<Option<Config>> {
+    Ok(Config::discover()?.map(|(path, config)| {
+        crate::progress!("Using config: {}", path.display());
+        config
+    }))
+}
@@ -0,0 +1,229 @@
+//! URL normalization for deduplication
+//!
+//! Produces a comparison key only; callers keep the original URL for display.
+
+use crate::extract::is_tracking_param;
+use url::Url;
+
+/// Which optional rules to apply on top of the always-on ones
+#[derive(Debug, Clone)]
+pub struct NormalizeOptions {
+    /// `/a/` and `/a` are the same page (the root `/` is kept)
+    pub strip_trailing_slash: bool,
+    /// Drop `#fragment`
+    pub strip_fragment: bool,
+    /// Drop `utm_*`, `fbclid`, `gclid` and similar parameters
+    pub strip_tracking: bool,
+    /// Extra tracking parameter names (`name*` = prefix)
+    pub extra_tracking: Vec<String>,
+}
+
+impl Default for NormalizeOptions {
+    fn default() -> Self {
+        Self {
+            strip_trailing_slash: true,
+            strip_fragment: false,
+            strip_tracking: false,
+            extra_tracking: Vec::new(),
+        }
+    }
+}
+
+/// Comparison key for a URL: lowercase scheme and host, no default port, sorted query
+///
+/// Unparseable input is returned unchanged.
+pub fn normalize_url(url: &str, options: &NormalizeOptions) -> String {
+    // Parsing lowercases scheme and host and drops default ports
+    let Ok(mut parsed) = Url::parse(url) else {
+        return url.to_string();
+    };
+
+    let mut params: Vec<(String, String)> = parsed
+        .query_pairs()
+        .filter(|(key, _)| {
+            !(options.strip_tracking && is_tracking_param(key, &options.extra_tracking))
+        })
+        .map(|(key, value)| (key.into_owned(), value.into_owned()))
+        .collect();
+    params.sort();
+    if params.is_empty() {
+        parsed.set_query(None);
+    } else {
+        parsed.query_pairs_mut().clear().extend_pairs(params);
+    }
+
+    if options.strip_fragment {
+        parsed.set_fragment(None);
+    }
+
+    if options.strip_trailing_slash && parsed.path().len() > 1 && parsed.path().ends_with('/') {
+        let path = parsed.path().trim_end_matches('/').to_string();
+        parsed.set_path(&path);
+    }
+
+    parsed.to_string()
+}
+
+/// Whether `to` is on a different site than `from` (a `www.` prefix is ignored)
+///
+/// Hosts are compared after parsing, which maps international domains to
+/// punycode, so `bücher.de` and `xn--bcher-kva.de` match. With
+/// `allow_subdomains`, hosts under the same registrable domain (eTLD+1, e.g.
+/// `blog.example.co.uk` and `example.co.uk`) also match. False when either
+/// URL has no host.
+pub fn is_cross_domain(from: &str, to: &str, allow_subdomains: bool) -> bool {
+    let host = |url: &str| {
+        let host = Url::parse(url).ok()?.host_str()?.to_string();
+        let registrable = allow_subdomains
+            .then(|| psl::domain_str(&host).map(String::from))
+            .flatten();
+        Some(registrable.unwrap_or_else(|| host.trim_start_matches("www.").to_string()))
+    };
+    match (host(from), host(to)) {
+        (Some(from), Some(to)) => from != to,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn norm(url: &str) -> String {
+        normalize_url(url, &NormalizeOptions::default())
+    }
+
+    #[test]
+    fn test_lowercase_host_and_scheme() {
+        assert_eq!(norm("HTTPS://Docs.RS/Serde"), "https://docs.rs/Serde");
+    }
+
+    #[test]
+    fn test_default_port() {
+        assert_eq!(norm("https://x.com:443/a"), "https://x.com/a");
+        assert_eq!(norm("http://x.com:80/a"), "http://x.com/a");
+        assert_eq!(norm("https://x.com:8443/a"), "https://x.com:8443/a");
+    }
+
+    #[test]
+    fn test_query_order() {
+        assert_eq!(
+            norm("https://x.com/a?b=1&a=2"),
+            norm("https://x.com/a?a=2&b=1")
+        );
+        assert_eq!(norm("https://x.com/a?"), "https://x.com/a");
+    }
+
+    #[test]
+    fn test_trailing_slash() {
+        assert_eq!(norm("https://x.com/a/"), "https://x.com/a");
+        assert_eq!(norm("https://x.com/"), "https://x.com/");
+        assert_eq!(norm("https://x.com"), "https://x.com/");
+
+        let keep = NormalizeOptions {
+            strip_trailing_slash: false,
+            ..Default::default()
+        };
+        assert_eq!(normalize_url("https://x.com/a/", &keep), "https://x.com/a/");
+    }
+
+    #[test]
+    fn test_fragment() {
+        assert_eq!(norm("https://x.com/a#intro"), "https://x.com/a#intro");
+
+        let strip = NormalizeOptions {
+            strip_fragment: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            normalize_url("https://x.com/a/#intro", &strip),
+            "https://x.com/a"
+        );
+    }
+
+    #[test]
+    fn test_tracking_params_opt_in() {
+        let url = "https://x.com/a?utm_source=news&id=7&fbclid=abc";
+        assert_eq!(norm(url), "https://x.com/a?fbclid=abc&id=7&utm_source=news");
+
+        let strip = NormalizeOptions {
+            strip_tracking: true,
+            ..Default::default()
+        };
+        assert_eq!(normalize_url(url, &strip), "https://x.com/a?id=7");
+        assert_eq!(
+            normalize_url("https://x.com/a?utm_medium=x", &strip),
+            "https://x.com/a"
+        );
+    }
+
+    #[test]
+    fn test_idn_host() {
+        assert_eq!(
+            norm("https://Bücher.de/katalog/"),
+            norm("https://xn--bcher-kva.de/katalog")
+        );
+    }
+
+    #[test]
+    fn test_is_cross_domain() {
+        assert!(!is_cross_domain(
+            "https://xn--bcher-kva.de/a",
+            "https://www.bücher.de/b",
+            false
+        ));
+        assert!(!is_cross_domain(
+            "https://bücher.de/",
+            "https://BÜCHER.de/x",
+            false
+        ));
+        assert!(!is_cross_domain(
+            "https://example.com/",
+            "https://www.example.com/",
+            false
+        ));
+        assert!(is_cross_domain(
+            "https://bücher.de/",
+            "https://buecher.de/",
+            false
+        ));
+        assert!(is_cross_domain(
+            "https://example.com/",
+            "https://example.org/",
+            false
+        ));
+        assert!(!is_cross_domain("not a url", "https://example.org/", false));
+    }
+
+    #[test]
+    fn test_is_cross_domain_subdomains() {
+        let cases = [
+            ("https://blog.example.com/post", "https://example.com/post"),
+            ("https://example.com/", "https://docs.example.com/"),
+            ("https://a.example.co.uk/", "https://b.example.co.uk/"),
+        ];
+        for (from, to) in cases {
+            assert!(is_cross_domain(from, to, false), "{} -> {}", from, to);
+            assert!(!is_cross_domain(from, to, true), "{} -> {}", from, to);
+        }
+
+        // Different registrable domains stay cross-domain
+        for (from, to) in [
+            ("https://example.com/", "https://example.org/"),
+            ("https://a.co.uk/", "https://b.co.uk/"),
+            ("https://alice.github.io/", "https://bob.github.io/"),
+        ] {
+            assert!(is_cross_domain(from, to, true), "{} -> {}", from, to);
+        }
+        assert!(!is_cross_domain(
+            "http://127.0.0.1:8080/",
+            "http://127.0.0.1/",
+            true
+        ));
+    }
+
+    #[test]
+    fn test_unparseable() {
+        assert_eq!(norm("not a url"), "not a url");
+    }
+}
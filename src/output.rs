@@ -0,0 +1,69 @@
+//! Output helpers shared by the commands
+//!
+//! Report formats for check-links, refresh-data and verify-refs: compact JSON
+//! by default, YAML for embedding results in config files. Progress lines on
+//! stderr go through `progress!` so `--quiet` (and `--progress jsonl`) can
+//! silence them.
+
+use anyhow::Result;
+use clap::ValueEnum;
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static QUIET: AtomicBool = AtomicBool::new(false);
+
+/// Silence progress lines (--quiet); warnings and errors still print
+pub fn set_quiet(quiet: bool) {
+    QUIET.store(quiet, Ordering::Relaxed);
+}
+
+pub fn is_quiet() -> bool {
+    QUIET.load(Ordering::Relaxed)
+}
+
+/// `eprintln!` for progress and status lines, silenced by --quiet
+#[macro_export]
+macro_rules! progress {
+    ($($arg:tt)*) => {
+        if !$crate::output::is_quiet() {
+            eprintln!($($arg)*);
+        }
+    };
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Compact JSON on one line
+    #[default]
+    Json,
+    /// YAML document
+    Yaml,
+}
+
+impl OutputFormat {
+    /// Serialize a report, newline-terminated
+    pub fn render<T: Serialize>(self, value: &T) -> Result<String> {
+        Ok(match self {
+            OutputFormat::Json => serde_json::to_string(value)? + "\n",
+            OutputFormat::Yaml => serde_yaml::to_string(value)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let value = serde_json::json!({"ok": 1, "results": [{"url": "https://a.example"}]});
+        assert_eq!(
+            OutputFormat::Json.render(&value).unwrap(),
+            "{\"ok\":1,\"results\":[{\"url\":\"https://a.example\"}]}\n"
+        );
+        assert_eq!(
+            OutputFormat::Yaml.render(&value).unwrap(),
+            "ok: 1\nresults:\n- url: https://a.example\n"
+        );
+    }
+}
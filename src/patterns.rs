@@ -0,0 +1,212 @@
+//! Paywall, login-wall and soft-404 detection patterns
+//!
+//! Built-in English phrases and CSS selectors, optionally extended from a YAML
+//! file for non-English sites and niche paywall vendors:
+//!
+//! ```yaml
+//! paywall:
+//!   phrases: ["jetzt abonnieren"]
+//!   selectors: [".tp-modal"]
+//! login:
+//!   phrases: ["bitte melden sie sich an"]
+//!   selectors: []
+//! not_found:
+//!   phrases: ["seite nicht gefunden"]
+//! ```
+//!
+//! `not_found` phrases only match the page title or `<h1>`, not body text.
+
+use anyhow::{bail, Context, Result};
+use scraper::{Html, Selector};
+use serde::Deserialize;
+use std::path::Path;
+
+/// Phrases (case-insensitive substrings of the HTML) and CSS selectors
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PatternSet {
+    #[serde(default)]
+    pub phrases: Vec<String>,
+    #[serde(default)]
+    pub selectors: Vec<String>,
+}
+
+impl PatternSet {
+    fn new(phrases: &[&str], selectors: &[&str]) -> Self {
+        Self {
+            phrases: phrases.iter().map(|s| s.to_string()).collect(),
+            selectors: selectors.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    /// Whether any phrase occurs in `lower` (lowercased HTML) or any selector matches
+    pub fn matches(&self, doc: &Html, lower: &str) -> bool {
+        self.phrases.iter().any(|p| lower.contains(p.as_str())) || self.matches_selectors(doc)
+    }
+
+    /// Whether any selector matches
+    pub fn matches_selectors(&self, doc: &Html) -> bool {
+        self.selectors.iter().any(|sel_str| {
+            Selector::parse(sel_str).is_ok_and(|sel| doc.select(&sel).next().is_some())
+        })
+    }
+
+    fn extend(&mut self, other: PatternSet) {
+        self.phrases
+            .extend(other.phrases.into_iter().map(|p| p.to_lowercase()));
+        self.selectors.extend(other.selectors);
+    }
+}
+
+/// Paywall, login-wall and not-found patterns
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WallPatterns {
+    #[serde(default)]
+    pub paywall: PatternSet,
+    #[serde(default)]
+    pub login: PatternSet,
+    /// Soft-404 indicators (200 status with a "page not found" page)
+    #[serde(default)]
+    pub not_found: PatternSet,
+}
+
+impl WallPatterns {
+    /// Built-in English patterns
+    pub fn builtin() -> Self {
+        Self {
+            paywall: PatternSet::new(
+                &[
+                    "subscribe to continue",
+                    "subscription required",
+                    "premium content",
+                    "paywall",
+                    "member-only",
+                    "members only",
+                    "unlock this article",
+                    "purchase to read",
+                    "buy now to read",
+                    "paid subscribers",
+                ],
+                &[
+                    "[class*='paywall']",
+                    "[id*='paywall']",
+                    "[class*='subscription-wall']",
+                    "[class*='piano-offer']",
+                    "[class*='premium-wall']",
+                ],
+            ),
+            login: PatternSet::new(
+                &[
+                    "sign in to continue",
+                    "log in to continue",
+                    "login to continue",
+                    "please sign in",
+                    "please log in",
+                    "create an account",
+                    "sign up to view",
+                    "register to view",
+                    "authentication required",
+                ],
+                &[
+                    "[class*='login-wall']",
+                    "[class*='auth-wall']",
+                    "[class*='signup-wall']",
+                    "[id*='login-modal']",
+                    "[class*='gate-content']",
+                ],
+            ),
+            not_found: PatternSet::new(
+                &[
+                    "page not found",
+                    "404 not found",
+                    "error 404",
+                    "404 error",
+                    "page does not exist",
+                    "page doesn't exist",
+                    "page could not be found",
+                    "page can't be found",
+                    "page cannot be found",
+                ],
+                &[
+                    "body.error404",
+                    "body.error-404",
+                    "body.page-404",
+                    "body.not-found",
+                ],
+            ),
+        }
+    }
+
+    /// Built-in patterns plus those from a YAML file
+    pub async fn load(path: &Path) -> Result<Self> {
+        let content = tokio::fs::read_to_string(path)
+            .await
+            .with_context(|| format!("Failed to read patterns file: {}", path.display()))?;
+        let extra = Self::parse(&content)
+            .with_context(|| format!("Failed to parse patterns file: {}", path.display()))?;
+
+        let mut patterns = Self::builtin();
+        patterns.merge(extra);
+        Ok(patterns)
+    }
+
+    /// Parse a patterns YAML file (selectors are validated up front)
+    pub fn parse(content: &str) -> Result<Self> {
+        let patterns: Self = serde_yaml::from_str(content)?;
+        for (section, set) in [
+            ("paywall", &patterns.paywall),
+            ("login", &patterns.login),
+            ("not_found", &patterns.not_found),
+        ] {
+            for sel in &set.selectors {
+                if Selector::parse(sel).is_err() {
+                    bail!("{}.selectors: invalid CSS selector '{}'", section, sel);
+                }
+            }
+        }
+        Ok(patterns)
+    }
+
+    /// Add another set of patterns to these
+    pub fn merge(&mut self, other: WallPatterns) {
+        self.paywall.extend(other.paywall);
+        self.login.extend(other.login);
+        self.not_found.extend(other.not_found);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_partial_sections() {
+        let patterns =
+            WallPatterns::parse("paywall:\n  phrases: [\"Jetzt abonnieren\"]\n").unwrap();
+        assert_eq!(patterns.paywall.phrases, vec!["Jetzt abonnieren"]);
+        assert!(patterns.paywall.selectors.is_empty());
+        assert!(patterns.login.phrases.is_empty());
+    }
+
+    #[test]
+    fn test_parse_invalid_selector() {
+        let err = WallPatterns::parse("login:\n  selectors: [\"div[\"]\n").unwrap_err();
+        assert!(err.to_string().contains("login.selectors"));
+    }
+
+    #[test]
+    fn test_merge_lowercases_phrases() {
+        let mut patterns = WallPatterns::builtin();
+        let builtin_count = patterns.paywall.phrases.len();
+        patterns.merge(
+            WallPatterns::parse("paywall:\n  phrases: [\"Réservé aux abonnés\"]\n").unwrap(),
+        );
+
+        assert_eq!(patterns.paywall.phrases.len(), builtin_count + 1);
+        let doc = Html::parse_document("<p>Article réservé aux abonnés</p>");
+        assert!(patterns
+            .paywall
+            .matches(&doc, "<p>article réservé aux abonnés</p>"));
+    }
+}
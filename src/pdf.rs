@@ -3,34 +3,146 @@
 //! Local extraction, no external APIs.
 //! Output matches fetch command structure for consistency.
 
-use crate::fetch::{CodeBlock, Link, Page, PageStatus, Section};
+use crate::events;
+use crate::fetch::{
+    char_count, detect_text_language, reading_minutes, word_count, CodeBlock, Link, Page,
+    PageStatus, Section,
+};
+use crate::progress;
+use crate::util::truncate_words;
 use anyhow::Result;
 use clap::Args;
+use futures::stream::{self, StreamExt};
+use pdf_extract::encryption::DecryptionError;
+use pdf_extract::{
+    decode_text_string, Document, MediaBox, OutputDev, OutputError, PlainTextOutput, Transform,
+};
 use regex::Regex;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use url::Url;
 
 #[derive(Args)]
 pub struct PdfArgs {
     /// PDF files to extract
     #[arg(required = true)]
     pub files: Vec<PathBuf>,
+
+    /// Pages to extract, 1-based (e.g. "1-5,8,10-12")
+    #[arg(long, value_parser = parse_page_ranges)]
+    pub pages: Option<PageRanges>,
+
+    /// OCR pages when a PDF has no text layer (scanned documents; needs the `ocr` feature)
+    #[arg(long)]
+    pub ocr: bool,
+
+    /// Password for encrypted PDFs
+    #[arg(long)]
+    pub password: Option<String>,
+
+    /// Read multi-column pages column by column: a column count, or "auto" to detect gutters
+    #[arg(long, value_parser = parse_columns)]
+    pub columns: Option<Columns>,
+
+    /// PDFs extracted at once
+    #[arg(long, short, default_value = "4")]
+    pub parallel: usize,
+}
+
+/// Column handling for multi-column layouts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Columns {
+    /// Split at every gutter found
+    Auto,
+    /// Split at the widest `n - 1` gutters
+    Count(usize),
+}
+
+fn parse_columns(s: &str) -> Result<Columns, String> {
+    if s.eq_ignore_ascii_case("auto") {
+        return Ok(Columns::Auto);
+    }
+    match s.parse::<usize>() {
+        Ok(n @ 1..=6) => Ok(Columns::Count(n)),
+        _ => Err(format!("expected 1-6 or \"auto\", got '{}'", s)),
+    }
+}
+
+/// Extraction settings shared by local and downloaded PDFs
+#[derive(Debug, Default)]
+struct PdfOptions {
+    pages: Option<PageRanges>,
+    ocr: bool,
+    password: Option<String>,
+    columns: Option<Columns>,
+}
+
+/// Selected page ranges (inclusive, 1-based)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PageRanges(Vec<(u32, u32)>);
+
+impl PageRanges {
+    fn contains(&self, page: u32) -> bool {
+        self.0
+            .iter()
+            .any(|(start, end)| (*start..=*end).contains(&page))
+    }
+}
+
+/// Parse a page range spec like "1-5,8,10-12"
+fn parse_page_ranges(spec: &str) -> Result<PageRanges, String> {
+    let parse_page = |s: &str| -> Result<u32, String> {
+        match s.trim().parse::<u32>() {
+            Ok(0) => Err("pages are numbered from 1".to_string()),
+            Ok(n) => Ok(n),
+            Err(_) => Err(format!("invalid page number: '{}'", s.trim())),
+        }
+    };
+
+    let mut ranges = Vec::new();
+    for part in spec.split(',') {
+        let part = part.trim();
+        if part.is_empty() {
+            return Err(format!("empty range in '{}'", spec));
+        }
+        let range = match part.split_once('-') {
+            Some((start, end)) => {
+                let (start, end) = (parse_page(start)?, parse_page(end)?);
+                if start > end {
+                    return Err(format!("invalid range '{}': start is after end", part));
+                }
+                (start, end)
+            }
+            None => {
+                let page = parse_page(part)?;
+                (page, page)
+            }
+        };
+        ranges.push(range);
+    }
+
+    Ok(PageRanges(ranges))
 }
 
 pub async fn run_pdf(args: PdfArgs) -> Result<()> {
     let file_count = args.files.len();
-    eprintln!(
-        "Extracting {} PDF{}...",
+    let parallel = args.parallel.min(file_count).max(1);
+    progress!(
+        "Extracting {} PDF{} ({} parallel)...",
         file_count,
-        if file_count == 1 { "" } else { "s" }
+        if file_count == 1 { "" } else { "s" },
+        parallel
     );
 
-    let mut results: Vec<Page> = Vec::new();
-
-    for file in &args.files {
-        eprintln!("  -> {}", file.display());
-        let page = extract_pdf(file).await;
-        results.push(page);
-    }
+    let options = PdfOptions {
+        pages: args.pages,
+        ocr: args.ocr,
+        password: args.password,
+        columns: args.columns,
+    };
+    events::start(file_count);
+    let results = extract_pdfs(args.files, options, parallel).await;
+    events::done();
 
     let ok_count = results
         .iter()
@@ -46,11 +158,36 @@ pub async fn run_pdf(args: PdfArgs) -> Result<()> {
         }
     }
 
-    eprintln!("Done: {}/{} OK", ok_count, file_count);
+    progress!("Done: {}/{} OK", ok_count, file_count);
     Ok(())
 }
 
-async fn extract_pdf(path: &PathBuf) -> Page {
+/// Extract PDFs on blocking threads, at most `parallel` at once, in input order
+async fn extract_pdfs(files: Vec<PathBuf>, options: PdfOptions, parallel: usize) -> Vec<Page> {
+    let options = Arc::new(options);
+    stream::iter(files)
+        .map(|file| {
+            let options = Arc::clone(&options);
+            async move {
+                let file_url = format!("file://{}", file.display());
+                let page = tokio::task::spawn_blocking(move || {
+                    progress!("  -> {}", file.display());
+                    extract_pdf(&file, &options)
+                })
+                .await
+                .unwrap_or_else(|e| {
+                    error_page(&file_url, &format!("PDF extraction failed: {}", e))
+                });
+                events::item(&page.url, page.status == PageStatus::Ok);
+                page
+            }
+        })
+        .buffered(parallel)
+        .collect()
+        .await
+}
+
+fn extract_pdf(path: &Path, options: &PdfOptions) -> Page {
     let file_url = format!("file://{}", path.display());
 
     // Check file exists
@@ -58,46 +195,142 @@ async fn extract_pdf(path: &PathBuf) -> Page {
         return error_page(&file_url, "File not found");
     }
 
-    // Extract text using pdf-extract
-    let text = match pdf_extract::extract_text(path) {
-        Ok(t) => t,
+    let doc = match Document::load(path) {
+        Ok(d) => d,
         Err(e) => {
             return error_page(&file_url, &format!("PDF extraction failed: {}", e));
         }
     };
 
-    if text.is_empty() {
-        return error_page(&file_url, "PDF contains no extractable text");
+    let ocr = options.ocr.then_some(path);
+    extract_document(doc, &file_url, path, options, ocr)
+}
+
+/// Extract a PDF downloaded from a URL (same output as local files, without OCR)
+pub fn extract_pdf_bytes(bytes: &[u8], url: &str) -> Page {
+    let doc = match Document::load_mem(bytes) {
+        Ok(d) => d,
+        Err(e) => return error_page(url, &format!("PDF extraction failed: {}", e)),
+    };
+
+    // URL path stands in for the filename in title fallback
+    let name = Url::parse(url)
+        .map(|u| u.path().to_string())
+        .unwrap_or_default();
+    extract_document(doc, url, Path::new(&name), &PdfOptions::default(), None)
+}
+
+/// Build a page from a loaded PDF (`ocr` is the file to OCR when there is no text layer)
+fn extract_document(
+    mut doc: Document,
+    url: &str,
+    path: &Path,
+    options: &PdfOptions,
+    ocr: Option<&Path>,
+) -> Page {
+    let pages = options.pages.as_ref();
+    let password = options.password.as_deref();
+
+    // Many encrypted PDFs only restrict permissions and open with an empty password
+    if doc.is_encrypted() {
+        if let Err(e) = doc.decrypt(password.unwrap_or("")) {
+            let incorrect = matches!(
+                e,
+                pdf_extract::Error::Decryption(DecryptionError::IncorrectPassword)
+            );
+            return error_page(
+                url,
+                &match (incorrect, password) {
+                    (true, None) => "PDF is encrypted; supply --password".to_string(),
+                    (true, Some(_)) => "PDF is encrypted; incorrect --password".to_string(),
+                    (false, _) => format!("PDF decryption failed: {}", e),
+                },
+            );
+        }
     }
 
+    // Document metadata (Info dictionary) is more reliable than text heuristics
+    let metadata = read_metadata(&doc);
+
+    // Extract text page by page, skipping pages outside the selection;
+    // aligned tables come out separately so their rows don't run into prose
+    let (text, page_count, tables) = match extract_pages_text(&doc, pages, options.columns) {
+        Ok(t) => t,
+        Err(e) => {
+            return error_page(url, &format!("PDF extraction failed: {}", e));
+        }
+    };
+
+    if page_count == 0 {
+        return error_page(
+            url,
+            &format!(
+                "No pages in selected range (document has {} pages)",
+                doc.get_pages().len()
+            ),
+        );
+    }
+
+    // Scanned documents have no text layer; OCR them only when asked (slow)
+    let mut alerts = Vec::new();
+    let text = if !text.trim().is_empty() {
+        text
+    } else if let Some(ocr_path) = ocr {
+        let page_nums: Vec<u32> = doc
+            .get_pages()
+            .into_keys()
+            .filter(|n| pages.is_none_or(|p| p.contains(*n)))
+            .collect();
+        match ocr_pages(ocr_path, &page_nums) {
+            Ok(t) if !t.trim().is_empty() => {
+                alerts.push("Text extracted with OCR (no text layer)".to_string());
+                t
+            }
+            Ok(_) => return error_page(url, "PDF contains no extractable text (OCR found none)"),
+            Err(e) => return error_page(url, &format!("OCR failed: {}", e)),
+        }
+    } else {
+        return error_page(url, "PDF contains no extractable text");
+    };
+
     // Parse the extracted text into sections
     let sections = parse_sections(&text);
 
     // Extract any URLs from the text
     let links = extract_links(&text);
 
-    // Try to extract title from first line or filename
-    let title = extract_title(&text, path);
+    // Prefer Info dictionary title, then first line or filename
+    let title = metadata.title.or_else(|| extract_title(&text, path));
 
-    // Calculate total chars
-    let chars: usize = sections
-        .iter()
-        .map(|s| s.content.len() + s.heading.len())
-        .sum();
+    let chars = char_count(&sections);
+    let words = word_count(&sections);
 
     Page {
-        url: file_url,
+        url: url.to_string(),
         status: PageStatus::Ok,
         title,
         site: None,
-        author: extract_author(&text),
-        date: extract_date(&text),
+        author: metadata.author.or_else(|| extract_author(&text)),
+        date: metadata.date.or_else(|| extract_date(&text)),
         doi: extract_doi(&text),
+        canonical: None,
+        lang: detect_text_language(&text),
+        subject: metadata.subject,
+        pages: Some(page_count),
+        meta: None,
+        jsonld: vec![],
+        content_type: None,
+        text: None,
         sections,
         links,
         code: extract_code(&text),
-        alerts: vec![],
+        tables,
+        chunks: vec![],
+        alerts,
         chars,
+        words,
+        reading_minutes: reading_minutes(words),
+        elapsed_ms: None,
     }
 }
 
@@ -110,11 +343,447 @@ fn error_page(url: &str, error: &str) -> Page {
         author: None,
         date: None,
         doi: None,
+        canonical: None,
+        lang: None,
+        subject: None,
+        pages: None,
+        meta: None,
+        jsonld: vec![],
+        content_type: None,
+        text: None,
         sections: vec![],
         links: vec![],
         code: vec![],
+        tables: vec![],
+        chunks: vec![],
         alerts: vec![error.to_string()],
         chars: 0,
+        words: 0,
+        reading_minutes: 0,
+        elapsed_ms: None,
+    }
+}
+
+/// Extract text of the selected pages, returning the text, number of pages read and tables
+fn extract_pages_text(
+    doc: &Document,
+    pages: Option<&PageRanges>,
+    columns: Option<Columns>,
+) -> Result<(String, usize, Vec<Table>), OutputError> {
+    let mut text = String::new();
+    let mut tables = Vec::new();
+    let mut count = 0;
+
+    for page_num in doc.get_pages().into_keys() {
+        if pages.is_some_and(|p| !p.contains(page_num)) {
+            continue;
+        }
+        let mut plain = String::new();
+        let mut output = BothOutputs(PlainTextOutput::new(&mut plain), PositionedText::default());
+        pdf_extract::output_doc_page(doc, &mut output, page_num)?;
+        let BothOutputs(_, positioned) = output;
+
+        let (page_tables, lines) = extract_tables(group_lines(positioned.words));
+        match columns {
+            Some(columns) => {
+                text.push_str(&column_text(&lines, columns));
+                text.push_str("\n\n");
+            }
+            // Keep pdf-extract's own spacing unless table rows have to come out
+            None if page_tables.is_empty() => text.push_str(&plain),
+            None => {
+                let lines: Vec<&[Word]> = lines.iter().map(Vec::as_slice).collect();
+                push_lines(&mut text, &lines);
+                text.push_str("\n\n");
+            }
+        }
+        tables.extend(page_tables);
+        count += 1;
+    }
+
+    tables.truncate(20);
+    Ok((text, count, tables))
+}
+
+/// Feeds one page to two output devices in a single pass
+struct BothOutputs<A, B>(A, B);
+
+impl<A: OutputDev, B: OutputDev> OutputDev for BothOutputs<A, B> {
+    fn begin_page(
+        &mut self,
+        page_num: u32,
+        media_box: &MediaBox,
+        art_box: Option<(f64, f64, f64, f64)>,
+    ) -> Result<(), OutputError> {
+        self.0.begin_page(page_num, media_box, art_box)?;
+        self.1.begin_page(page_num, media_box, art_box)
+    }
+
+    fn end_page(&mut self) -> Result<(), OutputError> {
+        self.0.end_page()?;
+        self.1.end_page()
+    }
+
+    fn output_character(
+        &mut self,
+        trm: &Transform,
+        width: f64,
+        spacing: f64,
+        font_size: f64,
+        char: &str,
+    ) -> Result<(), OutputError> {
+        self.0
+            .output_character(trm, width, spacing, font_size, char)?;
+        self.1
+            .output_character(trm, width, spacing, font_size, char)
+    }
+
+    fn begin_word(&mut self) -> Result<(), OutputError> {
+        self.0.begin_word()?;
+        self.1.begin_word()
+    }
+
+    fn end_word(&mut self) -> Result<(), OutputError> {
+        self.0.end_word()?;
+        self.1.end_word()
+    }
+
+    fn end_line(&mut self) -> Result<(), OutputError> {
+        self.0.end_line()?;
+        self.1.end_line()
+    }
+}
+
+/// Run of glyphs on one baseline, in PDF user space (y grows upwards)
+#[derive(Debug, Clone)]
+struct Word {
+    x0: f64,
+    x1: f64,
+    y: f64,
+    size: f64,
+    text: String,
+}
+
+/// Output device keeping word positions so columns can be read in order
+#[derive(Default)]
+struct PositionedText {
+    words: Vec<Word>,
+    new_word: bool,
+}
+
+impl OutputDev for PositionedText {
+    fn begin_page(
+        &mut self,
+        _page_num: u32,
+        _media_box: &MediaBox,
+        _art_box: Option<(f64, f64, f64, f64)>,
+    ) -> Result<(), OutputError> {
+        Ok(())
+    }
+
+    fn end_page(&mut self) -> Result<(), OutputError> {
+        Ok(())
+    }
+
+    fn output_character(
+        &mut self,
+        trm: &Transform,
+        width: f64,
+        _spacing: f64,
+        font_size: f64,
+        char: &str,
+    ) -> Result<(), OutputError> {
+        let (x, y) = (trm.m31, trm.m32);
+        let size = font_size * (trm.m11 * trm.m22).abs().sqrt();
+        let x1 = x + width * size;
+        if char.trim().is_empty() {
+            self.new_word = true;
+            return Ok(());
+        }
+
+        // Same rules as PlainTextOutput for where one word ends and the next begins
+        match self.words.last_mut() {
+            Some(word)
+                if !self.new_word
+                    && (y - word.y).abs() <= size * 0.5
+                    && x <= word.x1 + size * 0.1
+                    && x >= word.x0 =>
+            {
+                word.text.push_str(char);
+                word.x1 = x1;
+            }
+            _ => self.words.push(Word {
+                x0: x,
+                x1,
+                y,
+                size,
+                text: char.to_string(),
+            }),
+        }
+        self.new_word = false;
+        Ok(())
+    }
+
+    fn begin_word(&mut self) -> Result<(), OutputError> {
+        self.new_word = true;
+        Ok(())
+    }
+
+    fn end_word(&mut self) -> Result<(), OutputError> {
+        Ok(())
+    }
+
+    fn end_line(&mut self) -> Result<(), OutputError> {
+        Ok(())
+    }
+}
+
+/// Lines on one side of a gutter needed before it counts as a column break
+const MIN_COLUMN_LINES: usize = 3;
+
+/// Page text with each column read top to bottom before the next.
+///
+/// Gutters are vertical strips that (almost) no line crosses; lines that
+/// do cross one (titles, full-width figures) end the columns above them.
+fn column_text(lines: &[Vec<Word>], columns: Columns) -> String {
+    let gutters = find_gutters(lines, columns);
+
+    let mut text = String::new();
+    let mut pending: Vec<Vec<&[Word]>> = vec![Vec::new(); gutters.len() + 1];
+    for line in lines {
+        if line
+            .iter()
+            .any(|w| gutters.iter().any(|&(from, to)| w.x0 < from && w.x1 > to))
+        {
+            flush_columns(&mut pending, &mut text);
+            push_lines(&mut text, &[line.as_slice()]);
+            continue;
+        }
+        // Split the line where it crosses gutters
+        let mut rest = line.as_slice();
+        for (column, &(from, _)) in gutters.iter().enumerate() {
+            let split = rest.iter().take_while(|w| w.x1 <= from).count();
+            if split > 0 {
+                pending[column].push(&rest[..split]);
+            }
+            rest = &rest[split..];
+        }
+        if !rest.is_empty() {
+            pending[gutters.len()].push(rest);
+        }
+    }
+    flush_columns(&mut pending, &mut text);
+    text
+}
+
+/// Words grouped into lines (top to bottom), each sorted left to right
+fn group_lines(mut words: Vec<Word>) -> Vec<Vec<Word>> {
+    words.sort_by(|a, b| b.y.total_cmp(&a.y).then(a.x0.total_cmp(&b.x0)));
+    let mut lines: Vec<Vec<Word>> = Vec::new();
+    for word in words {
+        match lines.last_mut() {
+            Some(line) if (line[0].y - word.y).abs() <= word.size.min(line[0].size) * 0.5 => {
+                line.push(word)
+            }
+            _ => lines.push(vec![word]),
+        }
+    }
+    for line in &mut lines {
+        line.sort_by(|a, b| a.x0.total_cmp(&b.x0));
+    }
+    lines
+}
+
+/// Column gutters as (from, to) x ranges, left to right
+fn find_gutters(lines: &[Vec<Word>], columns: Columns) -> Vec<(f64, f64)> {
+    let (Some(left), Some(right)) = (
+        lines.iter().flatten().map(|w| w.x0).reduce(f64::min),
+        lines.iter().flatten().map(|w| w.x1).reduce(f64::max),
+    ) else {
+        return Vec::new();
+    };
+    let mut sizes: Vec<f64> = lines.iter().flatten().map(|w| w.size).collect();
+    sizes.sort_by(f64::total_cmp);
+    let min_width = sizes[sizes.len() / 2];
+
+    // Lines covering each 1pt strip; a few full-width lines may cross a gutter
+    let bins = (right - left).ceil() as usize + 1;
+    let mut coverage = vec![0usize; bins];
+    for line in lines {
+        let mut covered = vec![false; bins];
+        for word in line {
+            let from = (word.x0 - left).floor() as usize;
+            let to = ((word.x1 - left).ceil() as usize).min(bins);
+            covered[from..to].fill(true);
+        }
+        for (count, covered) in coverage.iter_mut().zip(covered) {
+            *count += covered as usize;
+        }
+    }
+    let crossing = lines.len() / 10;
+
+    let mut gutters = Vec::new();
+    let mut start = None;
+    for (i, &count) in coverage.iter().enumerate() {
+        match (count <= crossing, start) {
+            (true, None) => start = Some(i),
+            (false, Some(from)) => {
+                let (from, to) = (left + from as f64, left + i as f64);
+                let lines_beside = |side: fn(&Word, f64, f64) -> bool| {
+                    lines
+                        .iter()
+                        .filter(|l| l.iter().any(|w| side(w, from, to)))
+                        .count()
+                };
+                if to - from >= min_width
+                    && lines_beside(|w, from, _| w.x1 <= from) >= MIN_COLUMN_LINES
+                    && lines_beside(|w, _, to| w.x0 >= to) >= MIN_COLUMN_LINES
+                {
+                    gutters.push((from, to));
+                }
+                start = None;
+            }
+            _ => {}
+        }
+    }
+
+    if let Columns::Count(n) = columns {
+        gutters.sort_by(|a, b| (b.1 - b.0).total_cmp(&(a.1 - a.0)));
+        gutters.truncate(n - 1);
+        gutters.sort_by(|a, b| a.0.total_cmp(&b.0));
+    }
+    gutters
+}
+
+/// Append each column's lines in order, then reset them
+fn flush_columns(pending: &mut [Vec<&[Word]>], text: &mut String) {
+    for column in pending.iter_mut() {
+        if !column.is_empty() {
+            push_lines(text, column);
+            text.push('\n');
+        }
+        column.clear();
+    }
+}
+
+/// Append lines of words, with a blank line where the vertical gap suggests a paragraph break
+fn push_lines(text: &mut String, lines: &[&[Word]]) {
+    let mut last: Option<&Word> = None;
+    for line in lines {
+        let first = &line[0];
+        if last.is_some_and(|l| l.y - first.y > first.size * 1.5) {
+            text.push('\n');
+        }
+        let words: Vec<&str> = line.iter().map(|w| w.text.as_str()).collect();
+        text.push_str(&words.join(" "));
+        text.push('\n');
+        last = Some(first);
+    }
+}
+
+/// Rasterize pages with pdftoppm and recognize them with tesseract
+#[cfg(feature = "ocr")]
+fn ocr_pages(path: &Path, page_nums: &[u32]) -> Result<String> {
+    use anyhow::{bail, Context};
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    let mut text = String::new();
+    for page in page_nums {
+        let page = page.to_string();
+        let image = Command::new("pdftoppm")
+            .args(["-r", "300", "-png", "-singlefile", "-f", &page, "-l", &page])
+            .arg(path)
+            .output()
+            .context("Failed to run pdftoppm (is poppler-utils installed?)")?;
+        if !image.status.success() {
+            bail!("pdftoppm failed on page {}", page);
+        }
+
+        let mut tesseract = Command::new("tesseract")
+            .args(["stdin", "stdout"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .context("Failed to run tesseract (is it installed?)")?;
+        if let Some(mut stdin) = tesseract.stdin.take() {
+            stdin.write_all(&image.stdout)?;
+        }
+        let output = tesseract.wait_with_output()?;
+        if !output.status.success() {
+            bail!("tesseract failed on page {}", page);
+        }
+
+        text.push_str(&String::from_utf8_lossy(&output.stdout));
+        text.push('\n');
+    }
+
+    Ok(text)
+}
+
+#[cfg(not(feature = "ocr"))]
+fn ocr_pages(_path: &Path, _page_nums: &[u32]) -> Result<String> {
+    anyhow::bail!("OCR support not built in (rebuild with --features ocr)")
+}
+
+/// Bibliographic metadata from the PDF Info dictionary
+#[derive(Debug, Default)]
+struct PdfMetadata {
+    title: Option<String>,
+    author: Option<String>,
+    subject: Option<String>,
+    date: Option<String>,
+}
+
+fn read_metadata(doc: &Document) -> PdfMetadata {
+    let Some(info) = doc
+        .trailer
+        .get(b"Info")
+        .ok()
+        .and_then(|obj| doc.dereference(obj).ok())
+        .and_then(|(_, obj)| obj.as_dict().ok())
+    else {
+        return PdfMetadata::default();
+    };
+
+    let field = |key: &[u8]| {
+        info.get_deref(key, doc)
+            .ok()
+            .and_then(|obj| decode_text_string(obj).ok())
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+    };
+
+    PdfMetadata {
+        title: field(b"Title").map(|s| truncate_words(&s, 200)),
+        author: field(b"Author").map(|s| truncate_words(&s, 200)),
+        subject: field(b"Subject").map(|s| truncate_words(&s, 500)),
+        date: field(b"CreationDate")
+            .or_else(|| field(b"ModDate"))
+            .and_then(|d| parse_pdf_date(&d)),
+    }
+}
+
+/// Convert a PDF date (D:YYYYMMDDHHmmSS...) to ISO format (YYYY-MM-DD)
+fn parse_pdf_date(raw: &str) -> Option<String> {
+    let digits: String = raw
+        .trim_start_matches("D:")
+        .chars()
+        .take_while(|c| c.is_ascii_digit())
+        .take(8)
+        .collect();
+
+    match digits.len() {
+        8 => Some(format!(
+            "{}-{}-{}",
+            &digits[..4],
+            &digits[4..6],
+            &digits[6..8]
+        )),
+        6 => Some(format!("{}-{}", &digits[..4], &digits[4..6])),
+        4 => Some(digits),
+        _ => None,
     }
 }
 
@@ -149,8 +818,8 @@ fn parse_sections(text: &str) -> Vec<Section> {
             // Save current section
             sections.push(Section {
                 level: current_level,
-                heading: truncate(&current_heading, 200),
-                content: truncate(current_content.trim(), 10000),
+                heading: truncate_words(&current_heading, 200),
+                content: truncate_words(current_content.trim(), 10000),
             });
 
             // Start new section
@@ -180,8 +849,8 @@ fn parse_sections(text: &str) -> Vec<Section> {
     if !current_content.is_empty() {
         sections.push(Section {
             level: current_level,
-            heading: truncate(&current_heading, 200),
-            content: truncate(current_content.trim(), 10000),
+            heading: truncate_words(&current_heading, 200),
+            content: truncate_words(current_content.trim(), 10000),
         });
     }
 
@@ -190,6 +859,120 @@ fn parse_sections(text: &str) -> Vec<Section> {
     sections
 }
 
+/// Rows in an aligned block before it counts as a table
+const MIN_TABLE_ROWS: usize = 3;
+
+/// Longest cell in a table row; longer runs are prose
+const MAX_CELL_CHARS: usize = 60;
+
+/// Words in a cell from which it reads as running text
+const PROSE_CELL_WORDS: usize = 4;
+
+/// Gap between words, in font sizes, that separates cells (a space is ~0.25)
+const CELL_GAP: f64 = 1.0;
+
+/// Table as rows of cells
+type Table = Vec<Vec<String>>;
+
+/// Cell of a table row: words closer together than `CELL_GAP`
+struct Cell {
+    x0: f64,
+    x1: f64,
+    text: String,
+}
+
+/// Split column-aligned blocks out of a page's lines as tables.
+///
+/// A table is 3+ consecutive lines with the same number of cells whose gaps
+/// line up across every row, so both left- and right-aligned columns match.
+/// Side-by-side prose columns line up too, but their cells are sentences.
+/// Returns the tables and the lines that aren't part of one.
+fn extract_tables(lines: Vec<Vec<Word>>) -> (Vec<Table>, Vec<Vec<Word>>) {
+    let cells: Vec<_> = lines.iter().map(|line| line_cells(line)).collect();
+
+    // Blocks of aligned rows, tracking the gap all rows share at each column boundary
+    let mut blocks = Vec::new();
+    let mut start = 0;
+    let mut gaps: Vec<(f64, f64)> = Vec::new();
+    for (i, row) in cells.iter().enumerate() {
+        let row_gaps: Vec<_> = row.windows(2).map(|w| (w[0].x1, w[1].x0)).collect();
+        let shared: Vec<_> = gaps
+            .iter()
+            .zip(&row_gaps)
+            .map(|(a, b)| (a.0.max(b.0), a.1.min(b.1)))
+            .collect();
+        if !gaps.is_empty()
+            && row_gaps.len() == gaps.len()
+            && shared.iter().all(|(from, to)| from < to)
+        {
+            gaps = shared;
+            continue;
+        }
+
+        blocks.push(start..i);
+        start = i;
+        gaps = if row.len() >= 2 { row_gaps } else { Vec::new() };
+    }
+    blocks.push(start..cells.len());
+
+    let mut tables = Vec::new();
+    let mut in_table = vec![false; lines.len()];
+    for block in blocks {
+        let rows = &cells[block.clone()];
+        let is_table = rows.len() >= MIN_TABLE_ROWS
+            && rows[0].len() >= 2
+            && rows
+                .iter()
+                .flatten()
+                .all(|cell| cell.text.chars().count() <= MAX_CELL_CHARS)
+            && (0..rows[0].len()).all(|column| {
+                let prose = rows
+                    .iter()
+                    .filter(|row| row[column].text.split_whitespace().count() >= PROSE_CELL_WORDS)
+                    .count();
+                prose * 2 <= rows.len()
+            });
+        if !is_table {
+            continue;
+        }
+
+        tables.push(
+            rows.iter()
+                .take(200)
+                .map(|row| row.iter().map(|cell| cell.text.clone()).collect())
+                .collect(),
+        );
+        in_table[block].fill(true);
+    }
+
+    let rest = lines
+        .into_iter()
+        .zip(in_table)
+        .filter_map(|(line, in_table)| (!in_table).then_some(line))
+        .collect();
+    (tables, rest)
+}
+
+/// Words of a line merged into cells, splitting where the gap is a column gap
+fn line_cells(line: &[Word]) -> Vec<Cell> {
+    let mut cells: Vec<Cell> = Vec::new();
+    for word in line {
+        match cells.last_mut() {
+            Some(cell) if word.x0 - cell.x1 < word.size * CELL_GAP => {
+                cell.text.push(' ');
+                cell.text.push_str(&word.text);
+                cell.x1 = word.x1;
+            }
+            _ => cells.push(Cell {
+                x0: word.x0,
+                x1: word.x1,
+                text: word.text.clone(),
+            }),
+        }
+    }
+    cells
+}
+
 fn extract_title(text: &str, path: &Path) -> Option<String> {
     // Try first non-empty line
     let first_line = text
@@ -200,7 +983,7 @@ fn extract_title(text: &str, path: &Path) -> Option<String> {
     // If first line is reasonable length, use it
     if let Some(ref line) = first_line {
         if line.len() < 200 && line.len() > 3 {
-            return Some(truncate(line, 200));
+            return Some(truncate_words(line, 200));
         }
     }
 
@@ -244,7 +1027,7 @@ fn extract_links(text: &str) -> Vec<Link> {
         if !seen.contains(url) {
             seen.insert(url.to_string());
             links.push(Link {
-                text: truncate(url, 100),
+                text: truncate_words(url, 100),
                 url: url.to_string(),
             });
         }
@@ -287,7 +1070,7 @@ fn extract_code(text: &str) -> Vec<CodeBlock> {
                 if source.len() >= 20 {
                     blocks.push(CodeBlock {
                         lang: detect_language(&source),
-                        source: truncate(&source, 5000),
+                        source: truncate_words(&source, 5000),
                     });
                 }
             }
@@ -306,7 +1089,7 @@ fn extract_code(text: &str) -> Vec<CodeBlock> {
         if source.len() >= 20 {
             blocks.push(CodeBlock {
                 lang: detect_language(&source),
-                source: truncate(&source, 5000),
+                source: truncate_words(&source, 5000),
             });
         }
     }
@@ -314,7 +1097,8 @@ fn extract_code(text: &str) -> Vec<CodeBlock> {
     blocks
 }
 
-fn detect_language(code: &str) -> Option<String> {
+/// Guess a code block's language from telltale keywords
+pub(crate) fn detect_language(code: &str) -> Option<String> {
     if code.contains("fn ") && code.contains("->") {
         Some("rust".to_string())
     } else if code.contains("def ") && code.contains(":") {
@@ -330,14 +1114,6 @@ fn detect_language(code: &str) -> Option<String> {
     }
 }
 
-fn truncate(s: &str, max: usize) -> String {
-    if s.len() <= max {
-        s.to_string()
-    } else {
-        format!("{}...", &s[..max - 3])
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -348,6 +1124,229 @@ mod tests {
         assert_eq!(extract_doi(text), Some("10.1234/abc.123".to_string()));
     }
 
+    #[test]
+    fn test_parse_pdf_date() {
+        assert_eq!(
+            parse_pdf_date("D:20240115093000Z"),
+            Some("2024-01-15".to_string())
+        );
+        assert_eq!(
+            parse_pdf_date("D:20240115093000+02'00'"),
+            Some("2024-01-15".to_string())
+        );
+        assert_eq!(parse_pdf_date("D:2024"), Some("2024".to_string()));
+        assert_eq!(parse_pdf_date("garbage"), None);
+    }
+
+    #[test]
+    fn test_read_metadata() {
+        let doc = Document::load("test-data/sample.pdf").unwrap();
+        let meta = read_metadata(&doc);
+        assert_eq!(meta.title, Some("Sample Research Paper".to_string()));
+        assert_eq!(meta.author, Some("Jane Doe".to_string()));
+        assert_eq!(meta.subject, Some("PDF metadata fixture".to_string()));
+        assert_eq!(meta.date, Some("2024-01-15".to_string()));
+    }
+
+    #[test]
+    fn test_extract_pdf_uses_metadata() {
+        let page = extract_pdf(
+            &PathBuf::from("test-data/sample.pdf"),
+            &PdfOptions::default(),
+        );
+        assert_eq!(page.status, PageStatus::Ok);
+        assert_eq!(page.title, Some("Sample Research Paper".to_string()));
+        assert_eq!(page.author, Some("Jane Doe".to_string()));
+        assert_eq!(page.date, Some("2024-01-15".to_string()));
+        assert_eq!(page.pages, Some(3));
+    }
+
+    #[test]
+    fn test_extract_pdf_ocr_skipped_for_text_pdf() {
+        // Text layer present: --ocr must not kick in
+        let page = extract_pdf(
+            &PathBuf::from("test-data/sample.pdf"),
+            &PdfOptions {
+                ocr: true,
+                ..Default::default()
+            },
+        );
+        assert_eq!(page.status, PageStatus::Ok);
+        assert!(page.alerts.is_empty());
+        assert!(page.chars > 0);
+    }
+
+    #[test]
+    fn test_extract_pdf_encrypted() {
+        // RC4-encrypted fixture, user password "secret"
+        let path = PathBuf::from("test-data/encrypted.pdf");
+
+        let page = extract_pdf(&path, &PdfOptions::default());
+        assert_eq!(page.status, PageStatus::Dead);
+        assert_eq!(page.alerts, ["PDF is encrypted; supply --password"]);
+
+        let options = PdfOptions {
+            password: Some("wrong".to_string()),
+            ..Default::default()
+        };
+        let page = extract_pdf(&path, &options);
+        assert_eq!(page.status, PageStatus::Dead);
+        assert_eq!(page.alerts, ["PDF is encrypted; incorrect --password"]);
+
+        let options = PdfOptions {
+            password: Some("secret".to_string()),
+            ..Default::default()
+        };
+        let page = extract_pdf(&path, &options);
+        assert_eq!(page.status, PageStatus::Ok);
+        assert_eq!(page.pages, Some(1));
+        let content: String = page.sections.iter().map(|s| s.content.clone()).collect();
+        assert!(content.contains("encrypted with the password"));
+    }
+
+    #[test]
+    fn test_extract_pdf_bytes() {
+        let bytes = std::fs::read("test-data/sample.pdf").unwrap();
+        let page = extract_pdf_bytes(&bytes, "https://example.com/papers/sample.pdf");
+        assert_eq!(page.status, PageStatus::Ok);
+        assert_eq!(page.url, "https://example.com/papers/sample.pdf");
+        assert_eq!(page.title.as_deref(), Some("Sample Research Paper"));
+        assert_eq!(page.pages, Some(3));
+
+        let page = extract_pdf_bytes(b"<html>not a pdf</html>", "https://example.com/x.pdf");
+        assert_eq!(page.status, PageStatus::Dead);
+    }
+
+    #[test]
+    fn test_parse_page_ranges() {
+        let ranges = parse_page_ranges("1-5,8,10-12").unwrap();
+        assert_eq!(ranges, PageRanges(vec![(1, 5), (8, 8), (10, 12)]));
+        assert!(ranges.contains(3));
+        assert!(ranges.contains(8));
+        assert!(!ranges.contains(9));
+        assert!(ranges.contains(12));
+
+        assert!(parse_page_ranges("5-2")
+            .unwrap_err()
+            .contains("start is after end"));
+        assert!(parse_page_ranges("0").is_err());
+        assert!(parse_page_ranges("1,,3").is_err());
+        assert!(parse_page_ranges("a-b").is_err());
+    }
+
+    #[test]
+    fn test_extract_pdf_page_range() {
+        let options = PdfOptions {
+            pages: Some(parse_page_ranges("2-3").unwrap()),
+            ..Default::default()
+        };
+        let page = extract_pdf(&PathBuf::from("test-data/sample.pdf"), &options);
+        assert_eq!(page.status, PageStatus::Ok);
+        assert_eq!(page.pages, Some(2));
+        let content: String = page.sections.iter().map(|s| s.content.clone()).collect();
+        assert!(!content.contains("three pages of plain text"));
+        assert!(content.contains("methods"));
+
+        let options = PdfOptions {
+            pages: Some(parse_page_ranges("7-9").unwrap()),
+            ..Default::default()
+        };
+        let page = extract_pdf(&PathBuf::from("test-data/sample.pdf"), &options);
+        assert_eq!(page.status, PageStatus::Dead);
+        assert!(page.alerts[0].contains("3 pages"));
+    }
+
+    #[tokio::test]
+    async fn test_extract_pdfs_keeps_order() {
+        let files = vec![
+            PathBuf::from("test-data/two-column.pdf"),
+            PathBuf::from("test-data/missing.pdf"),
+            PathBuf::from("test-data/sample.pdf"),
+        ];
+        let pages = extract_pdfs(files, PdfOptions::default(), 2).await;
+
+        let urls: Vec<_> = pages.iter().map(|p| p.url.as_str()).collect();
+        assert_eq!(
+            urls,
+            [
+                "file://test-data/two-column.pdf",
+                "file://test-data/missing.pdf",
+                "file://test-data/sample.pdf"
+            ]
+        );
+        // A failed file doesn't stop the others
+        assert_eq!(pages[0].status, PageStatus::Ok);
+        assert_eq!(pages[1].status, PageStatus::Dead);
+        assert_eq!(pages[2].status, PageStatus::Ok);
+        assert_eq!(pages[2].title, Some("Sample Research Paper".to_string()));
+    }
+
+    #[test]
+    fn test_parse_columns() {
+        assert_eq!(parse_columns("auto"), Ok(Columns::Auto));
+        assert_eq!(parse_columns("2"), Ok(Columns::Count(2)));
+        assert!(parse_columns("0").is_err());
+        assert!(parse_columns("two").is_err());
+    }
+
+    #[test]
+    fn test_extract_columns_reading_order() {
+        // Rows of both columns are interleaved in the content stream
+        let doc = Document::load("test-data/two-column.pdf").unwrap();
+        let position = |text: &str, needle: &str| text.find(needle).unwrap();
+
+        let (plain, _, _) = extract_pages_text(&doc, None, None).unwrap();
+        assert!(position(&plain, "picks up the") < position(&plain, "Readers finish this column"));
+
+        for columns in [Columns::Auto, Columns::Count(2)] {
+            let (text, pages, _) = extract_pages_text(&doc, None, Some(columns)).unwrap();
+            assert_eq!(pages, 1);
+            let order: Vec<_> = [
+                "TWO COLUMN LAYOUT",
+                "The left column starts the article",
+                "Readers finish this column first.",
+                "The right column picks up the",
+                "This line is read last of all.",
+            ]
+            .iter()
+            .map(|needle| position(&text, needle))
+            .collect();
+            assert!(order.windows(2).all(|w| w[0] < w[1]), "{}", text);
+        }
+
+        // One column: plain top-to-bottom, left-to-right lines
+        let (text, _, _) = extract_pages_text(&doc, None, Some(Columns::Count(1))).unwrap();
+        assert!(text.contains("The left column starts the article The right column picks up the"));
+    }
+
+    #[test]
+    fn test_extract_tables() {
+        // Each cell is placed on its own, numbers right-aligned
+        let page = extract_pdf(Path::new("test-data/table.pdf"), &PdfOptions::default());
+        assert_eq!(page.tables.len(), 1, "{:?}", page.tables);
+        let table = &page.tables[0];
+        assert_eq!(table.len(), 4);
+        assert_eq!(table[0], ["Region", "Q1 2024", "Q2 2024", "Change"]);
+        assert_eq!(table[1], ["North America", "1,204", "1,388", "15.3%"]);
+        assert_eq!(table[2], ["Europe", "987", "1,002", "1.5%"]);
+
+        let content: String = page.sections.iter().map(|s| s.content.clone()).collect();
+        assert!(!content.contains("1,388"), "{}", content);
+        assert!(content.contains("Totals exclude"), "{}", content);
+        assert!(content.contains("as the table below shows"), "{}", content);
+    }
+
+    #[test]
+    fn test_extract_tables_ignores_prose() {
+        // Side-by-side prose columns line up, but their cells are sentences
+        let doc = Document::load("test-data/two-column.pdf").unwrap();
+        for columns in [None, Some(Columns::Auto)] {
+            let (text, _, tables) = extract_pages_text(&doc, None, columns).unwrap();
+            assert!(tables.is_empty(), "{:?}", tables);
+            assert!(text.contains("This line is read last of all."));
+        }
+    }
+
     #[test]
     fn test_extract_links() {
         let text = "See https://example.com and https://foo.bar/path for details.";
@@ -0,0 +1,233 @@
+//! prune command: Remove references no longer cited in any file
+//!
+//! Re-scans each file in `cited_in` and drops citations whose file no longer
+//! contains the URL (or no longer exists). References left with no citations
+//! are removed. Entries that never had a `cited_in` file are kept. BibTeX
+//! files (from `import`) are re-read as entries, so DOI-only ones still match.
+//!
+//! Relative `cited_in` paths resolve against the references file's directory
+//! (falling back to the working directory), and URLs compare normalized.
+
+use crate::import::bibtex_urls;
+use crate::normalize::{normalize_url, NormalizeOptions};
+use crate::progress;
+use crate::scan::extract_doc_urls;
+use crate::schema::ReferencesFile;
+use anyhow::{Context, Result};
+use clap::Args;
+use serde::Serialize;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+#[derive(Args)]
+pub struct PruneArgs {
+    /// Path to references.yaml file
+    #[arg(default_value = "references.yaml")]
+    pub file: PathBuf,
+
+    /// Report what would be pruned without modifying the file
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PruneOutput {
+    pub file: String,
+    pub pruned: Vec<String>,
+    /// Cited files that no longer exist
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub missing_files: Vec<String>,
+    pub total: usize,
+    pub dry_run: bool,
+}
+
+pub async fn run_prune(args: PruneArgs) -> Result<()> {
+    let mut refs_file = ReferencesFile::load(&args.file).await?;
+
+    // Re-scan every file that is cited somewhere
+    let cited_files: BTreeSet<String> = refs_file
+        .references
+        .iter()
+        .flat_map(|r| r.cited_in.iter().cloned())
+        .collect();
+
+    // A loose key: any spelling scan could have recorded still matches
+    let normalize = NormalizeOptions {
+        strip_fragment: true,
+        strip_tracking: true,
+        ..Default::default()
+    };
+    let base = args.file.parent().unwrap_or(Path::new(""));
+    let mut file_urls: HashMap<String, HashSet<String>> = HashMap::new();
+    let mut missing_files = Vec::new();
+    for file in cited_files {
+        let Some(path) = resolve_cited(base, &file) else {
+            missing_files.push(file);
+            continue;
+        };
+        let content = tokio::fs::read_to_string(&path)
+            .await
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let urls: Vec<String> = if path
+            .extension()
+            .is_some_and(|e| e.eq_ignore_ascii_case("bib"))
+        {
+            bibtex_urls(&content)
+        } else {
+            extract_doc_urls(&content, &path)
+                .into_iter()
+                .map(|f| f.url)
+                .collect()
+        };
+        let urls = urls.iter().map(|u| normalize_url(u, &normalize)).collect();
+        file_urls.insert(file, urls);
+    }
+
+    let pruned = prune_references(&mut refs_file, &file_urls, &normalize);
+    progress!(
+        "Pruned {} reference{} ({} remaining)",
+        pruned.len(),
+        if pruned.len() == 1 { "" } else { "s" },
+        refs_file.references.len()
+    );
+
+    if !args.dry_run {
+        refs_file.save(&args.file).await?;
+    } else {
+        progress!("Dry run - file not modified");
+    }
+
+    let output = PruneOutput {
+        file: args.file.display().to_string(),
+        pruned,
+        missing_files,
+        total: refs_file.references.len(),
+        dry_run: args.dry_run,
+    };
+    println!("{}", serde_json::to_string(&output)?);
+
+    Ok(())
+}
+
+/// A cited file on disk: relative paths are tried against the references
+/// file's directory, then the working directory
+fn resolve_cited(base: &Path, file: &str) -> Option<PathBuf> {
+    [base.join(file), PathBuf::from(file)]
+        .into_iter()
+        .find(|path| path.is_file())
+}
+
+/// Drop stale citations and uncited references, returning the pruned URLs
+///
+/// `file_urls` maps each readable cited file to the (normalized) URLs it
+/// contains; files missing from the map are treated as deleted.
+fn prune_references(
+    refs_file: &mut ReferencesFile,
+    file_urls: &HashMap<String, HashSet<String>>,
+    normalize: &NormalizeOptions,
+) -> Vec<String> {
+    let mut pruned = Vec::new();
+
+    refs_file.references.retain_mut(|reference| {
+        if reference.cited_in.is_empty() {
+            return true;
+        }
+        let url = normalize_url(&reference.url, normalize);
+        reference
+            .cited_in
+            .retain(|file| file_urls.get(file).is_some_and(|urls| urls.contains(&url)));
+        if reference.cited_in.is_empty() {
+            pruned.push(reference.url.clone());
+            return false;
+        }
+        true
+    });
+
+    refs_file.meta.total_links = refs_file.references.len();
+    pruned
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::{Meta, Reference, Status};
+
+    fn reference(url: &str, cited_in: &[&str]) -> Reference {
+        Reference {
+            url: url.to_string(),
+            title: url.to_string(),
+            categories: vec!["general".to_string()],
+            tags: vec![],
+            cited_in: cited_in.iter().map(|s| s.to_string()).collect(),
+            status: Status::Ok,
+            verified: None,
+            first_seen: None,
+            last_seen: None,
+            notes: None,
+            content_hash: None,
+        }
+    }
+
+    #[test]
+    fn test_prune_references() {
+        let mut refs_file = ReferencesFile {
+            meta: Meta {
+                project: None,
+                created: "2025-01-01".to_string(),
+                last_verified: None,
+                tool: "ref".to_string(),
+                total_links: 5,
+            },
+            references: vec![
+                reference("https://kept.com", &["a.md", "b.md"]),
+                reference("https://removed.com", &["a.md"]),
+                reference("https://deleted-file.com", &["gone.md"]),
+                reference("https://manual.com", &[]),
+                reference("https://moved.com", &["a.md", "gone.md"]),
+                reference("https://slash.com/page/", &["a.md"]),
+            ],
+        };
+        let normalize = NormalizeOptions {
+            strip_tracking: true,
+            ..Default::default()
+        };
+        let normalized = |urls: &[&str]| -> HashSet<String> {
+            urls.iter().map(|u| normalize_url(u, &normalize)).collect()
+        };
+        let file_urls = HashMap::from([
+            (
+                "a.md".to_string(),
+                normalized(&[
+                    "https://kept.com",
+                    "https://moved.com",
+                    "https://slash.com/page?utm_source=x",
+                ]),
+            ),
+            ("b.md".to_string(), HashSet::new()),
+        ]);
+
+        let pruned = prune_references(&mut refs_file, &file_urls, &normalize);
+
+        assert_eq!(
+            pruned,
+            vec!["https://removed.com", "https://deleted-file.com"]
+        );
+        let urls: Vec<_> = refs_file
+            .references
+            .iter()
+            .map(|r| r.url.as_str())
+            .collect();
+        assert_eq!(
+            urls,
+            vec![
+                "https://kept.com",
+                "https://manual.com",
+                "https://moved.com",
+                "https://slash.com/page/"
+            ]
+        );
+        assert_eq!(refs_file.references[0].cited_in, vec!["a.md"]);
+        assert_eq!(refs_file.references[2].cited_in, vec!["a.md"]);
+        assert_eq!(refs_file.meta.total_links, 4);
+    }
+}
@@ -1,14 +1,26 @@
 //! refresh-data command: Extract live data from URLs
 //!
-//! LLM-optimized output - JSON compact only.
+//! LLM-optimized output - compact JSON (or YAML with --format yaml).
 
-use crate::browser::BrowserPool;
-use crate::extract::{extract_amounts, extract_percentages, AmountMatch};
+use crate::browser::{parse_connect, parse_lang, BrowserPool, WaitUntil};
+use crate::events;
+use crate::extract::{
+    extract_amounts, extract_emails, extract_percentages, extract_phones, extract_urls, AmountMatch,
+};
+use crate::output::OutputFormat;
+use crate::progress;
+use crate::util::{bare_host, truncate};
 use anyhow::{Context, Result};
 use clap::Args;
+use futures::StreamExt;
 use serde::Serialize;
+use std::future::Future;
+use std::time::Duration;
 use tokio::fs;
 
+/// Delay between requests to the same host
+const HOST_DELAY: Duration = Duration::from_secs(1);
+
 #[derive(Args)]
 pub struct RefreshDataArgs {
     /// Extract data from a single URL
@@ -19,14 +31,110 @@ pub struct RefreshDataArgs {
     #[arg(value_name = "FILE")]
     file: Option<String>,
 
+    /// Hosts to extract from in parallel (URLs on one host still run one at a time)
+    #[arg(long, short, default_value = "4")]
+    pub(crate) parallel: usize,
+
     /// Timeout per URL in milliseconds
     #[arg(long, default_value = "20000")]
-    timeout: u64,
+    pub(crate) timeout: u64,
+
+    /// Attach to a running Chrome (ws:// or http://host:9222) instead of launching one
+    #[arg(
+        long,
+        visible_alias = "remote-debugging-url",
+        env = "REF_BROWSER_URL",
+        value_parser = parse_connect
+    )]
+    pub(crate) connect: Option<String>,
+
+    /// Route traffic and DNS through a SOCKS5 proxy (host:port), no local lookups
+    #[arg(long, value_name = "HOST:PORT", conflicts_with = "connect")]
+    pub(crate) socks5: Option<String>,
+
+    /// User agent string, or "chrome-latest" for the browser's own version
+    #[arg(long, value_name = "UA")]
+    pub(crate) user_agent: Option<String>,
+
+    /// Ask for pages in this language: Accept-Language and navigator.language (e.g. de-DE)
+    #[arg(long, value_name = "TAG", value_parser = parse_lang)]
+    lang: Option<String>,
+
+    /// Load images, media, fonts and stylesheets (blocked by default for speed)
+    #[arg(long)]
+    load_resources: bool,
+
+    /// Page lifecycle event that ends navigation
+    #[arg(long, value_enum, default_value = "load")]
+    wait_until: WaitUntil,
+
+    /// Report format
+    #[arg(long, value_enum, default_value = "json")]
+    format: OutputFormat,
+
+    /// Print only the ok/failed counts, no per-URL `results`
+    #[arg(long)]
+    summary_only: bool,
 }
 
 /// Configuration for refresh-data
 pub struct RefreshConfig {
     pub timeout_ms: u64,
+    /// Hosts processed concurrently (browser tabs)
+    pub parallel: usize,
+    /// Site extractors, tried in order (first match wins)
+    pub extractors: Vec<Box<dyn Extractor>>,
+    /// DevTools URL of a running Chrome to attach to (None = launch one)
+    pub connect: Option<String>,
+    /// SOCKS5 proxy (host:port) for a launched Chrome
+    pub socks5: Option<String>,
+    /// User agent override ("chrome-latest" = browser's own)
+    pub user_agent: Option<String>,
+    /// Load images, media, fonts and stylesheets (blocked by default)
+    pub load_resources: bool,
+    /// Lifecycle event that ends navigation
+    pub wait_until: WaitUntil,
+    /// Language tag for Accept-Language and navigator.language
+    pub lang: Option<String>,
+}
+
+/// A site-specific data extractor
+///
+/// Implement this and add it to `RefreshConfig::extractors` to support a new
+/// site without touching the dispatch code.
+pub trait Extractor: Send + Sync {
+    /// Extractor type name, reported as `type` in the output
+    fn name(&self) -> &'static str;
+    /// Whether this extractor handles the given URL
+    fn matches(&self, url: &str) -> bool;
+    /// Extract data from the rendered page HTML
+    fn extract(&self, url: &str, html: &str) -> ExtractedData;
+}
+
+/// Built-in extractors, most specific first, generic fallback last
+pub fn default_extractors() -> Vec<Box<dyn Extractor>> {
+    vec![
+        Box::new(InstagramExtractor),
+        Box::new(StatistaExtractor),
+        Box::new(YoutubeExtractor),
+        Box::new(XExtractor),
+        Box::new(MarketExtractor),
+        Box::new(GenericExtractor),
+    ]
+}
+
+/// Name of the fallback extractor, which handles any page
+const GENERIC: &str = "generic";
+
+/// Find the first extractor that handles a URL
+fn find_extractor<'a>(
+    extractors: &'a [Box<dyn Extractor>],
+    url: &str,
+) -> Option<&'a dyn Extractor> {
+    extractors
+        .iter()
+        .find(|e| e.matches(url))
+        .map(|e| e.as_ref())
 }
 
 /// Extracted data from a URL (compact)
@@ -42,11 +150,22 @@ pub struct ExtractedData {
     pub amounts: Option<Vec<AmountMatch>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub percentages: Option<Vec<String>>,
+    /// Contact emails (generic pages only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub emails: Option<Vec<String>>,
+    /// Contact phone numbers (generic pages only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub phones: Option<Vec<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub followers: Option<String>,
+    /// Accounts followed (X profiles)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub following: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub username: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub videos: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
 }
 
@@ -58,46 +177,72 @@ pub struct RefreshReport {
     pub results: Vec<ExtractedData>,
 }
 
+/// A report without its per-URL results (--summary-only)
+#[derive(Debug, Serialize)]
+pub struct RefreshSummary {
+    pub ok: usize,
+    pub failed: usize,
+}
+
+impl RefreshReport {
+    pub fn summary(&self) -> RefreshSummary {
+        RefreshSummary {
+            ok: self.ok,
+            failed: self.failed,
+        }
+    }
+}
+
 /// Run the refresh-data command
 pub async fn run_refresh_data(args: RefreshDataArgs) -> Result<()> {
-    let urls = get_extractable_urls(&args).await?;
+    let config = RefreshConfig {
+        timeout_ms: args.timeout,
+        parallel: args.parallel.max(1),
+        extractors: default_extractors(),
+        connect: args.connect.clone(),
+        socks5: args.socks5.clone(),
+        user_agent: args.user_agent.clone(),
+        load_resources: args.load_resources,
+        wait_until: args.wait_until,
+        lang: args.lang.clone(),
+    };
 
+    let urls = get_extractable_urls(&args, &config).await?;
     if urls.is_empty() {
         eprintln!("No extractable URLs found.");
         std::process::exit(1);
     }
 
-    eprintln!("Extracting data from {} URLs...", urls.len());
-
-    let config = RefreshConfig {
-        timeout_ms: args.timeout,
-    };
-
+    progress!("Extracting data from {} URLs...", urls.len());
+    events::start(urls.len());
     let report = refresh_data(&urls, &config).await?;
+    events::done();
 
-    // Output compact JSON to stdout
-    println!("{}", serde_json::to_string(&report)?);
+    if args.summary_only {
+        print!("{}", args.format.render(&report.summary())?);
+    } else {
+        print!("{}", args.format.render(&report)?);
+    }
 
-    eprintln!("Done: {}/{} OK", report.ok, report.ok + report.failed);
+    progress!("Done: {}/{} OK", report.ok, report.ok + report.failed);
 
     Ok(())
 }
 
-/// Determine extractor type from URL (auto-detect)
-fn get_extractor_type(url: &str) -> &'static str {
-    if url.contains("instagram.com") {
-        "instagram"
-    } else if url.contains("statista.com") {
-        "statista"
-    } else {
-        "generic"
-    }
+/// Name of the extractor that will handle a URL, from the same registry as dispatch
+fn get_extractor_type(extractors: &[Box<dyn Extractor>], url: &str) -> &'static str {
+    find_extractor(extractors, url)
+        .map(|e| e.name())
+        .unwrap_or(GENERIC)
 }
 
 /// Get URLs to extract from
-async fn get_extractable_urls(args: &RefreshDataArgs) -> Result<Vec<(String, String)>> {
+async fn get_extractable_urls(
+    args: &RefreshDataArgs,
+    config: &RefreshConfig,
+) -> Result<Vec<(String, String)>> {
     if let Some(url) = &args.url {
-        let ext_type = get_extractor_type(url);
+        let ext_type = get_extractor_type(&config.extractors, url);
         return Ok(vec![(url.clone(), ext_type.to_string())]);
     }
 
@@ -106,7 +251,7 @@ async fn get_extractable_urls(args: &RefreshDataArgs) -> Result<Vec<(String, Str
             .await
             .with_context(|| format!("Failed to read file: {}", file))?;
 
-        return Ok(extract_extractable_urls(&content));
+        return Ok(extract_extractable_urls(&content, &config.extractors));
     }
 
     eprintln!("Usage:");
@@ -115,31 +260,19 @@ async fn get_extractable_urls(args: &RefreshDataArgs) -> Result<Vec<(String, Str
     std::process::exit(1);
 }
 
-/// Extract URLs that have extractors
-fn extract_extractable_urls(content: &str) -> Vec<(String, String)> {
-    use regex::Regex;
-    use std::collections::HashSet;
-
-    let patterns = [
-        (Regex::new(r"https?://(?:www\.)?instagram\.com/[^\s\)\]]+").unwrap(), "instagram"),
-        (Regex::new(r"https?://(?:www\.)?statista\.com/[^\s\)\]]+").unwrap(), "statista"),
-        (Regex::new(r"https?://(?:www\.)?(?:influencermarketinghub|emarketer|techcrunch)\.com/[^\s\)\]]+").unwrap(), "market"),
-    ];
-
-    let mut seen = HashSet::new();
-    let mut urls = Vec::new();
-
-    for (re, ext_type) in &patterns {
-        for mat in re.find_iter(content) {
-            let url = mat.as_str().trim_end_matches([',', '.', ')', ']']);
-            if !seen.contains(url) {
-                seen.insert(url.to_string());
-                urls.push((url.to_string(), ext_type.to_string()));
-            }
-        }
-    }
-
-    urls
+/// URLs in `content` handled by a site-specific extractor (the generic
+/// fallback matches everything, so it doesn't count)
+fn extract_extractable_urls(
+    content: &str,
+    extractors: &[Box<dyn Extractor>],
+) -> Vec<(String, String)> {
+    extract_urls(content)
+        .into_iter()
+        .filter_map(|url| {
+            let ext_type = get_extractor_type(extractors, &url);
+            (ext_type != GENERIC).then(|| (url, ext_type.to_string()))
+        })
+        .collect()
 }
 
 /// Extract data from multiple URLs
@@ -147,31 +280,40 @@ pub async fn refresh_data(
     urls: &[(String, String)],
     config: &RefreshConfig,
 ) -> Result<RefreshReport> {
-    let pool = BrowserPool::new(1).await?; // Sequential for rate limiting
-    let mut results = Vec::with_capacity(urls.len());
-    let mut ok_count = 0;
-    let mut failed_count = 0;
+    let pool = BrowserPool::launch_or_connect(
+        config.parallel,
+        config.connect.as_deref(),
+        config.socks5.as_deref(),
+    )
+    .await?
+    .with_user_agent(config.user_agent.as_deref())
+    .await?
+    .with_resources(config.load_resources)
+    .with_lang(config.lang.as_deref())
+    .with_wait_until(config.wait_until);
 
-    for (url, ext_type) in urls {
-        eprintln!("  -> [{}] {}", ext_type, truncate(url, 50));
-
-        let page = pool.new_page().await?;
-        let result = extract_from_page(&page, url, ext_type, config.timeout_ms).await;
-
-        if result.success {
-            ok_count += 1;
-        } else {
-            failed_count += 1;
+    let results = for_each_host(urls, config.parallel, HOST_DELAY, |url, ext_type| {
+        let pool = &pool;
+        async move {
+            progress!("  -> [{}] {}", ext_type, truncate(url, 50));
+            // A tab that won't open fails this URL, not the run
+            let data = match pool.new_page().await {
+                Ok(page) => extract_from_page(&page, url, ext_type, config).await,
+                Err(e) => ExtractedData::failed(url, ext_type, format!("Browser error: {}", e)),
+            };
+            events::item(url, data.success);
+            Ok(data)
         }
-
-        results.push(result);
-
-        // Rate limit
-        tokio::time::sleep(std::time::Duration::from_secs(1)).await;
-    }
+    })
+    .await
+    .into_iter()
+    .collect::<Result<Vec<_>>>()?;
 
     pool.close().await?;
 
+    let ok_count = results.iter().filter(|r| r.success).count();
+    let failed_count = results.len() - ok_count;
+
     Ok(RefreshReport {
         ok: ok_count,
         failed: failed_count,
@@ -179,49 +321,218 @@ pub async fn refresh_data(
     })
 }
 
+/// Run `task` for each URL: different hosts concurrently (at most `parallel`),
+/// one host's URLs one at a time with `delay` between them
+///
+/// Results are returned in input order.
+async fn for_each_host<'a, T, F, Fut>(
+    urls: &'a [(String, String)],
+    parallel: usize,
+    delay: Duration,
+    task: F,
+) -> Vec<T>
+where
+    F: Fn(&'a str, &'a str) -> Fut,
+    Fut: Future<Output = T>,
+{
+    let task = &task;
+    let mut slots: Vec<Option<T>> = urls.iter().map(|_| None).collect();
+
+    let mut groups =
+        futures::stream::iter(group_by_host(urls).into_iter().map(|indices| async move {
+            let mut out = Vec::with_capacity(indices.len());
+            for (n, idx) in indices.into_iter().enumerate() {
+                if n > 0 {
+                    tokio::time::sleep(delay).await;
+                }
+                let (url, ext_type) = &urls[idx];
+                out.push((idx, task(url, ext_type).await));
+            }
+            out
+        }))
+        .buffer_unordered(parallel.max(1));
+
+    while let Some(group) = groups.next().await {
+        for (idx, result) in group {
+            slots[idx] = Some(result);
+        }
+    }
+
+    slots.into_iter().flatten().collect()
+}
+
+/// Indices of `urls` grouped by host, in order of first appearance
+fn group_by_host(urls: &[(String, String)]) -> Vec<Vec<usize>> {
+    let mut hosts: Vec<String> = Vec::new();
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+
+    for (idx, (url, _)) in urls.iter().enumerate() {
+        let host = bare_host(url).unwrap_or_else(|| url.clone());
+        match hosts.iter().position(|h| *h == host) {
+            Some(i) => groups[i].push(idx),
+            None => {
+                hosts.push(host);
+                groups.push(vec![idx]);
+            }
+        }
+    }
+
+    groups
+}
+
 /// Extract data from a single page
 async fn extract_from_page(
     page: &crate::browser::BrowserPage,
     url: &str,
     ext_type: &str,
-    timeout_ms: u64,
+    config: &RefreshConfig,
 ) -> ExtractedData {
-    let nav_result = page.goto(url, timeout_ms).await;
+    let nav_result = page.goto(url, config.timeout_ms).await;
     if let Err(e) = nav_result {
-        return ExtractedData {
+        return ExtractedData::failed(url, ext_type, e.to_string());
+    }
+
+    let content = match page.content().await {
+        Ok(c) => c,
+        Err(e) => return ExtractedData::failed(url, ext_type, e.to_string()),
+    };
+
+    match find_extractor(&config.extractors, url) {
+        Some(extractor) => extractor.extract(url, &content),
+        None => extract_generic(url, &content),
+    }
+}
+
+impl ExtractedData {
+    /// Failed extraction with an error message
+    pub fn failed(url: &str, ext_type: &str, error: String) -> Self {
+        Self {
             url: url.to_string(),
             extractor_type: ext_type.to_string(),
             success: false,
             title: None,
             amounts: None,
             percentages: None,
+            emails: None,
+            phones: None,
             followers: None,
+            following: None,
             username: None,
-            error: Some(e.to_string()),
-        };
+            videos: None,
+            error: Some(error),
+        }
     }
+}
 
-    let content = match page.content().await {
-        Ok(c) => c,
-        Err(e) => {
-            return ExtractedData {
-                url: url.to_string(),
-                extractor_type: ext_type.to_string(),
-                success: false,
-                title: None,
-                amounts: None,
-                percentages: None,
-                followers: None,
-                username: None,
-                error: Some(e.to_string()),
-            };
+/// Instagram profiles: follower count and username
+pub struct InstagramExtractor;
+
+impl Extractor for InstagramExtractor {
+    fn name(&self) -> &'static str {
+        "instagram"
+    }
+
+    fn matches(&self, url: &str) -> bool {
+        url.contains("instagram.com")
+    }
+
+    fn extract(&self, url: &str, html: &str) -> ExtractedData {
+        extract_instagram(url, html)
+    }
+}
+
+/// Statista pages: amounts, percentages and title
+pub struct StatistaExtractor;
+
+impl Extractor for StatistaExtractor {
+    fn name(&self) -> &'static str {
+        "statista"
+    }
+
+    fn matches(&self, url: &str) -> bool {
+        url.contains("statista.com")
+    }
+
+    fn extract(&self, url: &str, html: &str) -> ExtractedData {
+        extract_statista(url, html)
+    }
+}
+
+/// YouTube channels: subscriber count, video count, title and handle
+pub struct YoutubeExtractor;
+
+impl Extractor for YoutubeExtractor {
+    fn name(&self) -> &'static str {
+        "youtube"
+    }
+
+    fn matches(&self, url: &str) -> bool {
+        url.contains("youtube.com/@") || url.contains("youtube.com/channel/")
+    }
+
+    fn extract(&self, url: &str, html: &str) -> ExtractedData {
+        extract_youtube(url, html)
+    }
+}
+
+/// X (Twitter) profiles: followers, following, display name and handle
+pub struct XExtractor;
+
+impl Extractor for XExtractor {
+    fn name(&self) -> &'static str {
+        "x"
+    }
+
+    fn matches(&self, url: &str) -> bool {
+        is_x_profile(url)
+    }
+
+    fn extract(&self, url: &str, html: &str) -> ExtractedData {
+        extract_x(url, html)
+    }
+}
+
+/// Market research and tech news sites: amounts, percentages and title
+pub struct MarketExtractor;
+
+impl Extractor for MarketExtractor {
+    fn name(&self) -> &'static str {
+        "market"
+    }
+
+    fn matches(&self, url: &str) -> bool {
+        bare_host(url).is_some_and(|host| {
+            [
+                "influencermarketinghub.com",
+                "emarketer.com",
+                "techcrunch.com",
+            ]
+            .contains(&host.as_str())
+        })
+    }
+
+    fn extract(&self, url: &str, html: &str) -> ExtractedData {
+        ExtractedData {
+            extractor_type: self.name().to_string(),
+            ..extract_generic(url, html)
         }
-    };
+    }
+}
 
-    match ext_type {
-        "instagram" => extract_instagram(url, &content),
-        "statista" => extract_statista(url, &content),
-        _ => extract_generic(url, &content),
+/// Fallback for any page: amounts, percentages and title
+pub struct GenericExtractor;
+
+impl Extractor for GenericExtractor {
+    fn name(&self) -> &'static str {
+        GENERIC
+    }
+
+    fn matches(&self, _url: &str) -> bool {
+        true
+    }
+
+    fn extract(&self, url: &str, html: &str) -> ExtractedData {
+        extract_generic(url, html)
     }
 }
 
@@ -245,12 +556,201 @@ fn extract_instagram(url: &str, content: &str) -> ExtractedData {
         title: None,
         amounts: None,
         percentages: None,
+        emails: None,
+        phones: None,
         followers,
+        following: None,
         username,
+        videos: None,
         error: None,
     }
 }
 
+fn extract_youtube(url: &str, content: &str) -> ExtractedData {
+    use regex::Regex;
+
+    let data_re = Regex::new(r"(?s)ytInitialData\s*=\s*(\{.*?\});\s*</script>").unwrap();
+    let data: Option<serde_json::Value> = data_re
+        .captures(content)
+        .and_then(|c| serde_json::from_str(&c[1]).ok());
+
+    let Some(data) = data else {
+        // Some regions get a cookie consent interstitial instead of the channel page
+        let error = if content.contains("consent.youtube.com")
+            || content.contains("Before you continue to YouTube")
+        {
+            "YouTube consent page shown instead of channel (region requires cookie consent)"
+        } else {
+            "ytInitialData not found in page"
+        };
+        return ExtractedData::failed(url, "youtube", error.to_string());
+    };
+
+    // Header texts like "1.2M subscribers" / "345 videos" move around between
+    // layouts, so scan every text node rather than relying on a fixed path
+    let mut texts = Vec::new();
+    collect_youtube_texts(&data, &mut texts);
+
+    let count_re =
+        |noun: &str| Regex::new(&format!(r"^([0-9][0-9,.]*[KMB]?)\s+{}s?$", noun)).unwrap();
+    let subscribers_re = count_re("subscriber");
+    let videos_re = count_re("video");
+    let find_count = |re: &Regex| {
+        texts
+            .iter()
+            .find_map(|t| re.captures(t.trim()).map(|c| c[1].to_string()))
+    };
+
+    let metadata = &data["metadata"]["channelMetadataRenderer"];
+    let title = metadata["title"].as_str().map(|s| s.to_string());
+
+    // Handle from URL (@handle), falling back to the vanity URL in metadata
+    let handle = url
+        .split('/')
+        .find(|seg| seg.starts_with('@'))
+        .or_else(|| {
+            metadata["vanityChannelUrl"]
+                .as_str()
+                .and_then(|v| v.rsplit('/').next())
+                .filter(|seg| seg.starts_with('@'))
+        })
+        .map(|s| s.split(['?', '#']).next().unwrap_or(s).to_string());
+
+    ExtractedData {
+        url: url.to_string(),
+        extractor_type: "youtube".to_string(),
+        success: true,
+        title,
+        amounts: None,
+        percentages: None,
+        emails: None,
+        phones: None,
+        followers: find_count(&subscribers_re),
+        following: None,
+        username: handle,
+        videos: find_count(&videos_re),
+        error: None,
+    }
+}
+
+/// Profile URL on x.com/twitter.com (`/handle`, not `/handle/status/..` or `/search`)
+fn is_x_profile(url: &str) -> bool {
+    const RESERVED: [&str; 10] = [
+        "home", "i", "search", "explore", "settings", "intent", "share", "hashtag", "login",
+        "messages",
+    ];
+    let Ok(parsed) = url::Url::parse(url) else {
+        return false;
+    };
+    let host = parsed.host_str().unwrap_or("");
+    let host = host
+        .strip_prefix("www.")
+        .or_else(|| host.strip_prefix("mobile."))
+        .unwrap_or(host);
+    if host != "x.com" && host != "twitter.com" {
+        return false;
+    }
+    let segments: Vec<&str> = parsed.path().split('/').filter(|s| !s.is_empty()).collect();
+    match segments.as_slice() {
+        [handle] => {
+            handle.len() <= 15
+                && handle
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_')
+                && !RESERVED.contains(&handle.to_lowercase().as_str())
+        }
+        _ => false,
+    }
+}
+
+/// X embeds a schema.org ProfilePage for logged-out visitors; without it we
+/// got the login wall, which must not be reported as zero followers
+fn extract_x(url: &str, content: &str) -> ExtractedData {
+    let doc = scraper::Html::parse_document(content);
+    let sel = scraper::Selector::parse(r#"script[type="application/ld+json"]"#).unwrap();
+    let person = doc
+        .select(&sel)
+        .filter_map(|el| serde_json::from_str::<serde_json::Value>(&el.inner_html()).ok())
+        .find(|v| v["@type"] == "ProfilePage")
+        .map(|page| match &page["mainEntity"] {
+            serde_json::Value::Null => page["author"].clone(),
+            entity => entity.clone(),
+        });
+
+    let Some(person) = person else {
+        let error = if content.contains("/i/flow/login")
+            || content.contains("Sign in to X")
+            || content.contains("Log in to X")
+        {
+            "X login wall shown instead of profile (profile data requires sign-in)"
+        } else {
+            "X profile data not found in page"
+        };
+        return ExtractedData::failed(url, "x", error.to_string());
+    };
+
+    let counter = |name: &str| {
+        person["interactionStatistic"]
+            .as_array()?
+            .iter()
+            .find(|c| c["name"] == name)
+            .and_then(|c| c["userInteractionCount"].as_u64())
+            .map(|n| n.to_string())
+    };
+    let handle = person["additionalName"]
+        .as_str()
+        .map(String::from)
+        .or_else(|| {
+            url.trim_end_matches('/')
+                .rsplit('/')
+                .next()
+                .map(|s| s.split(['?', '#']).next().unwrap_or(s).to_string())
+        })
+        .map(|h| format!("@{}", h.trim_start_matches('@')));
+
+    ExtractedData {
+        url: url.to_string(),
+        extractor_type: "x".to_string(),
+        success: true,
+        title: person["givenName"].as_str().map(String::from),
+        amounts: None,
+        percentages: None,
+        emails: None,
+        phones: None,
+        followers: counter("Follows"),
+        following: counter("Friends"),
+        username: handle,
+        videos: None,
+        error: None,
+    }
+}
+
+/// Collect display texts from ytInitialData (simpleText, content, joined runs)
+fn collect_youtube_texts(value: &serde_json::Value, texts: &mut Vec<String>) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for key in ["simpleText", "content"] {
+                if let Some(text) = map.get(key).and_then(|v| v.as_str()) {
+                    texts.push(text.to_string());
+                }
+            }
+            if let Some(runs) = map.get("runs").and_then(|v| v.as_array()) {
+                let joined: String = runs.iter().filter_map(|r| r["text"].as_str()).collect();
+                texts.push(joined);
+            }
+            for v in map.values() {
+                collect_youtube_texts(v, texts);
+            }
+        }
+        serde_json::Value::Array(items) => {
+            for v in items {
+                collect_youtube_texts(v, texts);
+            }
+        }
+        _ => {}
+    }
+}
+
 fn extract_statista(url: &str, content: &str) -> ExtractedData {
     let amounts = extract_amounts(content);
     let percentages = extract_percentages(content);
@@ -271,8 +771,12 @@ fn extract_statista(url: &str, content: &str) -> ExtractedData {
         } else {
             Some(percentages)
         },
+        emails: None,
+        phones: None,
         followers: None,
+        following: None,
         username: None,
+        videos: None,
         error: None,
     }
 }
@@ -280,11 +784,13 @@ fn extract_statista(url: &str, content: &str) -> ExtractedData {
 fn extract_generic(url: &str, content: &str) -> ExtractedData {
     let amounts = extract_amounts(content);
     let percentages = extract_percentages(content);
+    let emails = extract_emails(content);
+    let phones = extract_phones(content);
     let title = extract_title(content);
 
     ExtractedData {
         url: url.to_string(),
-        extractor_type: "generic".to_string(),
+        extractor_type: GENERIC.to_string(),
         success: true,
         title,
         amounts: if amounts.is_empty() {
@@ -297,8 +803,12 @@ fn extract_generic(url: &str, content: &str) -> ExtractedData {
         } else {
             Some(percentages)
         },
+        emails: (!emails.is_empty()).then_some(emails),
+        phones: (!phones.is_empty()).then_some(phones),
         followers: None,
+        following: None,
         username: None,
+        videos: None,
         error: None,
     }
 }
@@ -317,20 +827,69 @@ fn extract_title(content: &str) -> Option<String> {
     title_re.captures(content).map(|c| c[1].trim().to_string())
 }
 
-fn truncate(s: &str, max: usize) -> String {
-    if s.len() <= max {
-        s.to_string()
-    } else {
-        format!("{}...", &s[..max - 3])
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::time::Instant;
+
+    fn urls(list: &[&str]) -> Vec<(String, String)> {
+        list.iter()
+            .map(|u| (u.to_string(), "generic".to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_group_by_host() {
+        let urls = urls(&[
+            "https://a.com/1",
+            "https://b.com/1",
+            "https://www.a.com/2",
+            "https://c.com/1",
+        ]);
+        assert_eq!(group_by_host(&urls), vec![vec![0, 2], vec![1], vec![3]]);
+    }
+
+    #[tokio::test]
+    async fn test_for_each_host_parallel_hosts() {
+        let urls = urls(&["https://a.com/1", "https://b.com/1"]);
+        // Each task waits for the other: run one host after the other and
+        // neither finishes
+        let barrier = tokio::sync::Barrier::new(2);
+        let run = for_each_host(&urls, 4, Duration::ZERO, |url, _| {
+            let barrier = &barrier;
+            async move {
+                barrier.wait().await;
+                url.to_string()
+            }
+        });
+        let results = tokio::time::timeout(Duration::from_secs(10), run)
+            .await
+            .expect("hosts did not run in parallel");
+        assert_eq!(results, vec!["https://a.com/1", "https://b.com/1"]);
+    }
+
+    #[tokio::test]
+    async fn test_for_each_host_serializes_same_host() {
+        let urls = urls(&["https://a.com/1", "https://a.com/2", "https://b.com/1"]);
+        let start = Instant::now();
+        let results = for_each_host(&urls, 4, Duration::from_millis(100), |url, _| async move {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            url.to_string()
+        })
+        .await;
+
+        // a.com: 100ms + 100ms delay + 100ms; b.com runs alongside
+        assert!(start.elapsed() >= Duration::from_millis(300));
+        assert_eq!(
+            results,
+            vec!["https://a.com/1", "https://a.com/2", "https://b.com/1"]
+        );
+    }
 
     #[test]
     fn test_get_extractor_type() {
+        let extractors = default_extractors();
+        let get_extractor_type = |url| get_extractor_type(&extractors, url);
         assert_eq!(
             get_extractor_type("https://instagram.com/user"),
             "instagram"
@@ -339,9 +898,72 @@ mod tests {
             get_extractor_type("https://www.statista.com/stats"),
             "statista"
         );
+        assert_eq!(
+            get_extractor_type("https://www.youtube.com/@rustlang"),
+            "youtube"
+        );
+        assert_eq!(
+            get_extractor_type("https://youtube.com/channel/UC123"),
+            "youtube"
+        );
+        assert_eq!(
+            get_extractor_type("https://youtube.com/watch?v=x"),
+            "generic"
+        );
+        assert_eq!(get_extractor_type("https://x.com/rustlang"), "x");
+        assert_eq!(
+            get_extractor_type("https://mobile.twitter.com/rustlang/"),
+            "x"
+        );
+        assert_eq!(
+            get_extractor_type("https://x.com/rustlang/status/1"),
+            "generic"
+        );
+        assert_eq!(get_extractor_type("https://x.com/search?q=rust"), "generic");
+        assert_eq!(
+            get_extractor_type("https://www.emarketer.com/content/x"),
+            "market"
+        );
         assert_eq!(get_extractor_type("https://example.com"), "generic");
     }
 
+    #[test]
+    fn test_find_extractor_custom() {
+        struct Custom;
+        impl Extractor for Custom {
+            fn name(&self) -> &'static str {
+                "custom"
+            }
+            fn matches(&self, url: &str) -> bool {
+                url.contains("custom.example")
+            }
+            fn extract(&self, url: &str, html: &str) -> ExtractedData {
+                extract_generic(url, html)
+            }
+        }
+
+        let mut extractors: Vec<Box<dyn Extractor>> = vec![Box::new(Custom)];
+        extractors.extend(default_extractors());
+        let found = |url| find_extractor(&extractors, url).map(|e| e.name());
+        assert_eq!(found("https://custom.example/page"), Some("custom"));
+        assert_eq!(found("https://instagram.com/user"), Some("instagram"));
+        assert_eq!(found("https://example.com"), Some("generic"));
+
+        // Labels and file-mode URL selection come from the same registry
+        assert_eq!(
+            get_extractor_type(&extractors, "https://custom.example/a"),
+            "custom"
+        );
+        let urls = extract_extractable_urls(
+            "See https://custom.example/a and https://example.com/b",
+            &extractors,
+        );
+        assert_eq!(
+            urls,
+            [("https://custom.example/a".to_string(), "custom".to_string())]
+        );
+    }
+
     #[test]
     fn test_extract_title() {
         let html =
@@ -352,6 +974,20 @@ mod tests {
         assert_eq!(extract_title(html_no_h1), Some("Test Page".to_string()));
     }
 
+    #[test]
+    fn test_extract_generic_contacts() {
+        let html = r#"<html><body><h1>Contact</h1>
+            <a href="mailto:hello@acme.io">hello@acme.io</a> or call +1 415-555-0132
+            </body></html>"#;
+        let data = extract_generic("https://acme.io/contact", html);
+        assert_eq!(data.emails, Some(vec!["hello@acme.io".to_string()]));
+        assert_eq!(data.phones, Some(vec!["+1 415-555-0132".to_string()]));
+
+        let data = extract_generic("https://acme.io", "<h1>No contacts</h1>");
+        assert!(data.emails.is_none());
+        assert!(data.phones.is_none());
+    }
+
     #[test]
     fn test_extract_instagram() {
         let content = "Profile has 577K Followers and 100 posts";
@@ -360,6 +996,49 @@ mod tests {
         assert_eq!(result.username, Some("testuser".to_string()));
     }
 
+    #[test]
+    fn test_extract_youtube() {
+        let content = include_str!("../test-data/youtube-channel.html");
+        let result = extract_youtube("https://www.youtube.com/@rustlang", content);
+        assert!(result.success);
+        assert_eq!(result.extractor_type, "youtube");
+        assert_eq!(result.title, Some("Rust".to_string()));
+        assert_eq!(result.followers, Some("45.2K".to_string()));
+        assert_eq!(result.videos, Some("312".to_string()));
+        assert_eq!(result.username, Some("@rustlang".to_string()));
+    }
+
+    #[test]
+    fn test_extract_youtube_consent_page() {
+        let content = r#"<html><body><form action="https://consent.youtube.com/save">
+            Before you continue to YouTube</form></body></html>"#;
+        let result = extract_youtube("https://www.youtube.com/@rustlang", content);
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("consent"));
+    }
+
+    #[test]
+    fn test_extract_x() {
+        let content = include_str!("../test-data/x-profile.html");
+        let result = extract_x("https://x.com/rustlang", content);
+        assert!(result.success);
+        assert_eq!(result.extractor_type, "x");
+        assert_eq!(result.title, Some("Rust Language".to_string()));
+        assert_eq!(result.followers, Some("151234".to_string()));
+        assert_eq!(result.following, Some("97".to_string()));
+        assert_eq!(result.username, Some("@rustlang".to_string()));
+    }
+
+    #[test]
+    fn test_extract_x_login_wall() {
+        let content = r#"<html><body><a href="/i/flow/login">Log in</a>
+            <h1>Don't miss what's happening</h1></body></html>"#;
+        let result = extract_x("https://x.com/rustlang", content);
+        assert!(!result.success);
+        assert!(result.followers.is_none());
+        assert!(result.error.unwrap().contains("login wall"));
+    }
+
     #[test]
     fn test_extract_extractable_urls() {
         let content = r#"
@@ -368,7 +1047,7 @@ mod tests {
             and https://example.com for more.
         "#;
 
-        let urls = extract_extractable_urls(content);
+        let urls = extract_extractable_urls(content, &default_extractors());
         assert_eq!(urls.len(), 2); // Only instagram and statista
         assert!(urls.iter().any(|(u, _)| u.contains("instagram")));
         assert!(urls.iter().any(|(u, _)| u.contains("statista")));
@@ -0,0 +1,322 @@
+//! report command: Link health summary of references.yaml
+//!
+//! Aggregate counts as JSON, or a self-contained HTML page for dashboards.
+
+use crate::progress;
+use crate::schema::{ReferencesFile, Status};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use clap::{Args, ValueEnum};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+#[derive(Args)]
+pub struct ReportArgs {
+    /// Path to references.yaml file
+    #[arg(default_value = "references.yaml")]
+    pub file: PathBuf,
+
+    /// Output format
+    #[arg(long, short, value_enum, default_value = "json")]
+    pub format: ReportFormat,
+
+    /// Write to a file instead of stdout
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ReportFormat {
+    Json,
+    Html,
+}
+
+/// Entries listed in `oldest_unverified`
+const OLDEST_LIMIT: usize = 10;
+
+/// Link health summary
+#[derive(Debug, Serialize)]
+pub struct HealthReport {
+    pub file: String,
+    pub total: usize,
+    /// Share of checked (non-pending) references that are ok, 0-100
+    pub healthy_percent: Option<f64>,
+    /// Count per status; statuses with no references are omitted
+    pub by_status: BTreeMap<String, usize>,
+    pub by_category: BTreeMap<String, CategoryHealth>,
+    /// Never verified first, then least recently verified
+    pub oldest_unverified: Vec<StaleReference>,
+    pub generated: String,
+}
+
+#[derive(Debug, Default, Serialize, PartialEq)]
+pub struct CategoryHealth {
+    pub total: usize,
+    pub ok: usize,
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+pub struct StaleReference {
+    pub url: String,
+    pub status: Status,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verified: Option<String>,
+}
+
+pub async fn run_report(args: ReportArgs) -> Result<()> {
+    let refs_file = ReferencesFile::load(&args.file).await?;
+
+    let report = health_report(&refs_file, &args.file.display().to_string(), Utc::now());
+    let out = match args.format {
+        ReportFormat::Json => serde_json::to_string(&report)? + "\n",
+        ReportFormat::Html => to_html(&report),
+    };
+
+    match &args.output {
+        Some(path) => {
+            tokio::fs::write(path, out)
+                .await
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+            progress!("Wrote report to {}", path.display());
+        }
+        None => print!("{}", out),
+    }
+
+    Ok(())
+}
+
+/// Aggregate status, category and staleness stats
+pub fn health_report(refs_file: &ReferencesFile, file: &str, now: DateTime<Utc>) -> HealthReport {
+    let references = &refs_file.references;
+
+    let mut by_status = BTreeMap::new();
+    let mut by_category: BTreeMap<String, CategoryHealth> = BTreeMap::new();
+    for reference in references {
+        *by_status.entry(reference.status.to_string()).or_insert(0) += 1;
+        for category in &reference.categories {
+            let stats = by_category.entry(category.clone()).or_default();
+            stats.total += 1;
+            if reference.status == Status::Ok {
+                stats.ok += 1;
+            }
+        }
+    }
+
+    let checked = references
+        .iter()
+        .filter(|r| r.status != Status::Pending)
+        .count();
+    let ok = by_status.get("ok").copied().unwrap_or(0);
+    let healthy_percent =
+        (checked > 0).then(|| (ok as f64 * 1000.0 / checked as f64).round() / 10.0);
+
+    // Unparseable timestamps sort with the never-verified
+    let mut oldest: Vec<_> = references
+        .iter()
+        .map(|r| {
+            let verified = r
+                .verified
+                .as_deref()
+                .and_then(|v| DateTime::parse_from_rfc3339(v).ok());
+            (verified, r)
+        })
+        .collect();
+    oldest.sort_by_key(|(verified, _)| *verified);
+    let oldest_unverified = oldest
+        .into_iter()
+        .take(OLDEST_LIMIT)
+        .map(|(_, r)| StaleReference {
+            url: r.url.clone(),
+            status: r.status,
+            verified: r.verified.clone(),
+        })
+        .collect();
+
+    HealthReport {
+        file: file.to_string(),
+        total: references.len(),
+        healthy_percent,
+        by_status,
+        by_category,
+        oldest_unverified,
+        generated: now.to_rfc3339(),
+    }
+}
+
+/// Standalone HTML page (inline CSS, no scripts or external assets)
+pub fn to_html(report: &HealthReport) -> String {
+    let healthy = report
+        .healthy_percent
+        .map_or("n/a".to_string(), |p| format!("{}%", p));
+
+    let mut html = String::from(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>Reference health</title>
+<style>
+body { font: 14px/1.5 system-ui, sans-serif; margin: 2rem auto; max-width: 60rem; color: #222; }
+table { border-collapse: collapse; margin-bottom: 2rem; }
+th, td { border: 1px solid #ddd; padding: 0.3rem 0.8rem; text-align: left; }
+th { background: #f4f4f4; }
+.ok { color: #1a7f37; } .dead { color: #cf222e; } .timeout, .redirect, .paywall, .login { color: #9a6700; }
+</style>
+</head>
+<body>
+"#,
+    );
+    html.push_str(&format!(
+        "<h1>Reference health</h1>\n<p>{} &middot; {} references &middot; {} healthy &middot; generated {}</p>\n",
+        escape(&report.file),
+        report.total,
+        healthy,
+        escape(&report.generated)
+    ));
+
+    html.push_str("<h2>By status</h2>\n<table>\n<tr><th>Status</th><th>References</th></tr>\n");
+    for (status, count) in &report.by_status {
+        html.push_str(&format!(
+            "<tr><td class=\"{0}\">{0}</td><td>{1}</td></tr>\n",
+            escape(status),
+            count
+        ));
+    }
+    html.push_str("</table>\n");
+
+    html.push_str(
+        "<h2>By category</h2>\n<table>\n<tr><th>Category</th><th>References</th><th>OK</th></tr>\n",
+    );
+    for (category, stats) in &report.by_category {
+        html.push_str(&format!(
+            "<tr><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+            escape(category),
+            stats.total,
+            stats.ok
+        ));
+    }
+    html.push_str("</table>\n");
+
+    html.push_str(
+        "<h2>Oldest unverified</h2>\n<table>\n<tr><th>URL</th><th>Status</th><th>Verified</th></tr>\n",
+    );
+    for stale in &report.oldest_unverified {
+        let status = stale.status.to_string();
+        html.push_str(&format!(
+            "<tr><td>{}</td><td class=\"{1}\">{1}</td><td>{2}</td></tr>\n",
+            escape(&stale.url),
+            status,
+            escape(stale.verified.as_deref().unwrap_or("never"))
+        ));
+    }
+    html.push_str("</table>\n</body>\n</html>\n");
+    html
+}
+
+/// Escape text for HTML element content and quoted attributes
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::Reference;
+
+    fn refs_file() -> ReferencesFile {
+        let yaml = std::fs::read_to_string("test-data/references.yaml").unwrap();
+        let mut refs_file: ReferencesFile = serde_yaml::from_str(&yaml).unwrap();
+        let template = refs_file.references[0].clone();
+        refs_file.references.extend([
+            Reference {
+                url: "https://gone.example/<old>".to_string(),
+                status: Status::Dead,
+                verified: Some("2025-01-02T00:00:00+00:00".to_string()),
+                ..template.clone()
+            },
+            Reference {
+                url: "https://new.example/".to_string(),
+                categories: vec!["research".to_string(), "tools".to_string()],
+                status: Status::Pending,
+                verified: None,
+                ..template
+            },
+        ]);
+        refs_file
+    }
+
+    fn now() -> DateTime<Utc> {
+        "2026-01-01T00:00:00Z".parse().unwrap()
+    }
+
+    #[test]
+    fn test_health_report() {
+        let report = health_report(&refs_file(), "references.yaml", now());
+
+        assert_eq!(report.total, 4);
+        let by_status: Vec<_> = report
+            .by_status
+            .iter()
+            .map(|(s, n)| (s.as_str(), *n))
+            .collect();
+        assert_eq!(
+            by_status,
+            [("dead", 1), ("ok", 1), ("pending", 1), ("redirect", 1)]
+        );
+        // 1 ok of 3 checked; pending doesn't count against health
+        assert_eq!(report.healthy_percent, Some(33.3));
+
+        assert_eq!(
+            report.by_category["research"],
+            CategoryHealth { total: 3, ok: 1 }
+        );
+        assert_eq!(
+            report.by_category["general"],
+            CategoryHealth { total: 1, ok: 0 }
+        );
+        assert_eq!(report.by_category["tools"].total, 1);
+
+        let oldest: Vec<_> = report
+            .oldest_unverified
+            .iter()
+            .map(|s| s.url.as_str())
+            .collect();
+        assert_eq!(
+            oldest,
+            [
+                "https://example.org/old",
+                "https://new.example/",
+                "https://gone.example/<old>",
+                "https://example.com/paper"
+            ]
+        );
+        assert_eq!(report.generated, "2026-01-01T00:00:00+00:00");
+    }
+
+    #[test]
+    fn test_health_report_empty() {
+        let mut empty = refs_file();
+        empty.references.clear();
+        let report = health_report(&empty, "references.yaml", now());
+        assert_eq!(report.total, 0);
+        assert_eq!(report.healthy_percent, None);
+        assert!(report.by_status.is_empty());
+    }
+
+    #[test]
+    fn test_to_html() {
+        let html = to_html(&health_report(&refs_file(), "references.yaml", now()));
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("33.3% healthy"));
+        assert!(html.contains("<td>https://gone.example/&lt;old&gt;</td>"));
+        assert!(html.contains("<td class=\"dead\">dead</td>"));
+        // Self-contained: no scripts, stylesheets or images to fetch
+        for external in ["<script", "<link", "<img", "src="] {
+            assert!(!html.contains(external), "{}", external);
+        }
+    }
+}
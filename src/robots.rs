@@ -0,0 +1,311 @@
+//! robots.txt parsing and per-host caching
+//!
+//! Minimal parser following the common rules: user-agent groups, Allow/Disallow,
+//! `*` wildcards, `$` end anchors, longest match wins (Allow on ties).
+//! Groups are selected by product token (RFC 9309), not the browser user agent.
+
+use crate::browser::http_client;
+use anyhow::Result;
+use regex::Regex;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{Mutex, OnceCell};
+use url::Url;
+
+/// Product token matched against `User-agent:` lines
+pub const PRODUCT_TOKEN: &str = "ref";
+
+/// Parsed robots.txt rules
+#[derive(Debug, Default, Clone)]
+pub struct RobotsRules {
+    groups: Vec<Group>,
+}
+
+/// A user-agent group and its rules
+#[derive(Debug, Default, Clone)]
+struct Group {
+    agents: Vec<String>,
+    rules: Vec<Rule>,
+    /// Saw an Allow/Disallow line, even an empty one that adds no rule
+    closed: bool,
+}
+
+#[derive(Debug, Clone)]
+struct Rule {
+    allow: bool,
+    pattern: String,
+}
+
+impl RobotsRules {
+    /// Parse robots.txt content
+    pub fn parse(content: &str) -> Self {
+        let mut groups: Vec<Group> = Vec::new();
+        let mut current: Option<Group> = None;
+
+        for line in content.lines() {
+            // Strip comments
+            let line = line.split('#').next().unwrap_or("").trim();
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let key = key.trim().to_lowercase();
+            let value = value.trim();
+
+            match key.as_str() {
+                "user-agent" => {
+                    // Consecutive user-agent lines share one group
+                    match current.as_mut() {
+                        Some(g) if !g.closed => g.agents.push(value.to_lowercase()),
+                        _ => {
+                            if let Some(g) = current.take() {
+                                groups.push(g);
+                            }
+                            current = Some(Group {
+                                agents: vec![value.to_lowercase()],
+                                ..Group::default()
+                            });
+                        }
+                    }
+                }
+                "allow" | "disallow" => {
+                    let Some(g) = current.as_mut() else {
+                        continue;
+                    };
+                    g.closed = true;
+                    // Empty Disallow means allow everything
+                    if value.is_empty() {
+                        continue;
+                    }
+                    g.rules.push(Rule {
+                        allow: key == "allow",
+                        pattern: value.to_string(),
+                    });
+                }
+                _ => {}
+            }
+        }
+
+        if let Some(g) = current {
+            groups.push(g);
+        }
+
+        Self { groups }
+    }
+
+    /// Rules for the group naming the product token (ignoring case), else `*`
+    fn rules_for(&self, token: &str) -> Vec<&Rule> {
+        let token = token.to_lowercase();
+
+        // A matching specific group wins even with no rules (`Disallow:`);
+        // a `Name/1.0` version suffix is tolerated, an empty agent never matches
+        let specific: Vec<&Group> = self
+            .groups
+            .iter()
+            .filter(|g| {
+                g.agents.iter().any(|a| {
+                    let name = a.split('/').next().unwrap_or("").trim();
+                    !name.is_empty() && name == token
+                })
+            })
+            .collect();
+        if !specific.is_empty() {
+            return specific.into_iter().flat_map(|g| g.rules.iter()).collect();
+        }
+
+        self.groups
+            .iter()
+            .filter(|g| g.agents.iter().any(|a| a == "*"))
+            .flat_map(|g| g.rules.iter())
+            .collect()
+    }
+}
+
+/// Check whether a path (with query) may be fetched under the product token
+pub fn is_allowed(rules: &RobotsRules, path: &str, token: &str) -> bool {
+    let mut best: Option<(usize, bool)> = None;
+
+    for rule in rules.rules_for(token) {
+        if !pattern_matches(&rule.pattern, path) {
+            continue;
+        }
+        let len = rule.pattern.len();
+        best = match best {
+            Some((best_len, best_allow)) if best_len > len || (best_len == len && best_allow) => {
+                Some((best_len, best_allow))
+            }
+            _ => Some((len, rule.allow)),
+        };
+    }
+
+    best.map(|(_, allow)| allow).unwrap_or(true)
+}
+
+/// Match a robots pattern (`*` wildcard, trailing `$` anchor) against a path
+fn pattern_matches(pattern: &str, path: &str) -> bool {
+    let (pattern, anchored) = match pattern.strip_suffix('$') {
+        Some(p) => (p, true),
+        None => (pattern, false),
+    };
+
+    let body = pattern
+        .split('*')
+        .map(regex::escape)
+        .collect::<Vec<_>>()
+        .join(".*");
+    let re = format!("^{}{}", body, if anchored { "$" } else { "" });
+
+    Regex::new(&re).map(|r| r.is_match(path)).unwrap_or(false)
+}
+
+/// robots.txt cache keyed by origin, fetched at most once per host
+pub struct RobotsCache {
+    client: reqwest::Client,
+    entries: Mutex<HashMap<String, Arc<OnceCell<RobotsRules>>>>,
+}
+
+impl RobotsCache {
+    /// Create a cache that fetches robots.txt as the given user agent,
+    /// through the `--socks5` proxy if any
+    pub fn new(user_agent: &str, socks5: Option<&str>) -> Result<Self> {
+        let client = http_client(socks5)?
+            .user_agent(user_agent)
+            .timeout(Duration::from_secs(10))
+            .build()?;
+
+        Ok(Self {
+            client,
+            entries: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Check whether a URL may be fetched (unreachable robots.txt allows everything)
+    pub async fn is_allowed(&self, url: &str) -> bool {
+        let Ok(parsed) = Url::parse(url) else {
+            return true;
+        };
+        if !matches!(parsed.scheme(), "http" | "https") {
+            return true;
+        }
+
+        let origin = parsed.origin().ascii_serialization();
+        let cell = {
+            let mut entries = self.entries.lock().await;
+            Arc::clone(entries.entry(origin.clone()).or_default())
+        };
+
+        let rules = cell
+            .get_or_init(|| async {
+                let robots_url = format!("{}/robots.txt", origin);
+                match self.client.get(&robots_url).send().await {
+                    Ok(resp) if resp.status().is_success() => {
+                        RobotsRules::parse(&resp.text().await.unwrap_or_default())
+                    }
+                    _ => RobotsRules::default(),
+                }
+            })
+            .await;
+
+        let mut path = parsed.path().to_string();
+        if let Some(query) = parsed.query() {
+            path.push('?');
+            path.push_str(query);
+        }
+
+        is_allowed(rules, &path, PRODUCT_TOKEN)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROBOTS: &str = r#"
+# Example robots.txt
+User-agent: *
+Disallow: /private/
+Disallow: /*.pdf$
+Disallow: /search*q=
+Allow: /private/public-page
+
+User-agent: BadBot
+Disallow: /
+"#;
+
+    #[test]
+    fn test_is_allowed_default_group() {
+        let rules = RobotsRules::parse(ROBOTS);
+        let token = PRODUCT_TOKEN;
+        assert!(is_allowed(&rules, "/", token));
+        assert!(is_allowed(&rules, "/blog/post", token));
+        assert!(!is_allowed(&rules, "/private/secret", token));
+        assert!(is_allowed(&rules, "/private/public-page", token));
+    }
+
+    #[test]
+    fn test_is_allowed_wildcards() {
+        let rules = RobotsRules::parse(ROBOTS);
+        let token = PRODUCT_TOKEN;
+        assert!(!is_allowed(&rules, "/files/report.pdf", token));
+        assert!(is_allowed(&rules, "/files/report.pdf.html", token));
+        assert!(!is_allowed(&rules, "/search?q=rust", token));
+        assert!(is_allowed(&rules, "/search", token));
+    }
+
+    #[test]
+    fn test_is_allowed_disallow_all() {
+        let rules = RobotsRules::parse(ROBOTS);
+        assert!(!is_allowed(&rules, "/", "BadBot"));
+        assert!(!is_allowed(&rules, "/blog/post", "BadBot"));
+    }
+
+    #[test]
+    fn test_is_allowed_empty() {
+        let rules = RobotsRules::parse("");
+        assert!(is_allowed(&rules, "/anything", PRODUCT_TOKEN));
+
+        let rules = RobotsRules::parse("User-agent: *\nDisallow:\n");
+        assert!(is_allowed(&rules, "/anything", PRODUCT_TOKEN));
+    }
+
+    #[test]
+    fn test_empty_disallow_closes_group() {
+        let rules =
+            RobotsRules::parse("User-agent: *\nDisallow:\n\nUser-agent: BadBot\nDisallow: /\n");
+        assert!(is_allowed(&rules, "/anything", PRODUCT_TOKEN));
+        assert!(!is_allowed(&rules, "/anything", "BadBot"));
+
+        // A specific group allowing everything overrides a strict `*`
+        let rules =
+            RobotsRules::parse("User-agent: *\nDisallow: /\n\nUser-agent: GoodBot\nDisallow:\n");
+        assert!(is_allowed(&rules, "/anything", "GoodBot"));
+        assert!(!is_allowed(&rules, "/anything", PRODUCT_TOKEN));
+    }
+
+    #[test]
+    fn test_product_token_match() {
+        // Browser UA tokens never select a group meant for a browser
+        let rules = RobotsRules::parse(
+            "User-agent: *\nDisallow: /private/\n\nUser-agent: Mozilla\nUser-agent: Chrome\nUser-agent: Safari\nDisallow: /\n",
+        );
+        assert!(is_allowed(&rules, "/blog/post", PRODUCT_TOKEN));
+        assert!(!is_allowed(&rules, "/private/secret", PRODUCT_TOKEN));
+
+        // Own token, any case and with a version, picks its group
+        let rules =
+            RobotsRules::parse("User-agent: *\nDisallow:\n\nUser-agent: REF/2.0\nDisallow: /\n");
+        assert!(!is_allowed(&rules, "/anything", PRODUCT_TOKEN));
+
+        // Longer names containing the token don't match
+        let rules = RobotsRules::parse("User-agent: referrer-bot\nDisallow: /\n");
+        assert!(is_allowed(&rules, "/anything", PRODUCT_TOKEN));
+    }
+
+    #[test]
+    fn test_empty_user_agent_ignored() {
+        let rules =
+            RobotsRules::parse("User-agent: *\nDisallow: /private/\n\nUser-agent:\nDisallow: /\n");
+        assert!(is_allowed(&rules, "/blog/post", PRODUCT_TOKEN));
+        assert!(!is_allowed(&rules, "/private/secret", PRODUCT_TOKEN));
+    }
+}
@@ -1,29 +1,60 @@
-//! scan command: Extract URLs from markdown files
+//! scan command: Extract URLs from documentation files
 //!
-//! Scans markdown files, extracts URLs, and creates/updates references.yaml.
+//! Scans Markdown, reStructuredText and AsciiDoc files, extracts URLs, and creates/updates references.yaml.
 
-use crate::schema::{Meta, Reference, ReferencesFile, Status};
+use crate::extract::{strip_tracking_params, trim_url, ASCIIDOC_URL_PATTERN, URL_PATTERN};
+use crate::normalize::{normalize_url, NormalizeOptions};
+use crate::progress;
+use crate::schema::{Meta, Reference, ReferencesFile, Status, JSON_FILE};
 use anyhow::{Context, Result};
 use chrono::Utc;
 use clap::Args;
 use regex::Regex;
 use serde::Serialize;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 
 #[derive(Args)]
 pub struct ScanArgs {
-    /// Files or glob patterns to scan
-    #[arg(required = true)]
+    /// Files or glob patterns to scan (with --since: limits which changed files count)
+    #[arg(required_unless_present = "since")]
     pub files: Vec<PathBuf>,
 
     /// Output file (default: references.yaml)
     #[arg(short, long, default_value = "references.yaml")]
     pub output: PathBuf,
 
+    /// Write references.json (same schema, as JSON) instead of references.yaml
+    #[arg(long, conflicts_with = "output")]
+    pub json: bool,
+
     /// Merge with existing file instead of overwriting
     #[arg(long, default_value = "true")]
     pub merge: bool,
+
+    /// Scan files excluded by .gitignore/.ignore when walking directories
+    #[arg(long)]
+    pub no_ignore: bool,
+
+    /// Treat URLs differing only in #fragment as duplicates
+    #[arg(long)]
+    pub strip_fragments: bool,
+
+    /// Remove utm_*/fbclid/gclid-style parameters from stored URLs
+    #[arg(long)]
+    pub strip_tracking: bool,
+
+    /// Extra tracking parameter to strip (repeatable, `name*` = prefix)
+    #[arg(long, value_name = "NAME", requires = "strip_tracking")]
+    pub tracking_param: Vec<String>,
+
+    /// Only scan doc files changed since this git ref (`git diff --name-only`), merged into output
+    #[arg(long, value_name = "REF")]
+    pub since: Option<String>,
+
+    /// Report new URLs (in `added`) without modifying the output file
+    #[arg(long)]
+    pub dry_run: bool,
 }
 
 #[derive(Debug, Serialize)]
@@ -33,21 +64,39 @@ pub struct ScanOutput {
     pub total_urls: usize,
     pub new_urls: usize,
     pub updated_urls: usize,
+    /// URLs a --dry-run would add
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub added: Vec<String>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub dry_run: bool,
 }
 
-/// A URL found in a markdown file with optional title
+/// A URL found in a documentation file with optional title
 #[derive(Debug, Clone)]
-struct FoundUrl {
-    url: String,
-    title: Option<String>,
-    source_file: String,
+pub(crate) struct FoundUrl {
+    pub url: String,
+    pub title: Option<String>,
+    pub source_file: String,
 }
 
-pub async fn run_scan(args: ScanArgs) -> Result<()> {
-    // Expand file patterns and collect all files
-    let files = expand_files(&args.files).await?;
+pub async fn run_scan(mut args: ScanArgs) -> Result<()> {
+    if args.json {
+        args.output = PathBuf::from(JSON_FILE);
+    }
+    let normalize = NormalizeOptions {
+        strip_fragment: args.strip_fragments,
+        strip_tracking: args.strip_tracking,
+        extra_tracking: args.tracking_param.clone(),
+        ..Default::default()
+    };
+    let patterns = match &args.since {
+        Some(since) => changed_doc_files(git_changed_files(since)?, &args.files),
+        None => args.files.clone(),
+    };
+    let (files, url_map) = scan_files(&patterns, !args.no_ignore, &normalize).await?;
 
-    if files.is_empty() {
+    // Nothing changed since the ref is a normal outcome in CI
+    if files.is_empty() && args.since.is_none() {
         let error = serde_json::json!({
             "error": "no_files",
             "message": "No files found matching patterns"
@@ -56,6 +105,72 @@ pub async fn run_scan(args: ScanArgs) -> Result<()> {
         return Ok(());
     }
 
+    // Load existing file if merging
+    let (mut refs_file, _existing_count) = if args.merge && args.output.exists() {
+        let existing = ReferencesFile::load(&args.output).await?;
+        let count = existing.references.len();
+        (existing, count)
+    } else {
+        let now = Utc::now().format("%Y-%m-%d").to_string();
+        (
+            ReferencesFile {
+                meta: Meta {
+                    project: None,
+                    created: now,
+                    last_verified: None,
+                    tool: "ref".to_string(),
+                    total_links: 0,
+                },
+                references: Vec::new(),
+            },
+            0,
+        )
+    };
+
+    let known: HashSet<String> = refs_file.references.iter().map(|r| r.url.clone()).collect();
+    let (new_count, updated_count) = merge_references(&mut refs_file, url_map, &normalize);
+
+    // Write file
+    let added = if args.dry_run {
+        progress!("Dry run - file not modified");
+        refs_file
+            .references
+            .iter()
+            .filter(|r| !known.contains(&r.url))
+            .map(|r| r.url.clone())
+            .collect()
+    } else {
+        refs_file.save(&args.output).await?;
+        Vec::new()
+    };
+
+    // Output JSON result
+    let output = ScanOutput {
+        file: args.output.display().to_string(),
+        scanned_files: files.len(),
+        total_urls: refs_file.references.len(),
+        new_urls: new_count,
+        updated_urls: updated_count,
+        added,
+        dry_run: args.dry_run,
+    };
+    println!("{}", serde_json::to_string(&output)?);
+
+    Ok(())
+}
+
+/// Expand patterns and collect deduplicated references from all files
+///
+/// Returns the scanned files and the references keyed by normalized URL. Each
+/// reference keeps the URL as first written.
+pub(crate) async fn scan_files(
+    patterns: &[PathBuf],
+    respect_ignore: bool,
+    normalize: &NormalizeOptions,
+) -> Result<(Vec<PathBuf>, HashMap<String, Reference>)> {
+    // Expand file patterns and collect all files
+    let files = expand_files(patterns, respect_ignore).await?;
+
     // Extract URLs from all files
     let mut all_urls: Vec<FoundUrl> = Vec::new();
     for file in &files {
@@ -63,24 +178,35 @@ pub async fn run_scan(args: ScanArgs) -> Result<()> {
             .await
             .with_context(|| format!("Failed to read {}", file.display()))?;
 
-        let found = extract_markdown_urls(&content, file);
+        let found = extract_doc_urls(&content, file);
         all_urls.extend(found);
     }
 
     // Dedupe and merge by URL
+    let now = Utc::now().to_rfc3339();
     let mut url_map: HashMap<String, Reference> = HashMap::new();
 
     for found in &all_urls {
-        let entry = url_map.entry(found.url.clone()).or_insert_with(|| {
+        let url = if normalize.strip_tracking {
+            strip_tracking_params(&found.url, &normalize.extra_tracking)
+        } else {
+            found.url.clone()
+        };
+        let key = normalize_url(&url, normalize);
+        let entry = url_map.entry(key).or_insert_with(|| {
             let categories = infer_categories(&found.source_file);
             Reference {
-                url: found.url.clone(),
-                title: found.title.clone().unwrap_or_else(|| found.url.clone()),
+                url: url.clone(),
+                title: found.title.clone().unwrap_or_else(|| url.clone()),
                 categories,
+                tags: Vec::new(),
                 cited_in: Vec::new(),
                 status: Status::Pending,
                 verified: None,
+                first_seen: Some(now.clone()),
+                last_seen: Some(now.clone()),
                 notes: None,
+                content_hash: None,
             }
         });
 
@@ -97,32 +223,21 @@ pub async fn run_scan(args: ScanArgs) -> Result<()> {
         }
     }
 
-    // Load existing file if merging
-    let (mut refs_file, _existing_count) = if args.merge && args.output.exists() {
-        let content = tokio::fs::read_to_string(&args.output).await?;
-        let existing: ReferencesFile = serde_yaml::from_str(&content)?;
-        let count = existing.references.len();
-        (existing, count)
-    } else {
-        let now = Utc::now().format("%Y-%m-%d").to_string();
-        (
-            ReferencesFile {
-                meta: Meta {
-                    created: now,
-                    last_verified: None,
-                    tool: "ref".to_string(),
-                    total_links: 0,
-                },
-                references: Vec::new(),
-            },
-            0,
-        )
-    };
+    Ok((files, url_map))
+}
 
-    // Build a map of existing URLs for quick lookup
+/// Merge scanned references into the file, returning (new, updated) counts
+///
+/// Existing entries keep their status and `first_seen`; `last_seen` is bumped.
+pub(crate) fn merge_references(
+    refs_file: &mut ReferencesFile,
+    url_map: HashMap<String, Reference>,
+    normalize: &NormalizeOptions,
+) -> (usize, usize) {
+    // Existing entries by normalized URL, so `/a/` found again matches `/a`
     let mut existing_urls: HashMap<String, usize> = HashMap::new();
     for (i, r) in refs_file.references.iter().enumerate() {
-        existing_urls.insert(r.url.clone(), i);
+        existing_urls.insert(normalize_url(&r.url, normalize), i);
     }
 
     // Merge new URLs
@@ -143,6 +258,7 @@ pub async fn run_scan(args: ScanArgs) -> Result<()> {
             if existing.title == existing.url && new_ref.title != new_ref.url {
                 existing.title = new_ref.title;
             }
+            existing.last_seen = new_ref.last_seen;
         } else {
             // Add new reference
             refs_file.references.push(new_ref);
@@ -156,25 +272,67 @@ pub async fn run_scan(args: ScanArgs) -> Result<()> {
     // Sort references by URL for consistency
     refs_file.references.sort_by(|a, b| a.url.cmp(&b.url));
 
-    // Write file
-    let yaml = serde_yaml::to_string(&refs_file)?;
-    tokio::fs::write(&args.output, yaml).await?;
+    (new_count, updated_count)
+}
 
-    // Output JSON result
-    let output = ScanOutput {
-        file: args.output.display().to_string(),
-        scanned_files: files.len(),
-        total_urls: refs_file.references.len(),
-        new_urls: new_count,
-        updated_urls: updated_count,
-    };
-    println!("{}", serde_json::to_string(&output)?);
+/// Files changed since `since` (working tree vs ref), relative to the current directory
+fn git_changed_files(since: &str) -> Result<Vec<PathBuf>> {
+    let output = std::process::Command::new("git")
+        .args([
+            "diff",
+            "--name-only",
+            "--relative",
+            "--diff-filter=d",
+            since,
+            "--",
+        ])
+        .output()
+        .context("Failed to run git (is it installed?)")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "--since needs a git repository and a valid ref: {}",
+            String::from_utf8_lossy(&output.stderr)
+                .lines()
+                .next()
+                .unwrap_or_default()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(PathBuf::from)
+        .collect())
+}
 
-    Ok(())
+/// Changed documentation files that still exist, limited to `scope` (paths or globs) if given
+fn changed_doc_files(changed: Vec<PathBuf>, scope: &[PathBuf]) -> Vec<PathBuf> {
+    let in_scope = |path: &Path| {
+        scope.is_empty()
+            || scope.iter().any(|s| {
+                let s = s.strip_prefix(".").unwrap_or(s);
+                match glob::Pattern::new(&s.to_string_lossy()) {
+                    Ok(pattern) if pattern.as_str().contains('*') => {
+                        let options = glob::MatchOptions {
+                            require_literal_separator: true,
+                            ..Default::default()
+                        };
+                        pattern.matches_path_with(path, options)
+                    }
+                    _ => path.starts_with(s),
+                }
+            })
+    };
+    changed
+        .into_iter()
+        .filter(|path| DocFormat::from_path(path).is_some() && path.is_file() && in_scope(path))
+        .collect()
 }
 
 /// Expand file patterns to actual file paths
-async fn expand_files(patterns: &[PathBuf]) -> Result<Vec<PathBuf>> {
+///
+/// Directories are walked recursively for documentation files, honoring
+/// .gitignore/.ignore rules unless `respect_ignore` is false.
+async fn expand_files(patterns: &[PathBuf], respect_ignore: bool) -> Result<Vec<PathBuf>> {
     let mut files = Vec::new();
 
     for pattern in patterns {
@@ -191,55 +349,94 @@ async fn expand_files(patterns: &[PathBuf]) -> Result<Vec<PathBuf>> {
         } else if pattern.is_file() {
             files.push(pattern.clone());
         } else if pattern.is_dir() {
-            // Scan directory for markdown files
-            let mut entries = tokio::fs::read_dir(pattern).await?;
-            while let Some(entry) = entries.next_entry().await? {
-                let path = entry.path();
-                if path.is_file() {
-                    if let Some(ext) = path.extension() {
-                        if ext == "md" || ext == "markdown" {
-                            files.push(path);
-                        }
-                    }
-                }
-            }
+            files.extend(walk_docs(pattern, respect_ignore)?);
+        }
+    }
+
+    Ok(files)
+}
+
+/// Documentation files (.md, .rst, .adoc) under `dir` at any depth, sorted by path
+fn walk_docs(dir: &Path, respect_ignore: bool) -> Result<Vec<PathBuf>> {
+    let walker = ignore::WalkBuilder::new(dir)
+        .standard_filters(respect_ignore)
+        // Honor .gitignore outside git checkouts too
+        .require_git(false)
+        .build();
+
+    let mut files = Vec::new();
+    for entry in walker {
+        let path = entry?.into_path();
+        if DocFormat::from_path(&path).is_some() && path.is_file() {
+            files.push(path);
         }
     }
 
+    files.sort();
     Ok(files)
 }
 
-/// Extract URLs from markdown content
-fn extract_markdown_urls(content: &str, source_file: &Path) -> Vec<FoundUrl> {
+/// Markup format of a documentation file, by extension
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DocFormat {
+    Markdown,
+    Rst,
+    AsciiDoc,
+}
+
+impl DocFormat {
+    fn from_path(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_str()?.to_lowercase();
+        match ext.as_str() {
+            "md" | "markdown" => Some(Self::Markdown),
+            "rst" => Some(Self::Rst),
+            "adoc" | "asciidoc" => Some(Self::AsciiDoc),
+            _ => None,
+        }
+    }
+
+    /// Link regex with `title` and `url` groups
+    fn link_regex(self) -> Regex {
+        let pattern = match self {
+            // [title](url)
+            Self::Markdown => r"\[(?P<title>[^\]]+)\]\((?P<url>https?://[^\s\)]+)\)",
+            // `title <url>`_ (or __ for anonymous links)
+            Self::Rst => r"`(?P<title>[^`<]*?)\s*<(?P<url>https?://[^\s>]+)>`__?",
+            // link:url[title] or url[title]
+            Self::AsciiDoc => r"(?:link:)?(?P<url>https?://[^\s\[]+)\[(?P<title>[^\]]*)\]",
+        };
+        Regex::new(pattern).unwrap()
+    }
+}
+
+/// Extract URLs from a documentation file (format chosen by extension, Markdown by default)
+pub(crate) fn extract_doc_urls(content: &str, source_file: &Path) -> Vec<FoundUrl> {
     let mut found = Vec::new();
     let source = source_file.to_string_lossy().to_string();
+    let format = DocFormat::from_path(source_file).unwrap_or(DocFormat::Markdown);
 
-    // Match markdown links: [title](url)
-    let md_link_re = Regex::new(r"\[([^\]]+)\]\((https?://[^\s\)]+)\)").unwrap();
-    for cap in md_link_re.captures_iter(content) {
-        let title = cap[1].to_string();
-        let url = cap[2].to_string();
-        // Clean trailing punctuation from URL
-        let url = url
-            .trim_end_matches([',', '.', ')', ']', ';', ':'])
-            .to_string();
+    // Match format-specific links with titles
+    for cap in format.link_regex().captures_iter(content) {
+        let title = cap["title"].trim().to_string();
+        let url = trim_url(&cap["url"]).to_string();
 
         found.push(FoundUrl {
             url,
-            title: Some(title),
+            title: (!title.is_empty()).then_some(title),
             source_file: source.clone(),
         });
     }
 
-    // Match bare URLs (not already in markdown links)
-    let bare_url_re = Regex::new(r#"https?://[^\s\)>\]"'`]+"#).unwrap();
+    // Match bare URLs (not already in links)
+    let bare_url_re = Regex::new(match format {
+        DocFormat::AsciiDoc => ASCIIDOC_URL_PATTERN,
+        _ => URL_PATTERN,
+    })
+    .unwrap();
     for mat in bare_url_re.find_iter(content) {
-        let url = mat.as_str();
-        let url = url
-            .trim_end_matches([',', '.', ')', ']', ';', ':'])
-            .to_string();
+        let url = trim_url(mat.as_str()).to_string();
 
-        // Skip if already found as markdown link
+        // Skip if already found as a link
         if !found.iter().any(|f| f.url == url) {
             found.push(FoundUrl {
                 url,
@@ -299,7 +496,7 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_extract_markdown_urls() {
+    fn test_extract_doc_urls() {
         let content = r#"
 # Test Document
 
@@ -311,7 +508,7 @@ Bare URL: https://bare.example.org/path
 Another [link](https://another.com/page?q=1).
         "#;
 
-        let found = extract_markdown_urls(content, Path::new("test.md"));
+        let found = extract_doc_urls(content, Path::new("test.md"));
 
         assert_eq!(found.len(), 4);
 
@@ -330,6 +527,200 @@ Another [link](https://another.com/page?q=1).
         assert_eq!(bare.title, None);
     }
 
+    fn scanned(url: &str, seen: &str) -> HashMap<String, Reference> {
+        let reference = Reference {
+            url: url.to_string(),
+            title: url.to_string(),
+            categories: vec!["general".to_string()],
+            tags: vec![],
+            cited_in: vec!["notes.md".to_string()],
+            status: Status::Pending,
+            verified: None,
+            first_seen: Some(seen.to_string()),
+            last_seen: Some(seen.to_string()),
+            notes: None,
+            content_hash: None,
+        };
+        HashMap::from([(normalize_url(url, &NormalizeOptions::default()), reference)])
+    }
+
+    #[test]
+    fn test_rescan_updates_last_seen() {
+        let mut refs_file = ReferencesFile {
+            meta: Meta {
+                project: None,
+                created: "2025-01-01".to_string(),
+                last_verified: None,
+                tool: "ref".to_string(),
+                total_links: 0,
+            },
+            references: vec![],
+        };
+
+        let first = "2025-01-01T00:00:00+00:00";
+        let second = "2025-02-01T00:00:00+00:00";
+        let normalize = NormalizeOptions::default();
+        assert_eq!(
+            merge_references(&mut refs_file, scanned("https://a.com", first), &normalize),
+            (1, 0)
+        );
+        assert_eq!(
+            merge_references(&mut refs_file, scanned("https://a.com", second), &normalize),
+            (0, 0)
+        );
+
+        let reference = &refs_file.references[0];
+        assert_eq!(reference.first_seen.as_deref(), Some(first));
+        assert_eq!(reference.last_seen.as_deref(), Some(second));
+        assert_eq!(refs_file.meta.total_links, 1);
+    }
+
+    #[test]
+    fn test_rescan_matches_normalized_url() {
+        let mut refs_file = ReferencesFile {
+            meta: Meta {
+                project: None,
+                created: "2025-01-01".to_string(),
+                last_verified: None,
+                tool: "ref".to_string(),
+                total_links: 0,
+            },
+            references: vec![],
+        };
+        let normalize = NormalizeOptions::default();
+        let seen = "2025-01-01T00:00:00+00:00";
+
+        merge_references(
+            &mut refs_file,
+            scanned("https://A.com/docs/?b=1&a=2", seen),
+            &normalize,
+        );
+        let (new, _) = merge_references(
+            &mut refs_file,
+            scanned("https://a.com/docs?a=2&b=1", seen),
+            &normalize,
+        );
+
+        assert_eq!(new, 0);
+        assert_eq!(refs_file.references.len(), 1);
+        // Original spelling is kept for display
+        assert_eq!(refs_file.references[0].url, "https://A.com/docs/?b=1&a=2");
+    }
+
+    #[test]
+    fn test_extract_rst_urls() {
+        let content = "See `Sphinx docs <https://www.sphinx-doc.org/>`_ and \
+            `anonymous <https://example.com/anon>`__.\n\nBare: https://bare.example.org\n";
+
+        let found = extract_doc_urls(content, Path::new("docs/index.rst"));
+
+        assert_eq!(found.len(), 3);
+        assert_eq!(found[0].url, "https://www.sphinx-doc.org/");
+        assert_eq!(found[0].title.as_deref(), Some("Sphinx docs"));
+        assert_eq!(found[1].url, "https://example.com/anon");
+        assert_eq!(found[1].title.as_deref(), Some("anonymous"));
+        assert_eq!(found[2].url, "https://bare.example.org");
+        assert_eq!(found[2].title, None);
+    }
+
+    #[test]
+    fn test_extract_asciidoc_urls() {
+        let content = "Read link:https://asciidoctor.org/docs[the manual] or \
+            https://github.com/asciidoctor[GitHub].\n\
+            Empty text: link:https://empty.example.com[]\n";
+
+        let found = extract_doc_urls(content, Path::new("guide.adoc"));
+
+        assert_eq!(found.len(), 3);
+        assert_eq!(found[0].url, "https://asciidoctor.org/docs");
+        assert_eq!(found[0].title.as_deref(), Some("the manual"));
+        assert_eq!(found[1].url, "https://github.com/asciidoctor");
+        assert_eq!(found[1].title.as_deref(), Some("GitHub"));
+        assert_eq!(found[2].url, "https://empty.example.com");
+        assert_eq!(found[2].title, None);
+    }
+
+    #[tokio::test]
+    async fn test_expand_files_recursive_gitignore() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        for sub in ["docs/guides/deep", "node_modules/pkg"] {
+            std::fs::create_dir_all(root.join(sub)).unwrap();
+        }
+        std::fs::write(root.join(".gitignore"), "node_modules/\n").unwrap();
+        std::fs::write(root.join("README.md"), "").unwrap();
+        std::fs::write(root.join("docs/guides/deep/setup.markdown"), "").unwrap();
+        std::fs::write(root.join("docs/guides/notes.txt"), "").unwrap();
+        std::fs::write(root.join("node_modules/pkg/README.md"), "").unwrap();
+
+        let relative = |files: Vec<PathBuf>| -> Vec<String> {
+            files
+                .iter()
+                .map(|f| {
+                    f.strip_prefix(root)
+                        .unwrap()
+                        .to_string_lossy()
+                        .replace('\\', "/")
+                })
+                .collect()
+        };
+
+        let files = expand_files(&[root.to_path_buf()], true).await.unwrap();
+        assert_eq!(
+            relative(files),
+            vec!["README.md", "docs/guides/deep/setup.markdown"]
+        );
+
+        // --no-ignore
+        let files = expand_files(&[root.to_path_buf()], false).await.unwrap();
+        assert_eq!(
+            relative(files),
+            vec![
+                "README.md",
+                "docs/guides/deep/setup.markdown",
+                "node_modules/pkg/README.md"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_scan_changed_files_only() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("docs")).unwrap();
+        std::fs::write(root.join("docs/changed.md"), "https://changed.com").unwrap();
+        std::fs::write(root.join("docs/untouched.md"), "https://untouched.com").unwrap();
+        std::fs::write(root.join("README.md"), "https://readme.com").unwrap();
+        std::fs::write(root.join("docs/data.txt"), "https://data.com").unwrap();
+
+        // As reported by git diff: a deleted file and a non-doc file are dropped
+        let changed = vec![
+            root.join("docs/changed.md"),
+            root.join("README.md"),
+            root.join("docs/data.txt"),
+            root.join("docs/deleted.md"),
+        ];
+        let files = changed_doc_files(changed.clone(), &[]);
+        assert_eq!(
+            files,
+            vec![root.join("docs/changed.md"), root.join("README.md")]
+        );
+
+        let (scanned, url_map) = scan_files(&files, true, &NormalizeOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(scanned.len(), 2);
+        let mut urls: Vec<&str> = url_map.values().map(|r| r.url.as_str()).collect();
+        urls.sort();
+        assert_eq!(urls, vec!["https://changed.com", "https://readme.com"]);
+
+        // Scope by directory and by glob
+        let docs = changed_doc_files(changed.clone(), &[root.join("docs")]);
+        assert_eq!(docs, vec![root.join("docs/changed.md")]);
+        let readme = changed_doc_files(changed, &[root.join("*.md")]);
+        assert_eq!(readme, vec![root.join("README.md")]);
+    }
+
     #[test]
     fn test_infer_categories() {
         assert!(infer_categories("docs/adr/ADR-001.md").contains(&"architecture".to_string()));
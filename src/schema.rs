@@ -1,19 +1,28 @@
 //! references.yaml schema v1.0.0
 //!
-//! Central schema for reference tracking and verification.
+//! Central schema for reference tracking and verification. The same schema is
+//! stored as JSON when the file name ends in `.json`.
 
+use crate::progress;
+use anyhow::{Context, Result};
+use clap::Args;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
 
 /// Root structure for references.yaml
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ReferencesFile {
     pub meta: Meta,
     pub references: Vec<Reference>,
 }
 
 /// Metadata about the references file
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Meta {
+    /// Project name (set by `init --interactive`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
     /// ISO date when file was created
     pub created: String,
     /// ISO datetime of last verification run (null if never)
@@ -25,7 +34,7 @@ pub struct Meta {
 }
 
 /// A single reference entry
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Reference {
     /// URL to verify
     pub url: String,
@@ -33,6 +42,9 @@ pub struct Reference {
     pub title: String,
     /// Categories for filtering (e.g., ["research", "wikipedia"])
     pub categories: Vec<String>,
+    /// Freeform tags for filtering (e.g., ["must-keep"]); not used for category inference
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
     /// Files that cite this reference
     pub cited_in: Vec<String>,
     /// Verification status
@@ -40,13 +52,24 @@ pub struct Reference {
     /// ISO datetime of last verification (null if pending)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub verified: Option<String>,
+    /// ISO datetime when scan first found this reference
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub first_seen: Option<String>,
+    /// ISO datetime of the latest scan that found this reference cited
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_seen: Option<String>,
     /// Notes (redirect target URL, error message, etc.)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub notes: Option<String>,
+    /// SHA-256 of the page's visible text at the last `verify-refs --hash`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_hash: Option<String>,
 }
 
 /// Reference verification status
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema, clap::ValueEnum,
+)]
 #[serde(rename_all = "lowercase")]
 pub enum Status {
     /// Not yet verified
@@ -55,6 +78,8 @@ pub enum Status {
     Ok,
     /// 404, 5xx, DNS failure, connection error
     Dead,
+    /// Navigation timed out (may be a slow site rather than a gone one)
+    Timeout,
     /// Ended up on different domain (link rot)
     Redirect,
     /// 200 but content blocked by paywall
@@ -69,6 +94,7 @@ impl std::fmt::Display for Status {
             Status::Pending => write!(f, "pending"),
             Status::Ok => write!(f, "ok"),
             Status::Dead => write!(f, "dead"),
+            Status::Timeout => write!(f, "timeout"),
             Status::Redirect => write!(f, "redirect"),
             Status::Paywall => write!(f, "paywall"),
             Status::Login => write!(f, "login"),
@@ -76,15 +102,140 @@ impl std::fmt::Display for Status {
     }
 }
 
+impl ReferencesFile {
+    /// Read a references file (JSON for `.json` paths, YAML otherwise)
+    pub async fn load(path: &Path) -> Result<Self> {
+        let content = tokio::fs::read_to_string(path)
+            .await
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        Self::parse(&content, path)
+    }
+
+    /// Parse file contents in the format `path` calls for
+    pub fn parse(content: &str, path: &Path) -> Result<Self> {
+        let parsed = if is_json_path(path) {
+            serde_json::from_str(content).map_err(anyhow::Error::from)
+        } else {
+            serde_yaml::from_str(content).map_err(anyhow::Error::from)
+        };
+        parsed.with_context(|| format!("Failed to parse {}", path.display()))
+    }
+
+    /// Serialize in the format `path` calls for (JSON is pretty-printed for diffs)
+    pub fn to_string_for(&self, path: &Path) -> Result<String> {
+        Ok(if is_json_path(path) {
+            serde_json::to_string_pretty(self)? + "\n"
+        } else {
+            serde_yaml::to_string(self)?
+        })
+    }
+
+    /// Write the file in the format `path` calls for
+    pub async fn save(&self, path: &Path) -> Result<()> {
+        tokio::fs::write(path, self.to_string_for(path)?)
+            .await
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+}
+
+/// Default file name with `--json`
+pub const JSON_FILE: &str = "references.json";
+
+/// Whether a references file path is stored as JSON
+pub fn is_json_path(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("json"))
+}
+
+#[derive(Args)]
+pub struct SchemaArgs {
+    /// Write the schema to a file instead of stdout
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+}
+
+/// JSON Schema for references.yaml
+pub fn json_schema() -> serde_json::Value {
+    schemars::schema_for!(ReferencesFile).to_value()
+}
+
+pub async fn run_schema(args: SchemaArgs) -> Result<()> {
+    let schema = json_schema();
+
+    match &args.output {
+        Some(path) => {
+            // Pretty-printed for editors and version control
+            let json = serde_json::to_string_pretty(&schema)?;
+            tokio::fs::write(path, json + "\n")
+                .await
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+            progress!("Wrote {}", path.display());
+        }
+        None => println!("{}", serde_json::to_string(&schema)?),
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn sample() -> serde_json::Value {
+        let yaml = std::fs::read_to_string("test-data/references.yaml").unwrap();
+        serde_yaml::from_str(&yaml).unwrap()
+    }
+
+    #[test]
+    fn test_json_schema_validates_sample() {
+        let validator = jsonschema::validator_for(&json_schema()).unwrap();
+        let mut sample = sample();
+        assert!(validator.is_valid(&sample));
+
+        sample["references"][0]["status"] = "broken".into();
+        assert!(!validator.is_valid(&sample));
+    }
+
+    #[test]
+    fn test_shipped_schema_matches() {
+        let shipped: serde_json::Value = serde_json::from_str(
+            &std::fs::read_to_string("schemas/references.v1.schema.json").unwrap(),
+        )
+        .unwrap();
+        let generated = json_schema();
+        let keys = |v: &serde_json::Value| -> Vec<String> {
+            let mut keys: Vec<String> = v.as_object().unwrap().keys().cloned().collect();
+            keys.sort();
+            keys
+        };
+        let shipped_props = &shipped["properties"];
+        assert_eq!(
+            keys(&shipped_props["meta"]["properties"]),
+            keys(&generated["$defs"]["Meta"]["properties"])
+        );
+        assert_eq!(
+            keys(&shipped_props["references"]["items"]["properties"]),
+            keys(&generated["$defs"]["Reference"]["properties"])
+        );
+
+        // A reference with every optional field, as the tool writes it
+        let mut sample = sample();
+        sample["meta"]["project"] = "docs".into();
+        let reference = &mut sample["references"][0];
+        reference["tags"] = serde_json::json!(["must-keep"]);
+        reference["first_seen"] = "2026-01-01T00:00:00+00:00".into();
+        reference["last_seen"] = "2026-02-01T00:00:00+00:00".into();
+        reference["content_hash"] = "ab".repeat(32).into();
+        let validator = jsonschema::validator_for(&shipped).unwrap();
+        assert!(validator.is_valid(&sample));
+    }
+
     #[test]
     fn test_status_display() {
         assert_eq!(Status::Pending.to_string(), "pending");
         assert_eq!(Status::Ok.to_string(), "ok");
         assert_eq!(Status::Dead.to_string(), "dead");
+        assert_eq!(Status::Timeout.to_string(), "timeout");
         assert_eq!(Status::Redirect.to_string(), "redirect");
         assert_eq!(Status::Paywall.to_string(), "paywall");
         assert_eq!(Status::Login.to_string(), "login");
@@ -97,16 +248,45 @@ mod tests {
         assert_eq!(status, Status::Ok);
     }
 
+    #[test]
+    fn test_timeout_status_roundtrip() {
+        let yaml = std::fs::read_to_string("test-data/references.yaml").unwrap();
+        let mut file = ReferencesFile::parse(&yaml, Path::new("references.yaml")).unwrap();
+        file.references[0].status = Status::Timeout;
+
+        for path in ["references.yaml", "references.json"] {
+            let path = Path::new(path);
+            let content = file.to_string_for(path).unwrap();
+            assert!(content.contains("timeout"));
+            let parsed = ReferencesFile::parse(&content, path).unwrap();
+            assert_eq!(parsed.references[0].status, Status::Timeout);
+        }
+
+        // Accepted by --status and the JSON Schema
+        assert_eq!(
+            <Status as clap::ValueEnum>::from_str("timeout", false),
+            Ok(Status::Timeout)
+        );
+        let validator = jsonschema::validator_for(&json_schema()).unwrap();
+        let mut sample = sample();
+        sample["references"][0]["status"] = "timeout".into();
+        assert!(validator.is_valid(&sample));
+    }
+
     #[test]
     fn test_serialize_reference() {
         let reference = Reference {
             url: "https://example.com".to_string(),
             title: "Example".to_string(),
             categories: vec!["test".to_string()],
+            tags: vec![],
             cited_in: vec!["README.md".to_string()],
             status: Status::Pending,
             verified: None,
+            first_seen: None,
+            last_seen: None,
             notes: None,
+            content_hash: None,
         };
         let yaml = serde_yaml::to_string(&reference).unwrap();
         assert!(yaml.contains("url: https://example.com"));
@@ -114,12 +294,14 @@ mod tests {
         // Optional fields should not appear when None
         assert!(!yaml.contains("verified:"));
         assert!(!yaml.contains("notes:"));
+        assert!(!yaml.contains("tags:"));
     }
 
     #[test]
     fn test_full_file_roundtrip() {
         let file = ReferencesFile {
             meta: Meta {
+                project: None,
                 created: "2025-12-15".to_string(),
                 last_verified: None,
                 tool: "ref".to_string(),
@@ -129,10 +311,14 @@ mod tests {
                 url: "https://example.com".to_string(),
                 title: "Example".to_string(),
                 categories: vec!["test".to_string()],
+                tags: vec![],
                 cited_in: vec!["README.md".to_string()],
                 status: Status::Ok,
                 verified: Some("2025-12-15T10:00:00Z".to_string()),
+                first_seen: None,
+                last_seen: None,
                 notes: None,
+                content_hash: None,
             }],
         };
         let yaml = serde_yaml::to_string(&file).unwrap();
@@ -140,4 +326,57 @@ mod tests {
         assert_eq!(parsed.meta.total_links, 1);
         assert_eq!(parsed.references[0].status, Status::Ok);
     }
+
+    #[test]
+    fn test_tags_roundtrip() {
+        let reference = Reference {
+            url: "https://example.com".to_string(),
+            title: "Example".to_string(),
+            categories: vec!["test".to_string()],
+            tags: vec!["must-keep".to_string(), "low-priority".to_string()],
+            cited_in: vec!["README.md".to_string()],
+            status: Status::Ok,
+            verified: None,
+            first_seen: None,
+            last_seen: None,
+            notes: None,
+            content_hash: None,
+        };
+        let yaml = serde_yaml::to_string(&reference).unwrap();
+        assert!(yaml.contains("- must-keep"));
+        let parsed: Reference = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(parsed.tags, vec!["must-keep", "low-priority"]);
+
+        // Files written before tags existed still load
+        let old =
+            "url: https://example.com\ntitle: Example\ncategories: []\ncited_in: []\nstatus: ok\n";
+        let parsed: Reference = serde_yaml::from_str(old).unwrap();
+        assert!(parsed.tags.is_empty());
+    }
+
+    #[test]
+    fn test_json_roundtrip_matches_yaml() {
+        let yaml = std::fs::read_to_string("test-data/references.yaml").unwrap();
+        let file = ReferencesFile::parse(&yaml, Path::new("references.yaml")).unwrap();
+
+        let json_path = Path::new("refs.JSON");
+        let json = file.to_string_for(json_path).unwrap();
+        assert!(json.trim_start().starts_with('{'));
+        let from_json = ReferencesFile::parse(&json, json_path).unwrap();
+
+        let yaml_path = Path::new("references.yaml");
+        let from_yaml =
+            ReferencesFile::parse(&file.to_string_for(yaml_path).unwrap(), yaml_path).unwrap();
+
+        // Same data either way
+        assert_eq!(
+            serde_json::to_value(&from_json).unwrap(),
+            serde_json::to_value(&from_yaml).unwrap()
+        );
+        assert_eq!(from_json.references.len(), file.references.len());
+
+        // JSON is also valid YAML, but a .json path is never read as YAML
+        assert!(ReferencesFile::parse(&json, Path::new("references.yaml")).is_ok());
+        assert!(ReferencesFile::parse(&yaml, json_path).is_err());
+    }
 }
@@ -0,0 +1,218 @@
+//! sitemap.xml parsing and fetching
+//!
+//! Follows sitemap indexes to their nested sitemaps; `.gz` sitemaps are decompressed.
+
+use crate::browser::http_client;
+use crate::progress;
+use anyhow::{bail, Context, Result};
+use flate2::read::GzDecoder;
+use regex::Regex;
+use std::collections::{HashSet, VecDeque};
+use std::io::Read;
+use std::time::Duration;
+
+/// Nested sitemaps fetched at most (guards against huge or cyclic indexes)
+const MAX_SITEMAPS: usize = 100;
+
+/// `<loc>` entries of a sitemap: page URLs, or nested sitemap URLs for an index
+pub fn parse_sitemap(xml: &str) -> Vec<String> {
+    let re = Regex::new(r"(?s)<(?:\w+:)?loc>\s*(?:<!\[CDATA\[)?(.*?)(?:\]\]>)?\s*</(?:\w+:)?loc>")
+        .unwrap();
+    re.captures_iter(xml)
+        .map(|cap| unescape(&cap[1]))
+        .filter(|url| !url.is_empty())
+        .collect()
+}
+
+/// Whether the document is a `<sitemapindex>` rather than a `<urlset>`
+pub fn is_sitemap_index(xml: &str) -> bool {
+    Regex::new(r"<(?:\w+:)?sitemapindex[\s>]")
+        .unwrap()
+        .is_match(xml)
+}
+
+/// All page URLs reachable from a sitemap, following indexes (deduped, in order)
+pub async fn fetch_sitemap_urls(url: &str, socks5: Option<&str>) -> Result<Vec<String>> {
+    let client = http_client(socks5)?
+        .user_agent(concat!("ref/", env!("CARGO_PKG_VERSION")))
+        .timeout(Duration::from_secs(30))
+        .build()?;
+
+    let mut queue = VecDeque::from([url.to_string()]);
+    let mut visited = HashSet::new();
+    let mut seen = HashSet::new();
+    let mut urls = Vec::new();
+
+    while let Some(sitemap_url) = queue.pop_front() {
+        if !visited.insert(sitemap_url.clone()) {
+            continue;
+        }
+        if visited.len() > MAX_SITEMAPS {
+            eprintln!("Stopping after {} sitemaps", MAX_SITEMAPS);
+            break;
+        }
+
+        progress!("Reading sitemap {}", sitemap_url);
+        let xml = match fetch_sitemap(&client, &sitemap_url).await {
+            Ok(xml) => xml,
+            // A broken nested sitemap shouldn't lose the rest of the site
+            Err(e) if sitemap_url != url => {
+                eprintln!("  Skipping: {}", e);
+                continue;
+            }
+            Err(e) => return Err(e),
+        };
+
+        let locs = parse_sitemap(&xml);
+        if is_sitemap_index(&xml) {
+            queue.extend(locs);
+        } else {
+            urls.extend(locs.into_iter().filter(|u| seen.insert(u.clone())));
+        }
+    }
+
+    Ok(urls)
+}
+
+async fn fetch_sitemap(client: &reqwest::Client, url: &str) -> Result<String> {
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .with_context(|| format!("Failed to fetch sitemap {}", url))?;
+    if !response.status().is_success() {
+        bail!("Sitemap {} returned {}", url, response.status());
+    }
+    let bytes = response.bytes().await?;
+    decode(&bytes).with_context(|| format!("Failed to read sitemap {}", url))
+}
+
+/// Sitemap body as text, gunzipping when it starts with the gzip magic bytes
+fn decode(bytes: &[u8]) -> Result<String> {
+    if bytes.starts_with(&[0x1f, 0x8b]) {
+        let mut xml = String::new();
+        GzDecoder::new(bytes).read_to_string(&mut xml)?;
+        return Ok(xml);
+    }
+    Ok(String::from_utf8_lossy(bytes).into_owned())
+}
+
+/// XML entities allowed in `<loc>` values
+fn unescape(s: &str) -> String {
+    s.trim()
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+    use wiremock::matchers::path;
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const URLSET: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+  <url><loc>https://example.com/</loc><lastmod>2024-01-01</lastmod></url>
+  <url><loc>
+    https://example.com/search?q=a&amp;page=2
+  </loc></url>
+  <url><loc><![CDATA[https://example.com/about]]></loc></url>
+</urlset>"#;
+
+    fn index(base: &str) -> String {
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+  <sitemap><loc>{base}/posts.xml</loc></sitemap>
+  <sitemap><loc>{base}/pages.xml.gz</loc></sitemap>
+</sitemapindex>"#
+        )
+    }
+
+    fn gzip(s: &str) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(s.as_bytes()).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[test]
+    fn test_parse_flat_sitemap() {
+        assert!(!is_sitemap_index(URLSET));
+        assert_eq!(
+            parse_sitemap(URLSET),
+            [
+                "https://example.com/",
+                "https://example.com/search?q=a&page=2",
+                "https://example.com/about",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_sitemap_index() {
+        let xml = index("https://example.com");
+        assert!(is_sitemap_index(&xml));
+        assert_eq!(
+            parse_sitemap(&xml),
+            [
+                "https://example.com/posts.xml",
+                "https://example.com/pages.xml.gz"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_decode_gzip() {
+        assert_eq!(decode(&gzip(URLSET)).unwrap(), URLSET);
+        assert_eq!(decode(URLSET.as_bytes()).unwrap(), URLSET);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_sitemap_index_with_two_children() {
+        let server = MockServer::start().await;
+        let posts = r#"<urlset><url><loc>https://example.com/a</loc></url>
+            <url><loc>https://example.com/b</loc></url></urlset>"#;
+        let pages = r#"<urlset><url><loc>https://example.com/b</loc></url>
+            <url><loc>https://example.com/c</loc></url></urlset>"#;
+
+        Mock::given(path("/sitemap.xml"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(index(&server.uri())))
+            .mount(&server)
+            .await;
+        Mock::given(path("/posts.xml"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(posts))
+            .mount(&server)
+            .await;
+        Mock::given(path("/pages.xml.gz"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(gzip(pages)))
+            .mount(&server)
+            .await;
+
+        let urls = fetch_sitemap_urls(&format!("{}/sitemap.xml", server.uri()), None)
+            .await
+            .unwrap();
+        assert_eq!(
+            urls,
+            [
+                "https://example.com/a",
+                "https://example.com/b",
+                "https://example.com/c"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fetch_sitemap_not_found() {
+        let server = MockServer::start().await;
+        let err = fetch_sitemap_urls(&format!("{}/sitemap.xml", server.uri()), None)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("404"));
+    }
+}
@@ -1,19 +1,26 @@
 //! update command: Self-update from GitHub releases
 //!
 //! Downloads the latest release binary from GitHub and replaces the current binary.
+//! The archive is checked against the release's published SHA-256 before install.
 
+use crate::progress;
 use anyhow::{bail, Context, Result};
 use clap::Args;
 use serde::Deserialize;
+use sha2::{Digest, Sha256};
 use std::env;
 use std::fs;
 use std::io::Write;
 use std::os::unix::fs::PermissionsExt;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 const GITHUB_REPO: &str = "royalbit/ref";
 const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+/// Wait before the first retry; doubled for each one after
+const RETRY_DELAY: Duration = Duration::from_millis(500);
+
 #[derive(Args)]
 pub struct UpdateArgs {
     /// Check for updates without installing
@@ -23,6 +30,18 @@ pub struct UpdateArgs {
     /// Force update even if already on latest version
     #[arg(long)]
     pub force: bool,
+
+    /// Restore the binary replaced by the last update
+    #[arg(long, conflicts_with_all = ["check", "force"])]
+    pub rollback: bool,
+
+    /// Timeout per request in seconds (release info, archive, checksum)
+    #[arg(long, default_value = "60")]
+    pub timeout: u64,
+
+    /// Retries on timeouts, connection errors and 5xx, with exponential backoff (never on 404)
+    #[arg(long, default_value = "3")]
+    pub retries: u8,
 }
 
 #[derive(Debug, Deserialize)]
@@ -38,13 +57,26 @@ struct Asset {
 }
 
 pub async fn run_update(args: UpdateArgs) -> Result<()> {
-    eprintln!("Current version: {}", CURRENT_VERSION);
-    eprintln!("Checking for updates...");
+    if args.rollback {
+        let current_exe = env::current_exe().context("Failed to get current executable path")?;
+        rollback_binary(&current_exe)?;
+        let output = serde_json::json!({
+            "status": "rolled_back",
+            "path": current_exe.display().to_string()
+        });
+        println!("{}", serde_json::to_string(&output)?);
+        eprintln!("Restored previous binary. Run again to undo.");
+        return Ok(());
+    }
 
-    let release = fetch_latest_release().await?;
+    progress!("Current version: {}", CURRENT_VERSION);
+    progress!("Checking for updates...");
+
+    let http = Http::new(args.timeout, args.retries)?;
+    let release = fetch_latest_release(&http).await?;
     let latest_version = release.tag_name.trim_start_matches('v');
 
-    eprintln!("Latest version: {}", latest_version);
+    progress!("Latest version: {}", latest_version);
 
     if latest_version == CURRENT_VERSION && !args.force {
         let output = serde_json::json!({
@@ -68,7 +100,7 @@ pub async fn run_update(args: UpdateArgs) -> Result<()> {
 
     // Determine target platform
     let target = get_target_triple()?;
-    eprintln!("Platform: {}", target);
+    progress!("Platform: {}", target);
 
     // Find matching asset
     let asset_name = format!("ref-{}.tar.gz", target);
@@ -78,27 +110,14 @@ pub async fn run_update(args: UpdateArgs) -> Result<()> {
         .find(|a| a.name == asset_name)
         .with_context(|| format!("No release found for platform: {}", target))?;
 
-    eprintln!("Downloading {}...", asset.name);
-
-    // Download to temp file
-    let temp_dir = env::temp_dir();
-    let archive_path = temp_dir.join(&asset.name);
-    download_file(&asset.browser_download_url, &archive_path).await?;
-
-    // Extract binary
-    eprintln!("Extracting...");
-    let binary_path = extract_binary(&archive_path, &temp_dir)?;
-
     // Get current binary path
     let current_exe = env::current_exe().context("Failed to get current executable path")?;
 
-    // Replace current binary
-    eprintln!("Installing to {}...", current_exe.display());
-    install_binary(&binary_path, &current_exe)?;
-
-    // Cleanup
-    let _ = fs::remove_file(&archive_path);
-    let _ = fs::remove_file(&binary_path);
+    install_asset(&http, &release, asset, &env::temp_dir(), |binary_path| {
+        progress!("Installing to {}...", current_exe.display());
+        install_binary(binary_path, &current_exe)
+    })
+    .await?;
 
     let output = serde_json::json!({
         "status": "updated",
@@ -113,19 +132,55 @@ pub async fn run_update(args: UpdateArgs) -> Result<()> {
     Ok(())
 }
 
-async fn fetch_latest_release() -> Result<Release> {
+/// HTTP client and retry policy shared by the update requests
+struct Http {
+    client: reqwest::Client,
+    retries: u8,
+}
+
+impl Http {
+    fn new(timeout_secs: u64, retries: u8) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .user_agent("ref-update")
+            .timeout(Duration::from_secs(timeout_secs))
+            .build()?;
+        Ok(Self { client, retries })
+    }
+
+    /// GET `url`, retrying timeouts, connection errors and 5xx with exponential backoff
+    ///
+    /// Other responses (404 included) are returned as-is on the first attempt.
+    async fn get(&self, url: &str) -> Result<reqwest::Response> {
+        let mut delay = RETRY_DELAY;
+        let mut attempt = 0;
+        loop {
+            let result = self.client.get(url).send().await;
+            let transient = match &result {
+                Ok(response) => response.status().is_server_error(),
+                Err(e) => e.is_timeout() || e.is_connect(),
+            };
+            if !transient || attempt >= self.retries {
+                return Ok(result?);
+            }
+            attempt += 1;
+            match &result {
+                Ok(response) => progress!("  {} from {}, retrying", response.status(), url),
+                Err(e) => progress!("  {}, retrying", e),
+            }
+            tokio::time::sleep(delay).await;
+            delay *= 2;
+        }
+    }
+}
+
+async fn fetch_latest_release(http: &Http) -> Result<Release> {
     let url = format!(
         "https://api.github.com/repos/{}/releases/latest",
         GITHUB_REPO
     );
 
-    let client = reqwest::Client::builder()
-        .user_agent("ref-update")
-        .build()?;
-
-    let response = client
+    let response = http
         .get(&url)
-        .send()
         .await
         .context("Failed to fetch release info")?;
 
@@ -141,24 +196,118 @@ async fn fetch_latest_release() -> Result<Release> {
     Ok(release)
 }
 
-async fn download_file(url: &str, path: &Path) -> Result<()> {
-    let client = reqwest::Client::builder()
-        .user_agent("ref-update")
-        .build()?;
+/// Download, verify, and extract `asset`, then hand the binary to `install`
+///
+/// `install` is only called once the archive matches its published checksum.
+async fn install_asset(
+    http: &Http,
+    release: &Release,
+    asset: &Asset,
+    temp_dir: &Path,
+    install: impl FnOnce(&Path) -> Result<()>,
+) -> Result<()> {
+    progress!("Downloading {}...", asset.name);
+    let archive = download_bytes(http, &asset.browser_download_url).await?;
+
+    progress!("Verifying checksum...");
+    let expected = fetch_checksum(http, release, &asset.name).await?;
+    verify_checksum(&archive, &expected, &asset.name)?;
+
+    // Write to temp file
+    let archive_path = temp_dir.join(&asset.name);
+    let mut file = fs::File::create(&archive_path)?;
+    file.write_all(&archive)?;
+
+    // Extract binary
+    progress!("Extracting...");
+    let binary_path = extract_binary(&archive_path, temp_dir)?;
 
-    let response = client.get(url).send().await.context("Failed to download")?;
+    let result = install(&binary_path);
+
+    // Cleanup
+    let _ = fs::remove_file(&archive_path);
+    let _ = fs::remove_file(&binary_path);
+
+    result
+}
+
+async fn download_bytes(http: &Http, url: &str) -> Result<Vec<u8>> {
+    let response = http.get(url).await.context("Failed to download")?;
 
     if !response.status().is_success() {
         bail!("Download failed: {}", response.status());
     }
 
-    let bytes = response.bytes().await?;
-    let mut file = fs::File::create(path)?;
-    file.write_all(&bytes)?;
+    Ok(response.bytes().await?.to_vec())
+}
 
+/// Expected SHA-256 for `asset_name`, from `<asset>.sha256` or `checksums.txt`
+async fn fetch_checksum(http: &Http, release: &Release, asset_name: &str) -> Result<String> {
+    let sidecar = format!("{}.sha256", asset_name);
+    let checksum_asset = release
+        .assets
+        .iter()
+        .find(|a| a.name == sidecar)
+        .or_else(|| release.assets.iter().find(|a| a.name == "checksums.txt"))
+        .with_context(|| {
+            format!(
+                "Release {} publishes no checksum for {} (refusing to install unverified binary)",
+                release.tag_name, asset_name
+            )
+        })?;
+
+    let content = download_bytes(http, &checksum_asset.browser_download_url)
+        .await
+        .context("Failed to download checksum")?;
+    parse_checksum(&String::from_utf8_lossy(&content), asset_name)
+        .with_context(|| format!("No checksum for {} in {}", asset_name, checksum_asset.name))
+}
+
+/// Hash for `asset_name` in `sha256sum` output (a lone hash is also accepted)
+fn parse_checksum(content: &str, asset_name: &str) -> Option<String> {
+    let mut lines = content.lines().filter(|l| !l.trim().is_empty()).peekable();
+    let mut single = None;
+
+    while let Some(line) = lines.next() {
+        let mut parts = line.split_whitespace();
+        let hash = parts.next()?;
+        match parts.next() {
+            // "<hash>  <name>" or "<hash> *<name>" (binary mode)
+            Some(name) if name.trim_start_matches('*') == asset_name => {
+                return Some(hash.to_lowercase());
+            }
+            Some(_) => {}
+            None if single.is_none() && lines.peek().is_none() => {
+                single = Some(hash.to_lowercase());
+            }
+            None => {}
+        }
+    }
+
+    single
+}
+
+/// Fail unless `bytes` hash to `expected`
+fn verify_checksum(bytes: &[u8], expected: &str, asset_name: &str) -> Result<()> {
+    let actual = sha256_hex(bytes);
+    if !actual.eq_ignore_ascii_case(expected) {
+        bail!(
+            "Checksum mismatch for {}: expected {}, got {} (download corrupted or tampered with; not installing)",
+            asset_name,
+            expected,
+            actual
+        );
+    }
     Ok(())
 }
 
+fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
 fn extract_binary(archive_path: &Path, dest_dir: &Path) -> Result<PathBuf> {
     use std::process::Command;
 
@@ -183,9 +332,14 @@ fn extract_binary(archive_path: &Path, dest_dir: &Path) -> Result<PathBuf> {
     Ok(dest_dir.join("ref"))
 }
 
+/// Backup path kept next to the binary for `--rollback`
+fn backup_path(exe: &Path) -> PathBuf {
+    exe.with_extension("old")
+}
+
 fn install_binary(src: &Path, dest: &Path) -> Result<()> {
-    // Backup current binary
-    let backup = dest.with_extension("old");
+    // Backup current binary (kept for --rollback)
+    let backup = backup_path(dest);
     if dest.exists() {
         fs::rename(dest, &backup).context("Failed to backup current binary")?;
     }
@@ -197,9 +351,6 @@ fn install_binary(src: &Path, dest: &Path) -> Result<()> {
             let mut perms = fs::metadata(dest)?.permissions();
             perms.set_mode(0o755);
             fs::set_permissions(dest, perms)?;
-
-            // Remove backup
-            let _ = fs::remove_file(&backup);
             Ok(())
         }
         Err(e) => {
@@ -212,6 +363,27 @@ fn install_binary(src: &Path, dest: &Path) -> Result<()> {
     }
 }
 
+/// Swap the `.old` backup and the current binary
+fn rollback_binary(exe: &Path) -> Result<()> {
+    let backup = backup_path(exe);
+    if !backup.exists() {
+        bail!(
+            "No previous binary to roll back to ({} not found)",
+            backup.display()
+        );
+    }
+
+    let swap = exe.with_extension("rollback");
+    fs::rename(exe, &swap).context("Failed to move current binary aside")?;
+    if let Err(e) = fs::rename(&backup, exe) {
+        let _ = fs::rename(&swap, exe);
+        return Err(e).context("Failed to restore previous binary");
+    }
+    // The replaced version becomes the new backup, so rollback can be undone
+    fs::rename(&swap, &backup).context("Failed to keep replaced binary as backup")?;
+    Ok(())
+}
+
 fn get_target_triple() -> Result<&'static str> {
     let os = env::consts::OS;
     let arch = env::consts::ARCH;
@@ -229,6 +401,154 @@ fn get_target_triple() -> Result<&'static str> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use wiremock::matchers::path;
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const SUMS: &str = "\
+9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08  ref-x86_64-apple-darwin.tar.gz
+e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855 *ref-x86_64-unknown-linux-musl.tar.gz
+";
+
+    #[test]
+    fn test_parse_checksum() {
+        assert_eq!(
+            parse_checksum(SUMS, "ref-x86_64-unknown-linux-musl.tar.gz").as_deref(),
+            Some("e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855")
+        );
+        assert_eq!(
+            parse_checksum(SUMS, "ref-aarch64-apple-darwin.tar.gz"),
+            None
+        );
+        assert_eq!(
+            parse_checksum("ABC123\n", "x.tar.gz").as_deref(),
+            Some("abc123")
+        );
+    }
+
+    #[test]
+    fn test_verify_checksum() {
+        // SHA-256 of the empty string
+        let empty = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+        assert!(verify_checksum(b"", empty, "a.tar.gz").is_ok());
+        let err = verify_checksum(b"tampered", empty, "a.tar.gz").unwrap_err();
+        assert!(err.to_string().contains("Checksum mismatch"));
+    }
+
+    #[test]
+    fn test_install_and_rollback() {
+        let temp = tempfile::tempdir().unwrap();
+        let exe = temp.path().join("ref");
+        let new = temp.path().join("ref-new");
+        fs::write(&exe, "v1").unwrap();
+        fs::write(&new, "v2").unwrap();
+
+        install_binary(&new, &exe).unwrap();
+        assert_eq!(fs::read_to_string(&exe).unwrap(), "v2");
+        assert_eq!(fs::read_to_string(backup_path(&exe)).unwrap(), "v1");
+
+        rollback_binary(&exe).unwrap();
+        assert_eq!(fs::read_to_string(&exe).unwrap(), "v1");
+        assert_eq!(fs::read_to_string(backup_path(&exe)).unwrap(), "v2");
+        assert!(!exe.with_extension("rollback").exists());
+    }
+
+    #[test]
+    fn test_rollback_without_backup() {
+        let temp = tempfile::tempdir().unwrap();
+        let exe = temp.path().join("ref");
+        fs::write(&exe, "v1").unwrap();
+
+        let err = rollback_binary(&exe).unwrap_err();
+        assert!(err.to_string().contains("No previous binary"));
+        assert_eq!(fs::read_to_string(&exe).unwrap(), "v1");
+    }
+
+    #[tokio::test]
+    async fn test_checksum_mismatch_skips_install() {
+        let server = MockServer::start().await;
+        let name = "ref-x86_64-unknown-linux-musl.tar.gz";
+        Mock::given(path(format!("/{}", name)))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_bytes(b"not the real archive".to_vec()),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(path("/checksums.txt"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(SUMS))
+            .mount(&server)
+            .await;
+
+        let asset = |name: &str| Asset {
+            name: name.to_string(),
+            browser_download_url: format!("{}/{}", server.uri(), name),
+        };
+        let release = Release {
+            tag_name: "v9.9.9".to_string(),
+            assets: vec![asset(name), asset("checksums.txt")],
+        };
+
+        let temp = tempfile::tempdir().unwrap();
+        let mut installed = false;
+        let http = Http::new(5, 0).unwrap();
+        let result = install_asset(&http, &release, &release.assets[0], temp.path(), |_| {
+            installed = true;
+            Ok(())
+        })
+        .await;
+
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("Checksum mismatch"));
+        assert!(!installed);
+        assert!(!temp.path().join(name).exists());
+    }
+
+    #[tokio::test]
+    async fn test_download_retries_server_errors() {
+        let server = MockServer::start().await;
+        Mock::given(path("/ref.tar.gz"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(2)
+            .expect(2)
+            .mount(&server)
+            .await;
+        Mock::given(path("/ref.tar.gz"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(b"archive".to_vec()))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let http = Http::new(5, 3).unwrap();
+        let url = format!("{}/ref.tar.gz", server.uri());
+        assert_eq!(download_bytes(&http, &url).await.unwrap(), b"archive");
+
+        // Out of retries: the last 5xx is reported
+        let http = Http::new(5, 0).unwrap();
+        Mock::given(path("/flaky.tar.gz"))
+            .respond_with(ResponseTemplate::new(502))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let url = format!("{}/flaky.tar.gz", server.uri());
+        let err = download_bytes(&http, &url).await.unwrap_err();
+        assert!(err.to_string().contains("502"));
+    }
+
+    #[tokio::test]
+    async fn test_download_does_not_retry_404() {
+        let server = MockServer::start().await;
+        Mock::given(path("/missing.tar.gz"))
+            .respond_with(ResponseTemplate::new(404))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let http = Http::new(5, 3).unwrap();
+        let url = format!("{}/missing.tar.gz", server.uri());
+        let err = download_bytes(&http, &url).await.unwrap_err();
+        assert!(err.to_string().contains("404"));
+    }
 
     #[test]
     fn test_get_target_triple() {
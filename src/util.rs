@@ -0,0 +1,120 @@
+//! Small string and URL helpers shared by the commands
+
+/// Find the largest valid char boundary <= pos
+pub fn floor_char_boundary(s: &str, pos: usize) -> usize {
+    if pos >= s.len() {
+        return s.len();
+    }
+    s.char_indices()
+        .map(|(i, _)| i)
+        .take_while(|&i| i <= pos)
+        .last()
+        .unwrap_or(0)
+}
+
+/// Cut to at most `max` bytes including a trailing "...", never inside a character
+pub fn truncate(s: &str, max: usize) -> String {
+    if s.len() <= max {
+        s.to_string()
+    } else {
+        let end = floor_char_boundary(s, max.saturating_sub(3));
+        format!("{}...", &s[..end])
+    }
+}
+
+/// Like [`truncate`], but backs up to a word break when one is close
+pub fn truncate_words(s: &str, max: usize) -> String {
+    if s.len() <= max {
+        return s.to_string();
+    }
+    let end = floor_char_boundary(s, max.saturating_sub(3));
+    let end = match s[..end].rfind(char::is_whitespace) {
+        Some(space) if space > end.saturating_sub(50) => space,
+        _ => end,
+    };
+    format!("{}...", s[..end].trim_end())
+}
+
+/// Host of a URL without a leading `www.`, for grouping and same-site checks
+pub fn bare_host(url: &str) -> Option<String> {
+    url::Url::parse(url)
+        .ok()?
+        .host_str()
+        .map(|h| h.trim_start_matches("www.").to_string())
+}
+
+/// For `skip_serializing_if` on counters
+pub fn is_zero(n: &usize) -> bool {
+    *n == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_floor_char_boundary() {
+        let s = "hello─world"; // ─ is at bytes 5..8
+        assert_eq!(floor_char_boundary(s, 5), 5); // exactly at boundary
+        assert_eq!(floor_char_boundary(s, 6), 5); // inside ─, goes back
+        assert_eq!(floor_char_boundary(s, 7), 5); // inside ─, goes back
+        assert_eq!(floor_char_boundary(s, 8), 8); // after ─
+        assert_eq!(floor_char_boundary(s, 99), s.len());
+    }
+
+    #[test]
+    fn test_bare_host() {
+        assert_eq!(
+            bare_host("https://www.example.com/a").as_deref(),
+            Some("example.com")
+        );
+        assert_eq!(
+            bare_host("https://docs.example.com").as_deref(),
+            Some("docs.example.com")
+        );
+        assert_eq!(bare_host("mailto:a@b.com"), None);
+        assert_eq!(bare_host("not a url"), None);
+    }
+
+    #[test]
+    fn test_truncate() {
+        assert_eq!(truncate("short", 10), "short");
+        assert_eq!(truncate("this is a long string", 10), "this is...");
+        // Multi-byte chars: ─ is 3 bytes, should not panic
+        let dashes = "─".repeat(100);
+        let result = truncate(&dashes, 20);
+        assert!(result.ends_with("..."));
+        assert!(result.len() <= 20);
+
+        // Byte 7 falls inside the emoji (bytes 4..8) and byte 9 inside é
+        let url = "http🦀é/path/to/page";
+        assert_eq!(truncate(url, 10), "http...");
+        assert_eq!(truncate(url, 12), "http🦀...");
+        assert_eq!(truncate(url, 13), "http🦀é...");
+        for max in 0..url.len() {
+            assert!(truncate(url, max).len() <= max.max(3));
+        }
+    }
+
+    #[test]
+    fn test_truncate_words() {
+        assert_eq!(truncate_words("short", 10), "short");
+        assert_eq!(truncate_words("this is a long string", 12), "this is...");
+
+        // Multibyte chars straddling the cut: no panic, no split codepoint
+        let text = "Résumé naïve café ".repeat(20);
+        for max in 12..80 {
+            let cut = truncate_words(&text, max);
+            assert!(cut.len() <= max, "{}: {}", max, cut);
+            assert!(cut.ends_with("..."));
+            let kept = cut.trim_end_matches("...");
+            assert!(text.starts_with(kept));
+            // Ends on a whole word
+            assert!(text[kept.len()..].starts_with(' '), "{}: {}", max, cut);
+        }
+
+        // No space nearby: cut mid-word at a char boundary
+        let cjk = "漢字".repeat(50);
+        assert_eq!(truncate_words(&cjk, 20), "漢字漢字漢...");
+    }
+}
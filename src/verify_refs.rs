@@ -7,18 +7,29 @@
 //! - paywall: 200 but content blocked by paywall
 //! - login: 200 but login required
 
-use crate::browser::BrowserPool;
-use crate::schema::{ReferencesFile, Status};
-use anyhow::{Context, Result};
-use chrono::Utc;
+use crate::browser::{format_redirects, parse_connect, retry_navigation, BrowserPool, WaitUntil};
+use crate::cookies::load_cookies;
+use crate::doi::{find_doi, lookup_doi, DoiLookup, CROSSREF_API, HANDLE_API};
+use crate::events;
+use crate::fetch::extract_title;
+use crate::filter::UrlFilter;
+use crate::normalize::is_cross_domain;
+use crate::output::OutputFormat;
+use crate::patterns::WallPatterns;
+use crate::progress;
+use crate::schema::{Reference, ReferencesFile, Status};
+use crate::util::{is_zero, truncate};
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
 use clap::Args;
-use futures::future::join_all;
+use futures::stream::{self, StreamExt};
 use scraper::{Html, Selector};
 use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::future::Future;
 use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::sync::Mutex;
-use url::Url;
 
 #[derive(Args)]
 pub struct VerifyRefsArgs {
@@ -33,13 +44,112 @@ pub struct VerifyRefsArgs {
     #[arg(long, short)]
     pub category: Option<Vec<String>>,
 
+    /// Filter by tag (can be used multiple times)
+    #[arg(long, short)]
+    pub tag: Option<Vec<String>>,
+
+    /// Only re-verify references currently in this status (can be used multiple times)
+    #[arg(long, short, value_enum)]
+    pub status: Option<Vec<Status>>,
+
+    /// Only verify URLs matching this regex (repeatable)
+    #[arg(long, value_name = "REGEX")]
+    pub include: Vec<String>,
+
+    /// Skip URLs matching this regex (repeatable, wins over --include)
+    #[arg(long, value_name = "REGEX")]
+    pub exclude: Vec<String>,
+
     /// Timeout per URL in milliseconds
     #[arg(long, default_value = "30000")]
     pub timeout: u64,
 
+    /// Retries on network failure, with exponential backoff (never on 404)
+    #[arg(long, default_value = "1")]
+    pub retries: u8,
+
     /// Dry run - don't write changes back to file
     #[arg(long)]
     pub dry_run: bool,
+
+    /// Cookie file for login-gated pages (Netscape or JSON array)
+    #[arg(long)]
+    pub cookies: Option<PathBuf>,
+
+    /// Attach to a running Chrome (ws:// or http://host:9222) instead of launching one
+    #[arg(
+        long,
+        visible_alias = "remote-debugging-url",
+        env = "REF_BROWSER_URL",
+        value_parser = parse_connect
+    )]
+    pub connect: Option<String>,
+
+    /// Route traffic and DNS through a SOCKS5 proxy (host:port), no local lookups
+    #[arg(long, value_name = "HOST:PORT", conflicts_with = "connect")]
+    pub socks5: Option<String>,
+
+    /// User agent string, or "chrome-latest" for the browser's own version
+    #[arg(long, value_name = "UA")]
+    pub user_agent: Option<String>,
+
+    /// Load images, media, fonts and stylesheets (blocked by default for speed)
+    #[arg(long)]
+    pub load_resources: bool,
+
+    /// Page lifecycle event that ends navigation
+    #[arg(long, value_enum, default_value = "load")]
+    pub wait_until: WaitUntil,
+
+    /// Wait up to this long for bot challenge pages ("Just a moment...") to clear
+    #[arg(long, value_name = "SECS", default_value = "0")]
+    pub wait_challenge: u64,
+
+    /// Only re-verify entries last verified more than this many days ago
+    #[arg(long, value_name = "DAYS")]
+    pub max_age: Option<u64>,
+
+    /// YAML file with extra paywall/login phrases and selectors (merged with built-ins)
+    #[arg(long, value_name = "FILE")]
+    pub patterns: Option<PathBuf>,
+
+    /// Flag pages whose visible text is shorter than this (JS challenges, blocks)
+    #[arg(long, value_name = "CHARS", visible_alias = "min-content-length")]
+    pub min_content: Option<usize>,
+
+    /// Treat redirects within the same registrable domain (docs.example.com -> example.com) as ok
+    #[arg(long)]
+    pub allow_subdomain_redirects: bool,
+
+    /// Print one NDJSON line per reference as it is verified, then the summary
+    #[arg(long, visible_alias = "jsonl")]
+    pub stream: bool,
+
+    /// Replace titles that are just the URL with the page's <title>/og:title
+    #[arg(long)]
+    pub update_titles: bool,
+
+    /// Store a SHA-256 of each page's visible text; note "content changed" when it differs
+    #[arg(long)]
+    pub hash: bool,
+
+    /// Look up DOIs (doi.org links, /10.xxxx/ paths) on Crossref, then doi.org:
+    /// unknown DOIs are dead, Crossref ones add title, authors and year to notes
+    #[arg(long)]
+    pub enrich_doi: bool,
+
+    /// Summary format
+    #[arg(long, value_enum, default_value = "json", conflicts_with = "stream")]
+    pub format: OutputFormat,
+}
+
+/// Per-reference line printed with --stream
+#[derive(Debug, Serialize)]
+pub struct StreamedResult<'a> {
+    pub url: &'a str,
+    pub status: Status,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notes: Option<&'a str>,
 }
 
 /// Summary of verification results
@@ -49,101 +159,210 @@ pub struct VerifySummary {
     pub verified: usize,
     pub ok: usize,
     pub dead: usize,
+    pub timeout: usize,
     pub redirect: usize,
     pub paywall: usize,
     pub login: usize,
     pub skipped: usize,
+    /// Stuck on a bot challenge page (status and date left as they were)
+    #[serde(skip_serializing_if = "is_zero")]
+    pub challenged: usize,
+    /// Titles filled in by --update-titles
+    #[serde(skip_serializing_if = "is_zero")]
+    pub titles_updated: usize,
+    /// Pages whose text changed since the stored --hash
+    #[serde(skip_serializing_if = "is_zero")]
+    pub content_changed: usize,
 }
 
-/// Output for JSON
+/// verify-refs report (JSON or YAML)
 #[derive(Debug, Serialize)]
 pub struct VerifyOutput {
     pub summary: VerifySummary,
+    /// URLs skipped by --include/--exclude
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub excluded: Vec<String>,
     pub file: String,
     pub timestamp: String,
 }
 
 pub async fn run_verify_refs(args: VerifyRefsArgs) -> Result<()> {
-    // Read and parse references.yaml
-    let content = tokio::fs::read_to_string(&args.file)
-        .await
-        .with_context(|| format!("Failed to read {}", args.file.display()))?;
+    // Read and parse references.yaml (or .json)
+    let refs_file = ReferencesFile::load(&args.file).await?;
+
+    let cookies = match &args.cookies {
+        Some(path) => load_cookies(path).await?,
+        None => vec![],
+    };
 
-    let refs_file: ReferencesFile =
-        serde_yaml::from_str(&content).context("Failed to parse references.yaml")?;
+    let patterns = Arc::new(match &args.patterns {
+        Some(path) => WallPatterns::load(path).await?,
+        None => WallPatterns::builtin(),
+    });
+
+    let filter = UrlFilter::new(&args.include, &args.exclude)?;
 
     let total = refs_file.references.len();
-    eprintln!("Loaded {} references from {}", total, args.file.display());
+    progress!("Loaded {} references from {}", total, args.file.display());
 
-    // Filter by category if specified
-    let indices_to_verify: Vec<usize> = refs_file
-        .references
-        .iter()
-        .enumerate()
-        .filter(|(_, r)| {
-            if let Some(cats) = &args.category {
-                r.categories.iter().any(|c| cats.contains(c))
-            } else {
-                true
-            }
-        })
-        .map(|(i, _)| i)
+    // Filter by category, tag, current status and staleness
+    let indices_to_verify = select_references(
+        &refs_file,
+        args.category.as_deref(),
+        args.tag.as_deref(),
+        args.status.as_deref(),
+        args.max_age,
+        Utc::now(),
+    );
+
+    // --include/--exclude: keep excluded URLs for the report
+    let (indices_to_verify, excluded): (Vec<usize>, Vec<usize>) = indices_to_verify
+        .into_iter()
+        .partition(|&idx| filter.allows(&refs_file.references[idx].url));
+    let excluded: Vec<String> = excluded
+        .into_iter()
+        .map(|idx| refs_file.references[idx].url.clone())
         .collect();
 
     let to_verify = indices_to_verify.len();
     let skipped = total - to_verify;
 
     if to_verify == 0 {
-        eprintln!("No references to verify (all filtered out)");
+        progress!("No references to verify (all filtered out or recently verified)");
         return Ok(());
     }
 
-    eprintln!(
+    progress!(
         "Verifying {} references ({} parallel)...",
-        to_verify, args.parallel
+        to_verify,
+        args.parallel
     );
 
     // Create browser pool
-    let pool = Arc::new(BrowserPool::new(args.parallel).await?);
+    let pool = Arc::new(
+        BrowserPool::launch_or_connect(
+            args.parallel,
+            args.connect.as_deref(),
+            args.socks5.as_deref(),
+        )
+        .await?
+        .with_cookies(&cookies)
+        .with_user_agent(args.user_agent.as_deref())
+        .await?
+        .with_resources(args.load_resources)
+        .with_challenge_wait(args.wait_challenge)
+        .with_wait_until(args.wait_until),
+    );
     let timeout = args.timeout;
+    let retries = args.retries;
+    let min_content = args.min_content;
+    let allow_subdomains = args.allow_subdomain_redirects;
+    let stream = args.stream;
+    let update_titles = args.update_titles;
+    let hash_content = args.hash;
+    let doi_client = if args.enrich_doi {
+        Some(Arc::new(crate::doi::client(args.socks5.as_deref())?))
+    } else {
+        None
+    };
 
     // Shared mutable references for updating
     let refs_file = Arc::new(Mutex::new(refs_file));
 
-    // Verify each reference
-    let tasks: Vec<_> = indices_to_verify
-        .into_iter()
-        .map(|idx| {
-            let pool = Arc::clone(&pool);
-            let refs_file = Arc::clone(&refs_file);
-            tokio::spawn(async move {
-                let url = {
-                    let file = refs_file.lock().await;
-                    file.references[idx].url.clone()
-                };
+    // Verify each reference, at most --parallel at a time
+    events::start(to_verify);
+    let results: Vec<(Status, bool, bool)> = run_bounded(indices_to_verify, args.parallel, |idx| {
+        let pool = Arc::clone(&pool);
+        let refs_file = Arc::clone(&refs_file);
+        let patterns = Arc::clone(&patterns);
+        let doi_client = doi_client.clone();
+        async move {
+            let url = {
+                let file = refs_file.lock().await;
+                file.references[idx].url.clone()
+            };
 
-                eprintln!("  -> {}", truncate(&url, 60));
-                let result = verify_url(&pool, &url, timeout).await;
+            progress!("  -> {}", truncate(&url, 60));
+            let doi = match (&doi_client, find_doi(&url)) {
+                (Some(client), Some(doi)) => Some((
+                    lookup_doi(client, CROSSREF_API, HANDLE_API, &doi).await,
+                    doi,
+                )),
+                _ => None,
+            };
+            let mut result = match doi {
+                // The registry has never heard of it: no need to load the page
+                Some((Ok(DoiLookup::Unknown), doi)) => VerifyResult {
+                    status: Status::Dead,
+                    notes: Some(format!("DOI not found: {}", doi)),
+                    title: None,
+                    content_hash: None,
+                },
+                lookup => {
+                    let mut result = verify_url(
+                        &pool,
+                        &url,
+                        timeout,
+                        retries,
+                        &patterns,
+                        min_content,
+                        allow_subdomains,
+                    )
+                    .await;
+                    let note = match lookup {
+                        Some((Ok(DoiLookup::Work(work)), doi)) => Some(work.summary(&doi)),
+                        Some((Ok(DoiLookup::Registered), doi)) => {
+                            Some(format!("DOI {} (not in Crossref)", doi))
+                        }
+                        Some((Err(e), _)) => Some(format!("DOI lookup failed: {:#}", e)),
+                        _ => None,
+                    };
+                    if let Some(note) = note {
+                        append_note(&mut result.notes, &note);
+                    }
+                    result
+                }
+            };
 
-                // Update the reference
-                {
-                    let mut file = refs_file.lock().await;
-                    file.references[idx].status = result.status;
-                    file.references[idx].verified = Some(Utc::now().to_rfc3339());
-                    file.references[idx].notes = result.notes;
+            // Update the reference
+            let (title_updated, content_changed) = {
+                let mut file = refs_file.lock().await;
+                let reference = &mut file.references[idx];
+                let content_changed = match result.content_hash.take() {
+                    Some(hash) if hash_content => update_content_hash(reference, hash),
+                    _ => false,
+                };
+                if content_changed {
+                    append_note(&mut result.notes, "content changed");
                 }
+                // Pending = never saw the page: keep the last verdict and its date
+                if result.status != Status::Pending {
+                    reference.status = result.status;
+                    reference.verified = Some(Utc::now().to_rfc3339());
+                }
+                reference.notes = result.notes.clone();
+                let title_updated = update_titles && upgrade_title(reference, result.title);
+                (title_updated, content_changed)
+            };
 
-                result.status
-            })
-        })
-        .collect();
+            events::item(&url, result.status == Status::Ok);
+            if stream {
+                let line = StreamedResult {
+                    url: &url,
+                    status: result.status,
+                    notes: result.notes.as_deref(),
+                };
+                if let Ok(json) = serde_json::to_string(&line) {
+                    println!("{}", json);
+                }
+            }
 
-    // Await all tasks
-    let statuses: Vec<Status> = join_all(tasks)
-        .await
-        .into_iter()
-        .filter_map(|r| r.ok())
-        .collect();
+            (result.status, title_updated, content_changed)
+        }
+    })
+    .await;
+
+    events::done();
 
     // Close browser
     if let Ok(pool) = Arc::try_unwrap(pool) {
@@ -153,23 +372,28 @@ pub async fn run_verify_refs(args: VerifyRefsArgs) -> Result<()> {
     // Compute summary
     let mut summary = VerifySummary {
         total,
-        verified: statuses.len(),
+        verified: results.len(),
         ok: 0,
         dead: 0,
+        timeout: 0,
         redirect: 0,
         paywall: 0,
         login: 0,
         skipped,
+        challenged: 0,
+        titles_updated: results.iter().filter(|(_, updated, _)| *updated).count(),
+        content_changed: results.iter().filter(|(_, _, changed)| *changed).count(),
     };
 
-    for status in &statuses {
+    for (status, _, _) in &results {
         match status {
             Status::Ok => summary.ok += 1,
             Status::Dead => summary.dead += 1,
+            Status::Timeout => summary.timeout += 1,
             Status::Redirect => summary.redirect += 1,
             Status::Paywall => summary.paywall += 1,
             Status::Login => summary.login += 1,
-            Status::Pending => {}
+            Status::Pending => summary.challenged += 1,
         }
     }
 
@@ -182,65 +406,156 @@ pub async fn run_verify_refs(args: VerifyRefsArgs) -> Result<()> {
 
     // Write back to file (unless dry run)
     if !args.dry_run {
-        let file = refs_file.lock().await;
-        let yaml = serde_yaml::to_string(&*file)?;
-        tokio::fs::write(&args.file, yaml)
-            .await
-            .with_context(|| format!("Failed to write {}", args.file.display()))?;
-        eprintln!("Updated {}", args.file.display());
+        refs_file.lock().await.save(&args.file).await?;
+        progress!("Updated {}", args.file.display());
     } else {
-        eprintln!("Dry run - file not modified");
+        progress!("Dry run - file not modified");
     }
 
-    // Output JSON summary
     let output = VerifyOutput {
         summary,
+        excluded,
         file: args.file.display().to_string(),
         timestamp: Utc::now().to_rfc3339(),
     };
-    println!("{}", serde_json::to_string(&output)?);
+    print!("{}", args.format.render(&output)?);
 
     Ok(())
 }
 
+/// Run `task` for each index with at most `parallel` in flight, in completion order
+///
+/// Tasks are polled on the current task rather than spawned, so a large file
+/// never has more than `parallel` verifications alive at once.
+async fn run_bounded<T, F, Fut>(indices: Vec<usize>, parallel: usize, task: F) -> Vec<T>
+where
+    F: Fn(usize) -> Fut,
+    Fut: Future<Output = T>,
+{
+    stream::iter(indices.into_iter().map(task))
+        .buffer_unordered(parallel.max(1))
+        .collect()
+        .await
+}
+
+/// Indices of references to verify (matching category, tag and status, older than max age)
+fn select_references(
+    refs_file: &ReferencesFile,
+    categories: Option<&[String]>,
+    tags: Option<&[String]>,
+    statuses: Option<&[Status]>,
+    max_age_days: Option<u64>,
+    now: DateTime<Utc>,
+) -> Vec<usize> {
+    refs_file
+        .references
+        .iter()
+        .enumerate()
+        .filter(|(_, r)| {
+            if let Some(cats) = categories {
+                r.categories.iter().any(|c| cats.contains(c))
+            } else {
+                true
+            }
+        })
+        .filter(|(_, r)| {
+            if let Some(tags) = tags {
+                r.tags.iter().any(|t| tags.contains(t))
+            } else {
+                true
+            }
+        })
+        .filter(|(_, r)| statuses.is_none_or(|s| s.contains(&r.status)))
+        .filter(|(_, r)| match max_age_days {
+            Some(days) => is_stale(r, days, now),
+            None => true,
+        })
+        .map(|(i, _)| i)
+        .collect()
+}
+
+/// Whether a reference needs re-verification (never verified or older than max age)
+fn is_stale(reference: &Reference, max_age_days: u64, now: DateTime<Utc>) -> bool {
+    let Some(verified) = reference
+        .verified
+        .as_deref()
+        .and_then(|v| DateTime::parse_from_rfc3339(v).ok())
+    else {
+        // Never verified, or unparseable timestamp
+        return true;
+    };
+    let max_age = i64::try_from(max_age_days)
+        .ok()
+        .and_then(Duration::try_days)
+        .unwrap_or(Duration::MAX);
+    now.signed_duration_since(verified.with_timezone(&Utc)) > max_age
+}
+
 /// Result of verifying a single URL
 struct VerifyResult {
     status: Status,
     notes: Option<String>,
+    /// Page title, for pages that loaded with readable content
+    title: Option<String>,
+    /// SHA-256 of the visible text, for pages that loaded with readable content
+    content_hash: Option<String>,
 }
 
-async fn verify_url(pool: &BrowserPool, url: &str, timeout: u64) -> VerifyResult {
+/// Replace a title that is just the URL (as stored by scan) with the page title
+fn upgrade_title(reference: &mut Reference, title: Option<String>) -> bool {
+    match title {
+        Some(title) if reference.title == reference.url && title != reference.url => {
+            reference.title = title;
+            true
+        }
+        _ => false,
+    }
+}
+
+async fn verify_url(
+    pool: &BrowserPool,
+    url: &str,
+    timeout: u64,
+    retries: u8,
+    patterns: &WallPatterns,
+    min_content: Option<usize>,
+    allow_subdomains: bool,
+) -> VerifyResult {
     let page = match pool.new_page().await {
         Ok(p) => p,
         Err(e) => {
             return VerifyResult {
                 status: Status::Dead,
                 notes: Some(format!("Browser error: {}", e)),
+                title: None,
+                content_hash: None,
             }
         }
     };
 
-    // Parse original URL to get host
-    let original_host = match Url::parse(url) {
-        Ok(u) => u.host_str().map(|s| s.to_string()),
-        Err(_) => None,
-    };
-
-    let nav = match page.goto(url, timeout).await {
+    let nav = match retry_navigation(retries, || page.goto(url, timeout)).await {
         Ok(n) => n,
         Err(e) => {
             return VerifyResult {
                 status: Status::Dead,
                 notes: Some(format!("Navigation error: {}", e)),
+                title: None,
+                content_hash: None,
             }
         }
     };
 
-    // Check for navigation errors (DNS, connection, etc.)
+    // Check for navigation errors (DNS, connection, etc.); timeouts may be transient
     if nav.error.is_some() {
         return VerifyResult {
-            status: Status::Dead,
+            status: if nav.timed_out() {
+                Status::Timeout
+            } else {
+                Status::Dead
+            },
             notes: nav.error,
+            title: None,
+            content_hash: None,
         };
     }
 
@@ -249,25 +564,44 @@ async fn verify_url(pool: &BrowserPool, url: &str, timeout: u64) -> VerifyResult
         return VerifyResult {
             status: Status::Dead,
             notes: Some(format!("HTTP {}", nav.status)),
+            title: None,
+            content_hash: None,
         };
     }
 
-    // Get final URL to check for cross-domain redirect
-    let final_url = page.current_url().await;
-    if let (Some(orig), Some(final_u)) = (&original_host, &final_url) {
-        if let Ok(parsed) = Url::parse(final_u) {
-            if let Some(final_host) = parsed.host_str() {
-                // Normalize hosts (remove www prefix for comparison)
-                let orig_norm = orig.trim_start_matches("www.");
-                let final_norm = final_host.trim_start_matches("www.");
-                if orig_norm != final_norm {
-                    return VerifyResult {
-                        status: Status::Redirect,
-                        notes: Some(final_u.clone()),
-                    };
-                }
-            }
+    // Get final URL to check for cross-domain redirect, by the server or the page itself
+    let client = page.client_redirect().await;
+    let final_url = match &client {
+        Some(redirect) => Some(redirect.url.clone()),
+        None => page.current_url().await,
+    };
+    if let Some(final_u) = final_url.filter(|u| is_cross_domain(url, u, allow_subdomains)) {
+        let mut via = Vec::new();
+        if !nav.redirects.is_empty() {
+            via.push(format_redirects(&nav.redirects));
         }
+        via.extend(client.map(|redirect| redirect.kind.to_string()));
+        let notes = if via.is_empty() {
+            final_u
+        } else {
+            format!("{} (via {})", final_u, via.join(", "))
+        };
+        return VerifyResult {
+            status: Status::Redirect,
+            notes: Some(notes),
+            title: None,
+            content_hash: None,
+        };
+    }
+
+    // Anti-bot interstitial: the real page was never seen, so no verdict
+    if nav.challenge {
+        return VerifyResult {
+            status: Status::Pending,
+            notes: Some("bot challenge (not verified)".to_string()),
+            title: None,
+            content_hash: None,
+        };
     }
 
     // Get page content to detect paywall/login
@@ -277,144 +611,482 @@ async fn verify_url(pool: &BrowserPool, url: &str, timeout: u64) -> VerifyResult
             return VerifyResult {
                 status: Status::Ok,
                 notes: None,
+                title: None,
+                content_hash: None,
             }
         }
     };
 
+    // CMSs often serve "page not found" with a 200
+    if nav.status == 200 && is_soft_404(&html, patterns) {
+        return VerifyResult {
+            status: Status::Dead,
+            notes: Some("soft 404".to_string()),
+            title: None,
+            content_hash: None,
+        };
+    }
+
     // Check for paywall indicators
-    if is_paywall(&html) {
+    if is_paywall(&html, patterns) {
         return VerifyResult {
             status: Status::Paywall,
             notes: Some("Paywall detected".to_string()),
+            title: None,
+            content_hash: None,
         };
     }
 
     // Check for login wall indicators
-    if is_login_wall(&html) {
+    if is_login_wall(&html, patterns) {
         return VerifyResult {
             status: Status::Login,
             notes: Some("Login required".to_string()),
+            title: None,
+            content_hash: None,
+        };
+    }
+
+    let title = extract_title(&Html::parse_document(&html));
+    let content_hash = Some(content_hash(&html));
+
+    // Loaded, but nothing to read: stays ok so it can be reviewed by hand
+    if min_content.is_some_and(|min| visible_text_len(&html) < min) {
+        return VerifyResult {
+            status: Status::Ok,
+            notes: Some("thin content, possible block".to_string()),
+            title,
+            content_hash,
         };
     }
 
     VerifyResult {
         status: Status::Ok,
-        notes: None,
+        notes: (!nav.redirects.is_empty())
+            .then(|| format!("redirected: {}", format_redirects(&nav.redirects))),
+        title,
+        content_hash,
     }
 }
 
 /// Detect paywall indicators in HTML
-fn is_paywall(html: &str) -> bool {
+fn is_paywall(html: &str, patterns: &WallPatterns) -> bool {
     let doc = Html::parse_document(html);
-    let lower = html.to_lowercase();
-
-    // Common paywall indicators
-    let paywall_patterns = [
-        "subscribe to continue",
-        "subscription required",
-        "premium content",
-        "paywall",
-        "member-only",
-        "members only",
-        "unlock this article",
-        "purchase to read",
-        "buy now to read",
-        "paid subscribers",
-    ];
-
-    for pattern in paywall_patterns {
-        if lower.contains(pattern) {
-            return true;
-        }
-    }
+    patterns.paywall.matches(&doc, &html.to_lowercase())
+}
 
-    // Check for paywall-specific CSS classes/IDs
-    let paywall_selectors = [
-        "[class*='paywall']",
-        "[id*='paywall']",
-        "[class*='subscription-wall']",
-        "[class*='piano-offer']",
-        "[class*='premium-wall']",
-    ];
-
-    for sel_str in paywall_selectors {
-        if let Ok(sel) = Selector::parse(sel_str) {
-            if doc.select(&sel).next().is_some() {
-                return true;
-            }
-        }
+/// Detect login wall indicators in HTML
+fn is_login_wall(html: &str, patterns: &WallPatterns) -> bool {
+    let doc = Html::parse_document(html);
+    patterns.login.matches(&doc, &html.to_lowercase())
+}
+
+/// Detect a not-found page served with a 200 status
+///
+/// Conservative: a phrase must make up (nearly) all of the title or `<h1>`, or
+/// one segment of a "Page Not Found | Site" title. Body text is never checked.
+fn is_soft_404(html: &str, patterns: &WallPatterns) -> bool {
+    let doc = Html::parse_document(html);
+    if patterns.not_found.matches_selectors(&doc) {
+        return true;
     }
 
-    false
+    let Ok(sel) = Selector::parse("title, h1") else {
+        return false;
+    };
+    doc.select(&sel)
+        .map(|el| {
+            el.text()
+                .collect::<String>()
+                .to_lowercase()
+                .replace('\u{2019}', "'")
+        })
+        .any(|heading| {
+            heading
+                .split(['|', '-', '\u{2013}', '\u{2014}', '\u{b7}', ':'])
+                .any(|segment| is_not_found_heading(segment, &patterns.not_found.phrases))
+        })
 }
 
-/// Detect login wall indicators in HTML
-fn is_login_wall(html: &str) -> bool {
+/// Characters of visible body text (whitespace collapsed, scripts and styles ignored)
+fn visible_text_len(html: &str) -> usize {
+    visible_text(html).chars().count()
+}
+
+/// Visible body text with whitespace collapsed, scripts and styles ignored
+fn visible_text(html: &str) -> String {
     let doc = Html::parse_document(html);
-    let lower = html.to_lowercase();
-
-    // Common login wall indicators
-    let login_patterns = [
-        "sign in to continue",
-        "log in to continue",
-        "login to continue",
-        "please sign in",
-        "please log in",
-        "create an account",
-        "sign up to view",
-        "register to view",
-        "authentication required",
-    ];
-
-    for pattern in login_patterns {
-        if lower.contains(pattern) {
-            return true;
-        }
-    }
+    let Ok(sel) = Selector::parse("body") else {
+        return String::new();
+    };
+    let Some(body) = doc.select(&sel).next() else {
+        return String::new();
+    };
+    body.descendants()
+        .filter_map(|node| {
+            let text = node.value().as_text()?;
+            let hidden = node.ancestors().any(|a| {
+                a.value().as_element().is_some_and(|el| {
+                    matches!(el.name(), "script" | "style" | "noscript" | "template")
+                })
+            });
+            (!hidden).then_some(text)
+        })
+        .flat_map(|text| text.split_whitespace())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
 
-    // Check for login-specific elements that block content
-    let login_selectors = [
-        "[class*='login-wall']",
-        "[class*='auth-wall']",
-        "[class*='signup-wall']",
-        "[id*='login-modal']",
-        "[class*='gate-content']",
-    ];
-
-    for sel_str in login_selectors {
-        if let Ok(sel) = Selector::parse(sel_str) {
-            if doc.select(&sel).next().is_some() {
-                return true;
-            }
-        }
-    }
+/// SHA-256 (hex) of the visible text, so markup-only changes don't count
+fn content_hash(html: &str) -> String {
+    Sha256::digest(visible_text(html).as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
 
-    false
+/// Store `hash`, returning whether it differs from a previously stored one
+fn update_content_hash(reference: &mut Reference, hash: String) -> bool {
+    let changed = reference
+        .content_hash
+        .as_ref()
+        .is_some_and(|old| *old != hash);
+    reference.content_hash = Some(hash);
+    changed
 }
 
-fn truncate(s: &str, max: usize) -> String {
-    if s.len() <= max {
-        s.to_string()
-    } else {
-        format!("{}...", &s[..max - 3])
-    }
+/// Add `note` after any existing notes
+fn append_note(notes: &mut Option<String>, note: &str) {
+    *notes = Some(match notes.take() {
+        Some(notes) => format!("{}; {}", notes, note),
+        None => note.to_string(),
+    });
+}
+
+/// Segment holds a not-found phrase plus at most two other words
+fn is_not_found_heading(segment: &str, phrases: &[String]) -> bool {
+    let words = segment.split_whitespace().count();
+    phrases
+        .iter()
+        .any(|p| segment.contains(p.as_str()) && words <= p.split_whitespace().count() + 2)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::schema::Meta;
+
+    fn reference(url: &str, category: &str, verified: Option<&str>) -> Reference {
+        Reference {
+            url: url.to_string(),
+            title: url.to_string(),
+            categories: vec![category.to_string()],
+            tags: vec![],
+            cited_in: vec!["README.md".to_string()],
+            status: Status::Ok,
+            verified: verified.map(|v| v.to_string()),
+            first_seen: None,
+            last_seen: None,
+            notes: None,
+            content_hash: None,
+        }
+    }
+
+    #[test]
+    fn test_upgrade_title() {
+        let html = r#"<html><head><meta property="og:title" content="Attention Is All You Need">
+            </head><body><p>Paper</p></body></html>"#;
+        let title = extract_title(&Html::parse_document(html));
+
+        let mut paper = reference("https://arxiv.org/abs/1706.03762", "research", None);
+        assert!(upgrade_title(&mut paper, title.clone()));
+        assert_eq!(paper.title, "Attention Is All You Need");
+
+        // Curated titles are never overwritten
+        let mut curated = paper.clone();
+        curated.title = "Transformer paper".to_string();
+        assert!(!upgrade_title(&mut curated, title));
+        assert_eq!(curated.title, "Transformer paper");
+
+        let mut untitled = reference("https://example.com", "docs", None);
+        assert!(!upgrade_title(&mut untitled, None));
+        assert_eq!(untitled.title, "https://example.com");
+    }
+
+    #[test]
+    fn test_select_references_max_age() {
+        let refs_file = ReferencesFile {
+            meta: Meta {
+                project: None,
+                created: "2025-01-01".to_string(),
+                last_verified: None,
+                tool: "ref".to_string(),
+                total_links: 4,
+            },
+            references: vec![
+                reference(
+                    "https://fresh.com",
+                    "research",
+                    Some("2025-06-09T00:00:00Z"),
+                ),
+                reference(
+                    "https://stale.com",
+                    "research",
+                    Some("2025-05-01T00:00:00Z"),
+                ),
+                reference("https://never.com", "research", None),
+                reference(
+                    "https://other.com",
+                    "docs",
+                    Some("2025-01-01T00:00:00+02:00"),
+                ),
+            ],
+        };
+        let now = DateTime::parse_from_rfc3339("2025-06-10T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+
+        assert_eq!(
+            select_references(&refs_file, None, None, None, None, now),
+            vec![0, 1, 2, 3]
+        );
+        assert_eq!(
+            select_references(&refs_file, None, None, None, Some(7), now),
+            vec![1, 2, 3]
+        );
+        assert_eq!(
+            select_references(&refs_file, None, None, None, Some(365), now),
+            vec![2]
+        );
+
+        let research = vec!["research".to_string()];
+        assert_eq!(
+            select_references(&refs_file, Some(&research), None, None, Some(7), now),
+            vec![1, 2]
+        );
+    }
+
+    #[test]
+    fn test_select_references_tags() {
+        let mut tagged = reference("https://keep.com", "research", None);
+        tagged.tags = vec!["must-keep".to_string()];
+        let refs_file = ReferencesFile {
+            meta: Meta {
+                project: None,
+                created: "2025-01-01".to_string(),
+                last_verified: None,
+                tool: "ref".to_string(),
+                total_links: 3,
+            },
+            references: vec![
+                tagged,
+                reference("https://untagged.com", "research", None),
+                reference("https://docs.com", "docs", None),
+            ],
+        };
+        let now = Utc::now();
+
+        let must_keep = vec!["must-keep".to_string()];
+        assert_eq!(
+            select_references(&refs_file, None, Some(&must_keep), None, None, now),
+            vec![0]
+        );
+        let docs = vec!["docs".to_string()];
+        assert!(
+            select_references(&refs_file, Some(&docs), Some(&must_keep), None, None, now)
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_run_bounded_limits_concurrency() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        // Stands in for BrowserPool::new_page: counts pages open at once
+        let open = AtomicUsize::new(0);
+        let peak = AtomicUsize::new(0);
+        let mut results = run_bounded((0..50).collect(), 4, |idx| {
+            let (open, peak) = (&open, &peak);
+            async move {
+                let now = open.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(std::time::Duration::from_millis(2)).await;
+                open.fetch_sub(1, Ordering::SeqCst);
+                idx
+            }
+        })
+        .await;
+
+        assert_eq!(peak.load(Ordering::SeqCst), 4);
+        results.sort_unstable();
+        assert_eq!(results, (0..50).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_select_references_status() {
+        let with_status = |url: &str, category: &str, status: Status| Reference {
+            status,
+            ..reference(url, category, None)
+        };
+        let refs_file = ReferencesFile {
+            meta: Meta {
+                project: None,
+                created: "2025-01-01".to_string(),
+                last_verified: None,
+                tool: "ref".to_string(),
+                total_links: 5,
+            },
+            references: vec![
+                with_status("https://ok.com", "research", Status::Ok),
+                with_status("https://dead.com", "research", Status::Dead),
+                with_status("https://moved.com", "research", Status::Redirect),
+                with_status("https://dead-docs.com", "docs", Status::Dead),
+                with_status("https://new.com", "research", Status::Pending),
+            ],
+        };
+        let now = Utc::now();
+
+        assert_eq!(
+            select_references(&refs_file, None, None, Some(&[Status::Dead]), None, now),
+            vec![1, 3]
+        );
+        assert_eq!(
+            select_references(
+                &refs_file,
+                None,
+                None,
+                Some(&[Status::Dead, Status::Redirect]),
+                None,
+                now
+            ),
+            vec![1, 2, 3]
+        );
+
+        // Combined with --category
+        let research = vec!["research".to_string()];
+        assert_eq!(
+            select_references(
+                &refs_file,
+                Some(&research),
+                None,
+                Some(&[Status::Dead]),
+                None,
+                now
+            ),
+            vec![1]
+        );
+    }
 
     #[test]
     fn test_is_paywall() {
-        assert!(is_paywall("<div>Subscribe to continue reading</div>"));
-        assert!(is_paywall("<div class='paywall-overlay'>content</div>"));
-        assert!(!is_paywall("<div>Normal content here</div>"));
+        let patterns = WallPatterns::builtin();
+        assert!(is_paywall(
+            "<div>Subscribe to continue reading</div>",
+            &patterns
+        ));
+        assert!(is_paywall(
+            "<div class='paywall-overlay'>content</div>",
+            &patterns
+        ));
+        assert!(!is_paywall("<div>Normal content here</div>", &patterns));
     }
 
     #[test]
     fn test_is_login_wall() {
-        assert!(is_login_wall("<div>Please sign in to continue</div>"));
-        assert!(is_login_wall("<div class='login-wall'>content</div>"));
-        assert!(!is_login_wall("<div>Normal content here</div>"));
+        let patterns = WallPatterns::builtin();
+        assert!(is_login_wall(
+            "<div>Please sign in to continue</div>",
+            &patterns
+        ));
+        assert!(is_login_wall(
+            "<div class='login-wall'>content</div>",
+            &patterns
+        ));
+        assert!(!is_login_wall("<div>Normal content here</div>", &patterns));
+    }
+
+    #[test]
+    fn test_is_soft_404() {
+        let patterns = WallPatterns::builtin();
+        let html = std::fs::read_to_string("test-data/soft-404.html").unwrap();
+        assert!(is_soft_404(&html, &patterns));
+        assert!(is_soft_404(
+            "<title>Page Not Found | Example</title><p>Sorry.</p>",
+            &patterns
+        ));
+        assert!(is_soft_404(
+            "<body class='error404'><h1>Nothing here</h1></body>",
+            &patterns
+        ));
+    }
+
+    #[test]
+    fn test_is_soft_404_incidental_phrase() {
+        let patterns = WallPatterns::builtin();
+        let html = std::fs::read_to_string("test-data/not-found-article.html").unwrap();
+        assert!(!is_soft_404(&html, &patterns));
+        assert!(!is_soft_404(
+            "<title>Docs</title><p>Returns page not found if the id is unknown.</p>",
+            &patterns
+        ));
+    }
+
+    #[test]
+    fn test_visible_text_len() {
+        let html = std::fs::read_to_string("test-data/js-challenge.html").unwrap();
+        assert_eq!(visible_text_len(&html), "Checking your browser.".len());
+        assert!(visible_text_len(&html) < 200);
+
+        let article = std::fs::read_to_string("test-data/not-found-article.html").unwrap();
+        assert!(visible_text_len(&article) > 200);
+        assert_eq!(visible_text_len("<p>  a \n b  </p>"), 3);
+    }
+
+    #[test]
+    fn test_content_hash_change() {
+        let before = "<html><body><h1>Pricing</h1><p>Pro plan: $10/month</p></body></html>";
+        let reformatted = "<html><body>\n<h1 class=\"x\">Pricing</h1>\n  <p>Pro plan:   $10/month</p><script>track()</script></body></html>";
+        let after = "<html><body><h1>Pricing</h1><p>Pro plan: $12/month</p></body></html>";
+
+        // Markup and whitespace don't count, text does
+        assert_eq!(content_hash(before), content_hash(reformatted));
+        assert_ne!(content_hash(before), content_hash(after));
+        assert_eq!(content_hash(before).len(), 64);
+
+        let mut reference = reference("https://example.com/pricing", "docs", None);
+        // First run only records the hash
+        assert!(!update_content_hash(&mut reference, content_hash(before)));
+        assert!(!update_content_hash(
+            &mut reference,
+            content_hash(reformatted)
+        ));
+
+        let mut notes = Some("redirected: 301 https://example.com/p".to_string());
+        if update_content_hash(&mut reference, content_hash(after)) {
+            append_note(&mut notes, "content changed");
+        }
+        assert_eq!(
+            notes.as_deref(),
+            Some("redirected: 301 https://example.com/p; content changed")
+        );
+        assert_eq!(reference.content_hash, Some(content_hash(after)));
+    }
+
+    #[tokio::test]
+    async fn test_custom_patterns() {
+        let html = std::fs::read_to_string("test-data/paywall-de.html").unwrap();
+        assert!(!is_paywall(&html, &WallPatterns::builtin()));
+
+        let patterns = WallPatterns::load(&PathBuf::from("test-data/patterns.yaml"))
+            .await
+            .unwrap();
+        assert!(is_paywall(&html, &patterns));
+        assert!(!is_login_wall(&html, &patterns));
+        assert!(is_login_wall("<div id='anmelde-dialog'></div>", &patterns));
+
+        assert!(is_soft_404("<h1>Seite nicht gefunden</h1>", &patterns));
+
+        // Built-ins still apply
+        assert!(is_paywall("<div>Subscribe to continue</div>", &patterns));
     }
 }
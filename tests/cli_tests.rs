@@ -73,6 +73,205 @@ fn test_update_help() {
         .stdout(predicate::str::contains("--force"));
 }
 
+#[test]
+fn test_prune_removes_uncited() {
+    let dir = tempdir().unwrap();
+    fs::write(
+        dir.path().join("notes.md"),
+        "See https://kept.com and https://removed.com",
+    )
+    .unwrap();
+    fs::write(dir.path().join("old.md"), "Old: https://old.com").unwrap();
+
+    ref_cmd()
+        .current_dir(dir.path())
+        .args(["scan", "notes.md", "old.md"])
+        .assert()
+        .success();
+
+    fs::write(dir.path().join("notes.md"), "See https://kept.com").unwrap();
+    fs::remove_file(dir.path().join("old.md")).unwrap();
+
+    ref_cmd()
+        .current_dir(dir.path())
+        .args(["prune", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"total\":1"))
+        .stdout(predicate::str::contains("\"missing_files\":[\"old.md\"]"));
+    let yaml = fs::read_to_string(dir.path().join("references.yaml")).unwrap();
+    assert!(yaml.contains("https://removed.com"));
+
+    ref_cmd()
+        .current_dir(dir.path())
+        .arg("prune")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("https://removed.com"))
+        .stdout(predicate::str::contains("https://old.com"));
+    let yaml = fs::read_to_string(dir.path().join("references.yaml")).unwrap();
+    assert!(yaml.contains("https://kept.com"));
+    assert!(!yaml.contains("https://removed.com"));
+    assert!(!yaml.contains("https://old.com"));
+}
+
+#[test]
+fn test_prune_from_other_directory() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("notes.md"), "See https://kept.com/page/").unwrap();
+    ref_cmd()
+        .current_dir(dir.path())
+        .args(["scan", "notes.md"])
+        .assert()
+        .success();
+
+    // Cited paths resolve next to references.yaml; a trailing slash or
+    // tracking parameter is still the same link
+    fs::write(
+        dir.path().join("notes.md"),
+        "See https://kept.com/page?utm_source=x",
+    )
+    .unwrap();
+    let elsewhere = tempdir().unwrap();
+    ref_cmd()
+        .current_dir(elsewhere.path())
+        .arg("prune")
+        .arg(dir.path().join("references.yaml"))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"pruned\":[]"))
+        .stdout(predicate::str::contains("\"total\":1"));
+}
+
+#[test]
+fn test_import_bibtex_merges() {
+    let dir = tempdir().unwrap();
+    fs::write(
+        dir.path().join("notes.md"),
+        "See https://arxiv.org/abs/1706.03762",
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("refs.bib"),
+        "@article{a, title = {Attention}, url = {https://arxiv.org/abs/1706.03762/}}\n\
+         @article{b, title = {BERT}, doi = {10.18653/v1/N19-1423}}\n\
+         @misc{c, title = {No link}}\n",
+    )
+    .unwrap();
+
+    ref_cmd()
+        .current_dir(dir.path())
+        .args(["scan", "notes.md"])
+        .assert()
+        .success();
+    ref_cmd()
+        .current_dir(dir.path())
+        .args(["import", "refs.bib"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"skipped\":1"))
+        .stdout(predicate::str::contains("\"new_urls\":1"))
+        .stdout(predicate::str::contains("\"total_urls\":2"));
+
+    let yaml = fs::read_to_string(dir.path().join("references.yaml")).unwrap();
+    assert!(yaml.contains("https://doi.org/10.18653/v1/N19-1423"));
+    assert!(yaml.contains("refs.bib"));
+    assert!(yaml.contains("notes.md"));
+
+    // The DOI-only entry never appears as a URL in the .bib but is still cited
+    ref_cmd()
+        .current_dir(dir.path())
+        .arg("prune")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"pruned\":[]"))
+        .stdout(predicate::str::contains("\"total\":2"));
+}
+
+#[test]
+fn test_scan_dry_run_keeps_file() {
+    let dir = tempdir().unwrap();
+    fs::write(
+        dir.path().join("notes.md"),
+        "See https://example.com/a and https://example.com/b",
+    )
+    .unwrap();
+    ref_cmd()
+        .current_dir(dir.path())
+        .args(["scan", "notes.md"])
+        .assert()
+        .success();
+    let before = fs::read_to_string(dir.path().join("references.yaml")).unwrap();
+
+    fs::write(
+        dir.path().join("notes.md"),
+        "See https://example.com/a, https://example.com/b and https://example.com/c",
+    )
+    .unwrap();
+    ref_cmd()
+        .current_dir(dir.path())
+        .args(["scan", "notes.md", "--dry-run"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"new_urls\":1"))
+        .stdout(predicate::str::contains(
+            "\"added\":[\"https://example.com/c\"]",
+        ))
+        .stdout(predicate::str::contains("\"dry_run\":true"));
+
+    let after = fs::read_to_string(dir.path().join("references.yaml")).unwrap();
+    assert_eq!(before, after);
+}
+
+#[test]
+fn test_scan_json_and_convert() {
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("notes.md"), "See https://example.com/a").unwrap();
+
+    ref_cmd()
+        .current_dir(dir.path())
+        .args(["scan", "notes.md", "--json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"file\":\"references.json\""));
+    let json: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(dir.path().join("references.json")).unwrap())
+            .unwrap();
+    assert_eq!(json["references"][0]["url"], "https://example.com/a");
+
+    // Other commands pick the format from the extension
+    ref_cmd()
+        .current_dir(dir.path())
+        .args(["export", "references.json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("https://example.com/a"));
+
+    ref_cmd()
+        .current_dir(dir.path())
+        .args(["convert", "references.json", "references.yaml"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"format\":\"yaml\""));
+    let yaml = fs::read_to_string(dir.path().join("references.yaml")).unwrap();
+    assert!(yaml.contains("url: https://example.com/a"));
+}
+
+#[test]
+fn test_schema_output() {
+    let dir = tempdir().unwrap();
+    let out = dir.path().join("references.schema.json");
+
+    ref_cmd()
+        .args(["schema", "--output", out.to_str().unwrap()])
+        .assert()
+        .success();
+
+    let schema: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&out).unwrap()).unwrap();
+    assert_eq!(schema["title"], "ReferencesFile");
+}
+
 #[test]
 fn test_check_links_no_args() {
     ref_cmd()
@@ -91,6 +290,405 @@ fn test_refresh_data_no_args() {
         .stderr(predicate::str::contains("Usage"));
 }
 
+#[test]
+fn test_fetch_stdin_html() {
+    let html = r#"<html><head><title>Offline</title></head><body><main>
+        <h1>Notes</h1><p>Parsed without a browser. See <a href="/docs">the docs</a>.</p>
+        </main></body></html>"#;
+    ref_cmd()
+        .args(["fetch", "--stdin-html", "--url", "https://example.com/a/b"])
+        .write_stdin(html)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            r#""url":"https://example.com/a/b""#,
+        ))
+        .stdout(predicate::str::contains(r#""title":"Offline""#))
+        .stdout(predicate::str::contains("https://example.com/docs"));
+}
+
+#[test]
+fn test_fetch_output_is_compact() {
+    let html = "<html><head><title>Compact</title></head><body><main>\n<h1>A</h1>\n<p>One.</p>\n<h2>B</h2>\n<p>Two.</p>\n</main></body></html>";
+    let output = ref_cmd()
+        .args(["fetch", "--stdin-html", "--url", "https://example.com/"])
+        .write_stdin(html)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    // One JSON object on one line, no indentation
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(stdout.lines().count(), 1);
+    assert!(!stdout.contains("  "));
+    let page: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(page["title"], "Compact");
+}
+
+#[test]
+fn test_debug_keeps_stdout() {
+    let html = "<html><head><title>Debug</title></head><body><p>Same JSON.</p></body></html>";
+    let fetch = |debug: bool| {
+        let mut cmd = ref_cmd();
+        cmd.args(["fetch", "--stdin-html", "--url", "https://example.com/"]);
+        if debug {
+            cmd.arg("--debug");
+        }
+        let output = cmd.write_stdin(html).output().unwrap();
+        assert!(output.status.success());
+        output.stdout
+    };
+    assert_eq!(fetch(true), fetch(false));
+}
+
+#[test]
+fn test_fetch_output_file() {
+    let dir = tempdir().unwrap();
+    let output = dir.path().join("page.json");
+
+    ref_cmd()
+        .args(["fetch", "--stdin-html", "--url", "https://example.com/a"])
+        .arg("--output")
+        .arg(&output)
+        .write_stdin("<html><head><title>Saved</title></head><body><p>Hi</p></body></html>")
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty());
+
+    let json: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+    assert_eq!(json["url"], "https://example.com/a");
+    assert_eq!(json["title"], "Saved");
+}
+
+#[test]
+fn test_fetch_output_dir() {
+    let dir = tempdir().unwrap();
+    let out_dir = dir.path().join("pages");
+
+    ref_cmd()
+        .args(["fetch", "--stdin-html", "--url", "https://example.com/a/b"])
+        .arg("--output-dir")
+        .arg(&out_dir)
+        .write_stdin("<html><head><title>Saved</title></head></html>")
+        .assert()
+        .success();
+
+    let content = fs::read_to_string(out_dir.join("example.com_a_b.json")).unwrap();
+    assert!(serde_json::from_str::<serde_json::Value>(&content).is_ok());
+}
+
+#[test]
+fn test_fetch_stdin_html_conflicts_with_urls() {
+    ref_cmd()
+        .args(["fetch", "--stdin-html", "https://example.com"])
+        .write_stdin("<html></html>")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+}
+
+#[test]
+fn test_fetch_jobs_file_errors_before_browser() {
+    let dir = tempdir().unwrap();
+    let jobs = dir.path().join("jobs.json");
+    fs::write(&jobs, r#"{"url": "https://example.com/"}"#).unwrap();
+    ref_cmd()
+        .args(["fetch", "--jobs"])
+        .arg(&jobs)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Failed to parse"));
+}
+
+#[test]
+fn test_check_links_summary_only() {
+    let output = ref_cmd()
+        .args([
+            "check-links",
+            "--url",
+            "http://localhost:9/health",
+            "--exclude",
+            "localhost",
+            "--summary-only",
+        ])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({"ok": 0, "failed": 0, "skipped": 1})
+    );
+}
+
+#[test]
+fn test_check_links_excluded_url_reported() {
+    // Everything excluded: no browser needed, URL still listed as skipped
+    ref_cmd()
+        .args([
+            "check-links",
+            "--url",
+            "http://localhost:9/health",
+            "--exclude",
+            "localhost",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""skipped":1"#))
+        .stdout(predicate::str::contains(
+            r#"{"url":"http://localhost:9/health","status":0,"skipped":true}"#,
+        ));
+}
+
+#[test]
+fn test_quiet_suppresses_progress() {
+    // Everything excluded: no browser needed, normally prints progress lines
+    let args = [
+        "check-links",
+        "--url",
+        "http://localhost:9/health",
+        "--exclude",
+        "localhost",
+    ];
+    ref_cmd()
+        .args(args)
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Done: 0/0 OK"));
+
+    ref_cmd()
+        .args(args)
+        .arg("--quiet")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""skipped":1"#))
+        .stderr(predicate::str::is_empty());
+}
+
+#[test]
+fn test_check_links_format_yaml() {
+    let output = ref_cmd()
+        .args([
+            "check-links",
+            "--url",
+            "http://localhost:9/health",
+            "--exclude",
+            "localhost",
+            "--format",
+            "yaml",
+        ])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let report: serde_yaml::Value = serde_yaml::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["skipped"], 1);
+    assert_eq!(report["results"][0]["url"], "http://localhost:9/health");
+
+    // Streamed lines are always JSON
+    ref_cmd()
+        .args(["check-links", "--url", "http://localhost:9/", "--jsonl"])
+        .args(["--format", "yaml"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+}
+
+#[test]
+fn test_refresh_data_format_yaml() {
+    let output = ref_cmd()
+        .args([
+            "refresh-data",
+            "--url",
+            "http://127.0.0.1:9/@nobody",
+            "--timeout",
+            "2000",
+            "--format",
+            "yaml",
+        ])
+        .timeout(std::time::Duration::from_secs(60))
+        .output()
+        .unwrap();
+    // Needs Chrome; skip on machines without one
+    if !output.status.success() {
+        assert!(String::from_utf8_lossy(&output.stderr).contains("Chrome"));
+        return;
+    }
+
+    let report: serde_yaml::Value = serde_yaml::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["ok"], 0);
+    assert_eq!(report["failed"], 1);
+}
+
+#[test]
+fn test_check_links_relative_base_dir() {
+    // Local-only links: no browser needed
+    let dir = tempdir().unwrap();
+    fs::write(dir.path().join("other.md"), "# Other\n").unwrap();
+    let index = dir.path().join("index.md");
+    fs::write(
+        &index,
+        "# Index\n\n[ok](./other.md) [broken](./missing.md) [top](#index)\n",
+    )
+    .unwrap();
+
+    ref_cmd()
+        .args(["check-links", "--base"])
+        .arg(dir.path())
+        .arg(&index)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""ok":2,"failed":1"#))
+        .stdout(predicate::str::contains(
+            r#"{"url":"./other.md","status":200}"#,
+        ))
+        .stdout(predicate::str::contains(
+            r#"{"url":"./missing.md","status":404,"error":"not found:"#,
+        ));
+}
+
+#[test]
+fn test_verify_refs_stream() {
+    let dir = tempdir().unwrap();
+    let refs = dir.path().join("references.yaml");
+    // Nothing listens on port 9 (discard) locally, so these are dead
+    fs::write(
+        &refs,
+        "meta:\n  created: 2025-01-01\n  tool: ref\n  total_links: 2\nreferences:\n\
+         - url: http://127.0.0.1:9/a\n  title: A\n  categories: []\n  cited_in: []\n  status: pending\n\
+         - url: http://127.0.0.1:9/b\n  title: B\n  categories: []\n  cited_in: []\n  status: pending\n",
+    )
+    .unwrap();
+
+    let output = ref_cmd()
+        .args([
+            "verify-refs",
+            "--stream",
+            "--retries",
+            "0",
+            "--timeout",
+            "2000",
+        ])
+        .arg(&refs)
+        .timeout(std::time::Duration::from_secs(60))
+        .output()
+        .unwrap();
+    // Needs Chrome; skip on machines without one
+    if !output.status.success() {
+        assert!(String::from_utf8_lossy(&output.stderr).contains("Chrome"));
+        return;
+    }
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let lines: Vec<serde_json::Value> = stdout
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(lines.len(), 3);
+    assert_eq!(lines.iter().filter(|l| l["url"].is_string()).count(), 2);
+    assert_eq!(lines[2]["summary"]["verified"], 2);
+}
+
+#[test]
+fn test_verify_refs_format_yaml() {
+    let dir = tempdir().unwrap();
+    let refs = dir.path().join("references.yaml");
+    fs::write(
+        &refs,
+        "meta:\n  created: 2025-01-01\n  tool: ref\n  total_links: 1\nreferences:\n\
+         - url: http://127.0.0.1:9/a\n  title: A\n  categories: []\n  cited_in: []\n  status: pending\n",
+    )
+    .unwrap();
+
+    let output = ref_cmd()
+        .args([
+            "verify-refs",
+            "--dry-run",
+            "--retries",
+            "0",
+            "--timeout",
+            "2000",
+            "--format",
+            "yaml",
+        ])
+        .arg(&refs)
+        .timeout(std::time::Duration::from_secs(60))
+        .output()
+        .unwrap();
+    // Needs Chrome; skip on machines without one
+    if !output.status.success() {
+        assert!(String::from_utf8_lossy(&output.stderr).contains("Chrome"));
+        return;
+    }
+
+    let report: serde_yaml::Value = serde_yaml::from_slice(&output.stdout).unwrap();
+    assert_eq!(report["summary"]["verified"], 1);
+    assert_eq!(report["summary"]["dead"], 1);
+}
+
+#[test]
+fn test_export_markdown() {
+    ref_cmd()
+        .args(["export", "test-data/references.yaml", "--format", "md"])
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(
+            "| url | title | status | categories | verified |\n",
+        ))
+        .stdout(predicate::str::contains(
+            "| https://example.org/old | https://example.org/old | redirect | general |  |",
+        ));
+}
+
+#[test]
+fn test_report_json() {
+    ref_cmd()
+        .args(["report", "test-data/references.yaml"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            r#""total":2,"healthy_percent":50.0"#,
+        ))
+        .stdout(predicate::str::contains(
+            r#""by_status":{"ok":1,"redirect":1}"#,
+        ));
+}
+
+#[test]
+fn test_init_template() {
+    let dir = tempdir().unwrap();
+    let output = dir.path().join("references.yaml");
+
+    ref_cmd()
+        .args(["init", "-o", output.to_str().unwrap()])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""file":"#))
+        .stdout(predicate::str::contains("seeded").not());
+
+    let content = fs::read_to_string(&output).unwrap();
+    assert!(content.contains("https://example.com"));
+    assert!(!content.contains("project:"));
+}
+
+#[test]
+fn test_init_interactive_without_tty() {
+    // Piped stdin: no prompts, same template as non-interactive
+    let dir = tempdir().unwrap();
+    let output = dir.path().join("references.yaml");
+
+    ref_cmd()
+        .args(["init", "--interactive", "-o", output.to_str().unwrap()])
+        .write_stdin("")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Not a terminal"));
+
+    let content = fs::read_to_string(&output).unwrap();
+    assert!(content.contains("https://example.com"));
+}
+
 #[test]
 fn test_check_links_file_not_found() {
     ref_cmd()
@@ -114,6 +712,7 @@ fn test_check_links_empty_file() {
 }
 
 #[test]
+#[allow(unused_must_use)] // outcome depends on Chrome being installed
 fn test_check_links_with_urls() {
     let dir = tempdir().unwrap();
     let file_path = dir.path().join("links.md");
@@ -128,6 +727,92 @@ fn test_check_links_with_urls() {
     // Don't assert success/failure as it depends on Chrome being installed
 }
 
+#[test]
+fn test_check_links_fail_on_error() {
+    let dir = tempdir().unwrap();
+    let file_path = dir.path().join("dead.md");
+    // Only a malformed mailto: link, checked without a browser
+    fs::write(&file_path, "Write to mailto:team@example").unwrap();
+    let file = file_path.to_str().unwrap();
+
+    ref_cmd()
+        .args(["check-links", "--schemes", "mailto", file])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"failed\":1"));
+
+    ref_cmd()
+        .args([
+            "check-links",
+            "--schemes",
+            "mailto",
+            "--fail-on-error",
+            file,
+        ])
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains("\"failed\":1"))
+        .stderr(predicate::str::contains("Failing: 1 link(s) failed"));
+
+    // Nothing failed: the flag alone doesn't fail the run
+    fs::write(&file_path, "Write to mailto:team@example.com").unwrap();
+    ref_cmd()
+        .args([
+            "check-links",
+            "--schemes",
+            "mailto",
+            "--fail-on-error",
+            file,
+        ])
+        .assert()
+        .success();
+}
+
+#[test]
+fn test_connect_validation() {
+    // Not a DevTools URL: rejected before any browser work
+    ref_cmd()
+        .args([
+            "fetch",
+            "--connect",
+            "localhost:9222",
+            "https://example.com",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "expected ws://... or http://host:port",
+        ));
+
+    // --socks5 only applies to a launched Chrome
+    ref_cmd()
+        .args([
+            "check-links",
+            "--connect",
+            "http://127.0.0.1:9222",
+            "--socks5",
+            "127.0.0.1:9050",
+            "test.md",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+
+    // Nothing listening: fails on connect instead of launching a local Chrome
+    let port = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    ref_cmd()
+        .env("REF_BROWSER_URL", format!("http://127.0.0.1:{}", port))
+        .args(["fetch", "https://example.com"])
+        .timeout(std::time::Duration::from_secs(30))
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Failed to connect to Chrome at"));
+}
+
 #[test]
 fn test_concurrency_validation() {
     ref_cmd()
@@ -140,3 +825,72 @@ fn test_concurrency_validation() {
         .assert()
         .failure();
 }
+
+#[test]
+fn test_pdf_ocr_flag_text_pdf() {
+    ref_cmd()
+        .args(["pdf", "--ocr", "test-data/sample.pdf"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Sample Research Paper"))
+        .stdout(predicate::str::contains("OCR").not());
+}
+
+#[test]
+fn test_progress_jsonl_events() {
+    let output = ref_cmd()
+        .args([
+            "pdf",
+            "test-data/sample.pdf",
+            "test-data/missing.pdf",
+            "--progress",
+            "jsonl",
+        ])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    // Every stderr line is an event; free-text progress is silenced
+    let events: Vec<serde_json::Value> = String::from_utf8(output.stderr)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(events.len(), 4);
+    assert_eq!(events[0], serde_json::json!({"event": "start", "total": 2}));
+    assert_eq!(events[1]["event"], "item");
+    assert_eq!(events[1]["done"], 1);
+    assert_eq!(events[2]["done"], 2);
+    assert_eq!(
+        events[3],
+        serde_json::json!({"event": "done", "ok": 1, "failed": 1})
+    );
+}
+
+#[test]
+fn test_pdf_invalid_page_range() {
+    ref_cmd()
+        .args(["pdf", "--pages", "5-2", "test-data/sample.pdf"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("start is after end"));
+}
+
+#[test]
+fn test_check_links_auth_not_echoed() {
+    // A malformed value is rejected without quoting it back
+    ref_cmd()
+        .args(["check-links", "--url", "https://example.com/", "--auth"])
+        .arg("wiki.corp.example=hunter2")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("invalid --auth"))
+        .stderr(predicate::str::contains("hunter2").not());
+
+    ref_cmd()
+        .args(["check-links", "--url", "https://example.com/"])
+        .env("REF_AUTH", "wiki.corp.example=hunter2")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("hunter2").not());
+}